@@ -3,10 +3,10 @@ use crate::{
     types::{Account, Principal, Subaccount, Timestamp, Ulid, Unit},
 };
 use candid::{CandidType, Principal as WrappedPrincipal};
-use canic::impl_storable_bounded;
+use canic::cdk::structures::storable::{Bound, Storable};
 use derive_more::Display;
 use serde::{Deserialize, Serialize};
-use std::cmp::Ordering;
+use std::{borrow::Cow, cmp::Ordering};
 
 ///
 /// Key
@@ -29,7 +29,15 @@ pub enum Key {
 }
 
 impl Key {
-    pub const STORABLE_MAX_SIZE: u32 = 128;
+    /// Leading byte of every encoded key. Keys written before the binary
+    /// encoding are CBOR and never start with it, so both can be read.
+    pub const ENCODING_VERSION: u8 = 1;
+
+    /// Version byte + variant tag + the largest payload (an `Account`).
+    pub const STORABLE_MAX_SIZE: u32 = 2 + Self::ACCOUNT_MAX_SIZE;
+
+    // principal length + principal + subaccount flag + subaccount
+    const ACCOUNT_MAX_SIZE: u32 = 1 + Principal::STORABLE_MAX_SIZE + 1 + 32;
 
     #[must_use]
     pub fn max_storable() -> Self {
@@ -58,6 +66,92 @@ impl Key {
             Self::Unit => 7,
         }
     }
+
+    /// Append the variant tag and payload. Byte order of the output matches
+    /// `Ord`: tags follow `variant_rank` and payloads are big-endian.
+    pub(crate) fn encode_into(&self, buf: &mut Vec<u8>) {
+        buf.push(self.variant_rank());
+
+        match self {
+            Self::Account(v) => {
+                encode_principal(&v.owner, buf);
+                match v.subaccount {
+                    None => buf.push(0),
+                    Some(sub) => {
+                        buf.push(1);
+                        buf.extend_from_slice(&sub.to_array());
+                    }
+                }
+            }
+            Self::Int(v) => buf.extend_from_slice(&(v.cast_unsigned() ^ (1 << 63)).to_be_bytes()),
+            Self::Principal(v) => encode_principal(v, buf),
+            Self::Subaccount(v) => buf.extend_from_slice(&v.to_array()),
+            Self::Timestamp(v) => buf.extend_from_slice(&v.get().to_be_bytes()),
+            Self::Uint(v) => buf.extend_from_slice(&v.to_be_bytes()),
+            Self::Ulid(v) => buf.extend_from_slice(&v.to_bytes()),
+            Self::Unit => {}
+        }
+    }
+
+    /// Read a key written by `encode_into`, which must fill all of `bytes`.
+    pub(crate) fn decode(bytes: &[u8]) -> Option<Self> {
+        let (tag, mut rest) = bytes.split_first()?;
+
+        let key = match tag {
+            0 => {
+                let owner = decode_principal(&mut rest)?;
+                let subaccount = match take(&mut rest, 1)? {
+                    [0] => None,
+                    [1] => Some(Subaccount::from_array(take_array(&mut rest)?)),
+                    _ => return None,
+                };
+                Self::Account(Account { owner, subaccount })
+            }
+            1 => Self::Int((u64::from_be_bytes(take_array(&mut rest)?) ^ (1 << 63)).cast_signed()),
+            2 => Self::Principal(decode_principal(&mut rest)?),
+            3 => Self::Subaccount(Subaccount::from_array(take_array(&mut rest)?)),
+            4 => Self::Timestamp(Timestamp::from(u64::from_be_bytes(take_array(&mut rest)?))),
+            5 => Self::Uint(u64::from_be_bytes(take_array(&mut rest)?)),
+            6 => Self::Ulid(Ulid::from_bytes(take_array(&mut rest)?)),
+            7 => Self::Unit,
+            _ => return None,
+        };
+
+        rest.is_empty().then_some(key)
+    }
+}
+
+// principals order by length first, so a length prefix keeps byte order
+fn encode_principal(principal: &Principal, buf: &mut Vec<u8>) {
+    let bytes = principal.as_slice();
+    #[allow(clippy::cast_possible_truncation)]
+    buf.push(bytes.len() as u8);
+    buf.extend_from_slice(bytes);
+}
+
+fn decode_principal(bytes: &mut &[u8]) -> Option<Principal> {
+    let len = *take(bytes, 1)?.first()? as usize;
+    if len > Principal::STORABLE_MAX_SIZE as usize {
+        return None;
+    }
+
+    Some(Principal::from_slice(take(bytes, len)?))
+}
+
+// take
+const fn take<'a>(bytes: &mut &'a [u8], len: usize) -> Option<&'a [u8]> {
+    if bytes.len() < len {
+        return None;
+    }
+    let (head, tail) = bytes.split_at(len);
+    *bytes = tail;
+
+    Some(head)
+}
+
+// take_array
+fn take_array<const N: usize>(bytes: &mut &[u8]) -> Option<[u8; N]> {
+    take(bytes, N)?.try_into().ok()
 }
 
 impl FieldValue for Key {
@@ -180,7 +274,33 @@ impl PartialOrd for Key {
     }
 }
 
-impl_storable_bounded!(Key, Self::STORABLE_MAX_SIZE, false);
+impl Storable for Key {
+    const BOUND: Bound = Bound::Bounded {
+        max_size: Self::STORABLE_MAX_SIZE,
+        is_fixed_size: false,
+    };
+
+    fn to_bytes(&self) -> Cow<'_, [u8]> {
+        Cow::Owned(self.into_bytes())
+    }
+
+    fn into_bytes(self) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(Self::STORABLE_MAX_SIZE as usize);
+        buf.push(Self::ENCODING_VERSION);
+        self.encode_into(&mut buf);
+
+        buf
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        match bytes.split_first() {
+            Some((&Self::ENCODING_VERSION, rest)) => {
+                Self::decode(rest).expect("invalid Key encoding")
+            }
+            _ => canic::serialize::deserialize(&bytes).expect("invalid legacy Key encoding"),
+        }
+    }
+}
 
 ///
 /// TESTS
@@ -202,4 +322,73 @@ mod tests {
             Key::STORABLE_MAX_SIZE
         );
     }
+
+    fn sample_keys() -> Vec<Key> {
+        vec![
+            Key::Account(Account::new(Principal::dummy(1), None::<Subaccount>)),
+            Key::Account(Account::new(
+                Principal::dummy(1),
+                Some(Subaccount::dummy(0)),
+            )),
+            Key::Account(Account::new(
+                Principal::dummy(1),
+                Some(Subaccount::dummy(9)),
+            )),
+            Key::Account(Account::new(Principal::dummy(2), None::<Subaccount>)),
+            Key::max_storable(),
+            Key::Int(i64::MIN),
+            Key::Int(-1),
+            Key::Int(0),
+            Key::Int(1),
+            Key::Int(i64::MAX),
+            Key::Principal(Principal::from_slice(&[])),
+            Key::Principal(Principal::from_slice(&[0xFF])),
+            Key::Principal(Principal::from_slice(&[0x00, 0x00])),
+            Key::Principal(Principal::MAX),
+            Key::Subaccount(Subaccount::dummy(0)),
+            Key::Subaccount(Subaccount::dummy(1)),
+            Key::Timestamp(Timestamp::from(0)),
+            Key::Timestamp(Timestamp::from(u64::MAX)),
+            Key::Uint(0),
+            Key::Uint(256),
+            Key::Uint(u64::MAX),
+            Key::Ulid(Ulid::nil()),
+            Key::Ulid(Ulid::from_u128(1 << 80)),
+            Key::Ulid(Ulid::max_storable()),
+            Key::Unit,
+        ]
+    }
+
+    #[test]
+    fn key_round_trips() {
+        for key in sample_keys() {
+            let bytes = Storable::to_bytes(&key);
+
+            assert_eq!(Key::from_bytes(bytes), key);
+        }
+    }
+
+    #[test]
+    fn key_bytes_follow_key_order() {
+        let keys = sample_keys();
+
+        for a in &keys {
+            for b in &keys {
+                assert_eq!(
+                    Storable::to_bytes(a).cmp(&Storable::to_bytes(b)),
+                    a.cmp(b),
+                    "byte order differs from Ord for {a:?} and {b:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn legacy_keys_still_decode() {
+        for key in sample_keys() {
+            let legacy = canic::serialize::serialize(&key).unwrap();
+
+            assert_eq!(Key::from_bytes(Cow::Owned(legacy)), key);
+        }
+    }
 }
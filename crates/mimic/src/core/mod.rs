@@ -8,7 +8,7 @@ pub mod visit;
 
 pub use key::Key;
 pub use serialize::{SerializeError, deserialize, serialize};
-pub use value::Value;
+pub use value::{Value, ValueError};
 pub use visit::{ValidateError, sanitize, validate};
 
 use thiserror::Error as ThisError;
@@ -24,4 +24,7 @@ pub enum CoreError {
 
     #[error("{0}")]
     SerializeError(#[from] SerializeError),
+
+    #[error("{0}")]
+    ValueError(#[from] ValueError),
 }
@@ -0,0 +1,58 @@
+use crate::{Error, core::traits::EntityKind, db::DbSession};
+
+///
+/// EntityHooks
+///
+/// Lifecycle hooks run by the save and delete executors.
+/// Every entity gets an empty impl by default; remove the generated one with
+/// `traits(remove(EntityHooks))` and implement the methods you need.
+///
+/// `before_*` hooks run before anything is written and veto the operation by
+/// returning an error. `after_*` hooks run once the row and its indexes have
+/// been written, so writes made through `db` are part of the same logical
+/// operation. An error from an `after_*` hook is returned to the caller but
+/// does not undo the write; trap if the whole message must be rolled back.
+///
+
+pub trait EntityHooks: Sized {
+    /// Called before an insert, update or replace, with the stored row if any.
+    /// Changes made to `self` are sanitized and validated before saving.
+    fn before_save(
+        &mut self,
+        _db: &DbSession<<Self as EntityKind>::Canister>,
+        _old: Option<&Self>,
+    ) -> Result<(), Error>
+    where
+        Self: EntityKind,
+    {
+        Ok(())
+    }
+
+    /// Called after the row has been saved, with the row it replaced if any.
+    fn after_save(
+        &self,
+        _db: &DbSession<<Self as EntityKind>::Canister>,
+        _old: Option<&Self>,
+    ) -> Result<(), Error>
+    where
+        Self: EntityKind,
+    {
+        Ok(())
+    }
+
+    /// Called before the row is deleted.
+    fn before_delete(&self, _db: &DbSession<<Self as EntityKind>::Canister>) -> Result<(), Error>
+    where
+        Self: EntityKind,
+    {
+        Ok(())
+    }
+
+    /// Called after the row and its index entries have been removed.
+    fn after_delete(&self, _db: &DbSession<<Self as EntityKind>::Canister>) -> Result<(), Error>
+    where
+        Self: EntityKind,
+    {
+        Ok(())
+    }
+}
@@ -1,10 +1,12 @@
 #[macro_use]
 mod macros;
+mod hooks;
 mod sanitize;
 mod validate;
 mod view;
 mod visitable;
 
+pub use hooks::*;
 pub use sanitize::*;
 pub use validate::*;
 pub use view::*;
@@ -27,13 +29,15 @@ pub use std::{
 };
 
 use crate::{
-    core::{Key, Value},
+    core::{Key, Value, value::ValueFamily},
     db::primitives::{
         BoolEqualityFilterKind, BoolListFilterKind, FilterKind, Int64RangeFilterKind,
         IntListFilterKind, Nat64RangeFilterKind, NatListFilterKind, TextFilterKind,
         TextListFilterKind,
     },
-    schema::node::Index,
+    db::store::{RowCodec, RowMigration},
+    schema::node::{Field, Index},
+    types::Timestamp,
 };
 
 /// ------------------------
@@ -59,7 +63,7 @@ pub trait CanisterKind: Kind {}
 /// EntityKind
 ///
 
-pub trait EntityKind: Kind + TypeKind + FieldValues {
+pub trait EntityKind: Kind + TypeKind + FieldValues + EntityHooks {
     type PrimaryKey: Copy + Into<Key>;
     type Store: StoreKind;
     type Canister: CanisterKind; // Self::Store::Canister shortcut
@@ -68,9 +72,43 @@ pub trait EntityKind: Kind + TypeKind + FieldValues {
     const PRIMARY_KEY: &'static str;
     const FIELDS: &'static [&'static str];
     const INDEXES: &'static [&'static Index];
+    const RELATIONS: &'static [&'static Field];
+    const SOFT_DELETE: bool;
+    const TTL_FIELD: Option<&'static str>;
+    const HISTORY_LIMIT: Option<u32>;
+
+    /// Version written into every stored row, see [`crate::db::store::encode_row`].
+    const SCHEMA_VERSION: u32;
+
+    /// Upgrades from each earlier version, `MIGRATIONS[0]` takes a row from version 1 to 2.
+    const MIGRATIONS: &'static [RowMigration];
+
+    /// How rows are serialized and whether they're compressed.
+    const CODEC: RowCodec;
+
+    /// Fields removed or renamed since compact rows were written, whose names
+    /// older rows are decoded with.
+    const RETIRED_FIELDS: &'static [&'static str];
 
     fn key(&self) -> Key;
     fn primary_key(&self) -> Self::PrimaryKey;
+
+    /// When the row was soft deleted; always `None` unless `SOFT_DELETE` is set.
+    fn deleted_at(&self) -> Option<Timestamp> {
+        None
+    }
+
+    fn set_deleted_at(&mut self, _deleted_at: Option<Timestamp>) {}
+
+    /// When the row expires; always `None` unless `TTL_FIELD` is set.
+    fn expires_at(&self) -> Option<Timestamp> {
+        None
+    }
+
+    fn is_expired(&self, now: Timestamp) -> bool {
+        self.expires_at()
+            .is_some_and(|expires_at| expires_at <= now)
+    }
 }
 
 ///
@@ -127,67 +165,153 @@ impl<T> TypeKind for T where
 ///
 
 pub trait FieldValues {
+    /// The value of a field, or of a dotted path into one (`stats.level`)
     fn get_value(&self, field: &str) -> Option<Value>;
+
+    /// The family of the value at `field`, or a dotted path into it, taken
+    /// from the field's type; `None` if the path doesn't resolve
+    fn path_family(field: &str) -> Option<ValueFamily>
+    where
+        Self: Sized;
+
+    /// Whether `field` names a field, or a dotted path that resolves into one
+    #[must_use]
+    fn has_path(field: &str) -> bool
+    where
+        Self: Sized,
+    {
+        Self::path_family(field).is_some()
+    }
 }
 
 ///
 /// FieldValue
 ///
+/// `get_path` and `path_family` resolve the segments of a dotted path below
+/// the value; an empty path is the value itself. `family` is the type's
+/// `ValueFamily`, known without a value.
+///
 
 pub trait FieldValue {
     fn to_value(&self) -> Value {
         Value::Unsupported
     }
+
+    fn get_path(&self, path: &[&str]) -> Option<Value> {
+        path.is_empty().then(|| self.to_value())
+    }
+
+    #[must_use]
+    fn family() -> ValueFamily
+    where
+        Self: Sized,
+    {
+        ValueFamily::Unsupported
+    }
+
+    #[must_use]
+    fn path_family(path: &[&str]) -> Option<ValueFamily>
+    where
+        Self: Sized,
+    {
+        path.is_empty().then(Self::family)
+    }
+
+    #[must_use]
+    fn has_path(path: &[&str]) -> bool
+    where
+        Self: Sized,
+    {
+        Self::path_family(path).is_some()
+    }
+}
+
+impl<T: FieldValue> FieldValue for Box<T> {
+    fn to_value(&self) -> Value {
+        (**self).to_value()
+    }
+
+    fn get_path(&self, path: &[&str]) -> Option<Value> {
+        (**self).get_path(path)
+    }
+
+    fn path_family(path: &[&str]) -> Option<ValueFamily> {
+        T::path_family(path)
+    }
 }
 
 impl FieldValue for &str {
     fn to_value(&self) -> Value {
         Value::Text((*self).to_string())
     }
+
+    fn family() -> ValueFamily {
+        ValueFamily::Textual
+    }
 }
 
 impl FieldValue for String {
     fn to_value(&self) -> Value {
         Value::Text(self.clone())
     }
+
+    fn family() -> ValueFamily {
+        ValueFamily::Textual
+    }
 }
 
 impl<T: FieldValue + Clone> FieldValue for &T {
     fn to_value(&self) -> Value {
         (*self).clone().to_value()
     }
+
+    fn get_path(&self, path: &[&str]) -> Option<Value> {
+        (*self).get_path(path)
+    }
+
+    fn path_family(path: &[&str]) -> Option<ValueFamily> {
+        T::path_family(path)
+    }
 }
 
 impl FieldValue for Vec<Value> {
     fn to_value(&self) -> Value {
         Value::List(self.clone())
     }
+
+    fn family() -> ValueFamily {
+        ValueFamily::Collection
+    }
 }
 
 // impl_field_value
 #[macro_export]
 macro_rules! impl_field_value {
-    ( $( $type:ty => $variant:ident ),* $(,)? ) => {
+    ( $( $type:ty => $variant:ident, $family:ident ),* $(,)? ) => {
         $(
             impl FieldValue for $type {
                 fn to_value(&self) -> Value {
                     Value::$variant((*self).into())
                 }
+
+                fn family() -> ValueFamily {
+                    ValueFamily::$family
+                }
             }
         )*
     };
 }
 
 impl_field_value!(
-    i8 => Int,
-    i16 => Int,
-    i32 => Int,
-    i64 => Int,
-    u8 => Uint,
-    u16 => Uint,
-    u32 => Uint,
-    u64 => Uint,
-    bool => Bool,
+    i8 => Int, Numeric,
+    i16 => Int, Numeric,
+    i32 => Int, Numeric,
+    i64 => Int, Numeric,
+    u8 => Uint, Numeric,
+    u16 => Uint, Numeric,
+    u32 => Uint, Numeric,
+    u64 => Uint, Numeric,
+    bool => Bool, Bool,
 );
 
 ///
@@ -61,6 +61,20 @@ impl ValueFamily {
         // scalar = not collection, not unit
         !self.is_collection() && !matches!(self, Self::Unit)
     }
+
+    /// Whether values of the two families can be compared with each other.
+    /// `Null` is compatible with every other comparable family, as an unset optional field is.
+    #[must_use]
+    pub const fn is_comparable_with(self, other: Self) -> bool {
+        match (self, other) {
+            (Self::Unit | Self::Unsupported, _) | (_, Self::Unit | Self::Unsupported) => false,
+            (Self::Null, _)
+            | (_, Self::Null)
+            | (Self::Identifier, Self::Textual)
+            | (Self::Textual, Self::Identifier) => true,
+            (a, b) => a as u8 == b as u8,
+        }
+    }
 }
 
 ///
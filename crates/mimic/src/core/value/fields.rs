@@ -0,0 +1,216 @@
+//! Untyped field access: an entity's fields as `Value`s, and `Value`s written
+//! back by splicing them into the CBOR body of a serde type, which is then
+//! decoded again so every value goes through the type's own `Deserialize`.
+
+use crate::{
+    Error,
+    core::{CoreError, deserialize, serialize, traits::EntityKind, value::Value},
+};
+use minicbor::{Decoder, Encoder, decode};
+use serde::{Serialize, Serializer, de::DeserializeOwned, ser::Error as _};
+use std::collections::BTreeMap;
+use thiserror::Error as ThisError;
+
+///
+/// ValueError
+///
+
+#[derive(Debug, ThisError)]
+pub enum ValueError {
+    #[error("unknown field '{0}'")]
+    UnknownField(String),
+
+    #[error("invalid row: {0}")]
+    InvalidRow(String),
+}
+
+impl From<ValueError> for Error {
+    fn from(err: ValueError) -> Self {
+        CoreError::from(err).into()
+    }
+}
+
+impl From<decode::Error> for ValueError {
+    fn from(err: decode::Error) -> Self {
+        Self::InvalidRow(err.to_string())
+    }
+}
+
+/// Every field of `entity`, fields that can't be turned into a `Value` are
+/// `Value::Unsupported`.
+#[must_use]
+pub fn entity_values<E: EntityKind>(entity: &E) -> BTreeMap<String, Value> {
+    E::FIELDS
+        .iter()
+        .map(|field| {
+            let value = entity.get_value(field).unwrap_or(Value::Unsupported);
+
+            ((*field).to_string(), value)
+        })
+        .collect()
+}
+
+/// The field names `ty` serializes, in order.
+pub fn field_names<T: Serialize>(ty: &T) -> Result<Vec<String>, Error> {
+    let body = serialize(ty)?;
+    let mut d = Decoder::new(&body);
+    let len = map_len(&mut d)?;
+
+    let mut names = Vec::new();
+    for _ in 0..len {
+        names.push(d.str().map_err(ValueError::from)?.to_string());
+        d.skip().map_err(ValueError::from)?;
+    }
+
+    Ok(names)
+}
+
+/// A copy of `base` with each field in `values` replaced.
+pub fn apply_values<T>(base: &T, values: &BTreeMap<String, Value>) -> Result<T, Error>
+where
+    T: Serialize + DeserializeOwned,
+{
+    let body = serialize(base)?;
+    let mut d = Decoder::new(&body);
+    let len = map_len(&mut d)?;
+    let mut e = Encoder::new(Vec::with_capacity(body.len()));
+    e.map(len).expect("writing to a Vec can't fail");
+
+    let mut names = Vec::new();
+    for _ in 0..len {
+        let start = d.position();
+        let name = d.str().map_err(ValueError::from)?;
+        e.writer_mut().extend_from_slice(&body[start..d.position()]);
+
+        let start = d.position();
+        d.skip().map_err(ValueError::from)?;
+        match values.get(name) {
+            Some(value) => e.writer_mut().extend(serialize(&ValuePayload(value))?),
+            None => e.writer_mut().extend_from_slice(&body[start..d.position()]),
+        }
+        names.push(name);
+    }
+
+    if let Some(unknown) = values.keys().find(|k| !names.contains(&k.as_str())) {
+        return Err(ValueError::UnknownField(unknown.clone()).into());
+    }
+
+    deserialize(&e.into_writer())
+}
+
+// map_len
+fn map_len(d: &mut Decoder) -> Result<u64, ValueError> {
+    d.map()?
+        .ok_or_else(|| ValueError::InvalidRow("not a definite-length map".to_string()))
+}
+
+///
+/// ValuePayload
+/// serializes what a `Value` holds, the way a field of that type would
+///
+
+struct ValuePayload<'a>(&'a Value);
+
+impl Serialize for ValuePayload<'_> {
+    fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        match self.0 {
+            Value::Account(v) => v.serialize(s),
+            Value::Blob(v) => s.serialize_bytes(v),
+            Value::Bool(v) => v.serialize(s),
+            Value::Date(v) => v.serialize(s),
+            Value::Decimal(v) => v.serialize(s),
+            Value::Duration(v) => v.serialize(s),
+            Value::Enum(v) => s.serialize_str(&v.variant),
+            Value::E8s(v) => v.serialize(s),
+            Value::E18s(v) => v.serialize(s),
+            Value::Float32(v) => v.serialize(s),
+            Value::Float64(v) => v.serialize(s),
+            Value::Int(v) => v.serialize(s),
+            Value::Int128(v) => v.serialize(s),
+            Value::IntBig(v) => v.serialize(s),
+            Value::List(items) => s.collect_seq(items.iter().map(ValuePayload)),
+            Value::None => s.serialize_none(),
+            Value::Principal(v) => v.serialize(s),
+            Value::Subaccount(v) => v.serialize(s),
+            Value::Text(v) => v.serialize(s),
+            Value::Timestamp(v) => v.serialize(s),
+            Value::Uint(v) => v.serialize(s),
+            Value::Uint128(v) => v.serialize(s),
+            Value::UintBig(v) => v.serialize(s),
+            Value::Ulid(v) => v.serialize(s),
+            Value::Unit => s.serialize_unit(),
+            Value::Unsupported => Err(S::Error::custom("unsupported value")),
+        }
+    }
+}
+
+///
+/// TESTS
+///
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Debug, Default, Deserialize, PartialEq, Serialize)]
+    struct Row {
+        name: String,
+        score: u8,
+        tags: Vec<String>,
+        nick: Option<String>,
+    }
+
+    fn values(pairs: &[(&str, Value)]) -> BTreeMap<String, Value> {
+        pairs
+            .iter()
+            .map(|(k, v)| ((*k).to_string(), v.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn replaces_only_given_fields() {
+        let base = Row {
+            name: "ada".to_string(),
+            score: 7,
+            ..Default::default()
+        };
+        let row = apply_values(
+            &base,
+            &values(&[
+                ("score", Value::Uint(9)),
+                ("tags", Value::from_list(&["a", "b"])),
+                ("nick", Value::Text("lovelace".to_string())),
+            ]),
+        )
+        .unwrap();
+
+        assert_eq!(
+            row,
+            Row {
+                name: "ada".to_string(),
+                score: 9,
+                tags: vec!["a".to_string(), "b".to_string()],
+                nick: Some("lovelace".to_string()),
+            }
+        );
+        assert_eq!(
+            field_names(&row).unwrap(),
+            ["name", "score", "tags", "nick"]
+        );
+    }
+
+    #[test]
+    fn wrong_values_fail() {
+        let base = Row::default();
+
+        for (field, value) in [
+            ("missing", Value::Uint(1)),
+            ("score", Value::Uint(300)),
+            ("score", Value::Text("high".to_string())),
+            ("name", Value::Unsupported),
+        ] {
+            assert!(apply_values(&base, &values(&[(field, value)])).is_err());
+        }
+    }
+}
@@ -1,5 +1,7 @@
 mod bytes;
 mod family;
+mod fields;
+mod path;
 mod tests;
 
 use crate::{
@@ -15,6 +17,11 @@ use serde::{Deserialize, Serialize};
 use std::cmp::Ordering;
 
 pub use family::{ValueFamily, ValueFamilyExt};
+pub use fields::{ValueError, apply_values, entity_values, field_names};
+pub use path::{
+    get_list_path, get_map_path, get_opt_path, list_path_family, map_path_family, path_index,
+    split_path,
+};
 
 ///
 /// CONSTANTS
@@ -81,7 +88,7 @@ pub enum Value {
     Int(i64),
     Int128(Int128),
     IntBig(Int),
-    List(Vec<Value>),
+    List(Vec<Self>),
     None,
     Principal(Principal),
     Subaccount(Subaccount),
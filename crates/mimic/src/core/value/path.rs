@@ -0,0 +1,151 @@
+//! Dotted field paths (`stats.level`, `tags.0`, `labels.en`), and the helpers
+//! the generated `FieldValue` impls use to follow them into lists and maps.
+//! A list index or map key that isn't there resolves to `Value::None`.
+
+use crate::core::{
+    traits::FieldValue,
+    value::{Value, ValueFamily},
+};
+
+/// The segments of a dotted path.
+#[must_use]
+pub fn split_path(path: &str) -> Vec<&str> {
+    path.split('.').collect()
+}
+
+/// An index segment.
+#[must_use]
+pub fn path_index(segment: &str) -> Option<usize> {
+    segment
+        .bytes()
+        .all(|b| b.is_ascii_digit())
+        .then(|| segment.parse().ok())
+        .flatten()
+}
+
+/// Follows `path` into an optional value; a missing value is `Value::None`.
+#[must_use]
+pub fn get_opt_path<T: FieldValue>(value: Option<&T>, path: &[&str]) -> Option<Value> {
+    match value {
+        Some(value) => value.get_path(path),
+        None => Some(Value::None),
+    }
+}
+
+/// Follows `path` into the items of a list or set, its first segment is an index.
+pub fn get_list_path<'a, T, I>(items: I, path: &[&str]) -> Option<Value>
+where
+    T: FieldValue + 'a,
+    I: IntoIterator<Item = &'a T>,
+{
+    let mut items = items.into_iter();
+
+    match path.split_first() {
+        None => Some(Value::List(items.map(FieldValue::to_value).collect())),
+        Some((index, rest)) => match items.nth(path_index(index)?) {
+            Some(item) => item.get_path(rest),
+            None => Some(Value::None),
+        },
+    }
+}
+
+/// Follows `path` into the entries of a map, its first segment is a key.
+pub fn get_map_path<'a, K, V, I>(entries: I, path: &[&str]) -> Option<Value>
+where
+    K: FieldValue + 'a,
+    V: FieldValue + 'a,
+    I: IntoIterator<Item = (&'a K, &'a V)>,
+{
+    let Some((key, rest)) = path.split_first() else {
+        return Some(Value::Unsupported);
+    };
+
+    match entries
+        .into_iter()
+        .find(|(k, _)| key_matches(&k.to_value(), key))
+    {
+        Some((_, value)) => value.get_path(rest),
+        None => Some(Value::None),
+    }
+}
+
+/// The family at `path` into the items of a list or set of `T`.
+#[must_use]
+pub fn list_path_family<T: FieldValue>(path: &[&str]) -> Option<ValueFamily> {
+    match path.split_first() {
+        None => Some(ValueFamily::Collection),
+        Some((index, rest)) => path_index(index).and_then(|_| T::path_family(rest)),
+    }
+}
+
+/// The family at `path` into the values of a map of `V`.
+#[must_use]
+pub fn map_path_family<V: FieldValue>(path: &[&str]) -> Option<ValueFamily> {
+    match path.split_first() {
+        None => Some(ValueFamily::Unsupported),
+        Some((_, rest)) => V::path_family(rest),
+    }
+}
+
+// key_matches
+// a map key as written in a path
+fn key_matches(key: &Value, segment: &str) -> bool {
+    match key {
+        Value::Text(s) => s == segment,
+        Value::Enum(e) => e.variant == segment,
+        Value::Int(n) => segment.parse() == Ok(*n),
+        Value::Uint(n) => segment.parse() == Ok(*n),
+        Value::Principal(p) => p.to_string() == segment,
+        Value::Ulid(u) => u.to_string() == segment,
+        _ => false,
+    }
+}
+
+///
+/// TESTS
+///
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn follows_lists_and_maps() {
+        let items = vec![1u8, 2, 3];
+        let get = |path: &str| get_list_path(&items, &split_path(path));
+
+        assert_eq!(get("1"), Some(Value::Uint(2)));
+        assert_eq!(get("5"), Some(Value::None));
+        assert_eq!(get("x"), None);
+        assert_eq!(get("1.0"), None);
+        assert_eq!(
+            get_list_path(&items, &[]),
+            Some(Value::List(vec![
+                Value::Uint(1),
+                Value::Uint(2),
+                Value::Uint(3)
+            ]))
+        );
+
+        let labels = BTreeMap::from([("en".to_string(), 1u32), ("fr".to_string(), 2)]);
+        let get = |path: &str| get_map_path(&labels, &split_path(path));
+        assert_eq!(get("fr"), Some(Value::Uint(2)));
+        assert_eq!(get("de"), Some(Value::None));
+        assert_eq!(get("fr.x"), None);
+    }
+
+    #[test]
+    fn checks_paths_by_type() {
+        let list = |path: &str| list_path_family::<u8>(&split_path(path));
+        assert_eq!(list_path_family::<u8>(&[]), Some(ValueFamily::Collection));
+        assert_eq!(list("1"), Some(ValueFamily::Numeric));
+        assert_eq!(list("1.0"), None);
+        assert_eq!(list("first"), None);
+        assert_eq!(list("+1"), None);
+
+        let map = |path: &str| map_path_family::<String>(&split_path(path));
+        assert_eq!(map("anything"), Some(ValueFamily::Textual));
+        assert_eq!(map("anything.more"), None);
+    }
+}
@@ -1,16 +1,24 @@
 use crate::{
     Error,
     core::{
-        Key, deserialize,
+        Key, Value,
+        hash::fnv1a_64,
         traits::{EntityKind, Path},
     },
     db::{
         Db,
-        query::QueryPlan,
-        store::{DataKey, DataRow, DataStore},
+        primitives::{BudgetMeter, ResumeToken},
+        query::{QueryError, QueryPlan},
+        response::{Related, RelatedRows},
+        store::{DataKey, DataRow, DataStore, decode_row},
     },
+    types::Timestamp,
+};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    marker::PhantomData,
+    ops::Bound,
 };
-use std::{marker::PhantomData, ops::Bound};
 
 ///
 /// Context
@@ -54,8 +62,7 @@ where
                 let start = Self::to_data_key(start);
                 let end = Self::to_data_key(end);
 
-                s.range((Bound::Included(start), Bound::Included(end)))
-                    .map(|e| e.key().clone())
+                s.range_keys((Bound::Included(start), Bound::Included(end)))
                     .collect()
             })?,
 
@@ -63,8 +70,7 @@ where
                 let start = DataKey::lower_bound::<E>();
                 let end = DataKey::upper_bound::<E>();
 
-                s.range((Bound::Included(start), Bound::Included(end)))
-                    .map(|entry| entry.key().clone())
+                s.range_keys((Bound::Included(start), Bound::Included(end)))
                     .collect()
             })?,
 
@@ -82,6 +88,27 @@ where
         Ok(candidates)
     }
 
+    /// Number of keys `plan` reads, without loading any rows.
+    pub fn count_from_plan(&self, plan: QueryPlan) -> Result<usize, Error> {
+        match plan {
+            QueryPlan::Range(start, end) => self.with_store(|s| {
+                let start = Self::to_data_key(start);
+                let end = Self::to_data_key(end);
+
+                s.range_keys((Bound::Included(start), Bound::Included(end)))
+                    .count()
+            }),
+            QueryPlan::FullScan => self.with_store(|s| {
+                let start = DataKey::lower_bound::<E>();
+                let end = DataKey::upper_bound::<E>();
+
+                s.range_keys((Bound::Included(start), Bound::Included(end)))
+                    .count()
+            }),
+            QueryPlan::Keys(_) | QueryPlan::Index(_) => Ok(self.candidates_from_plan(plan)?.len()),
+        }
+    }
+
     pub fn rows_from_plan(&self, plan: QueryPlan) -> Result<Vec<DataRow>, Error> {
         match plan {
             QueryPlan::Keys(keys) => {
@@ -98,7 +125,6 @@ where
                 let end = DataKey::upper_bound::<E>();
 
                 s.range((Bound::Included(start), Bound::Included(end)))
-                    .map(|entry| (entry.key().clone(), entry.value()))
                     .collect()
             }),
             QueryPlan::Index(_) => {
@@ -108,6 +134,101 @@ where
         }
     }
 
+    /// Visit the rows of `plan` in key order, starting after `resume`, until
+    /// `visit` returns `false` or `meter` is spent. Returns a token to resume
+    /// from if any rows were left unvisited.
+    pub fn scan(
+        &self,
+        plan: QueryPlan,
+        resume: Option<Key>,
+        meter: &mut BudgetMeter,
+        visit: impl FnMut(&DataKey, &[u8]) -> Result<bool, Error>,
+    ) -> Result<Option<ResumeToken>, Error> {
+        let after = resume.map(Self::to_data_key);
+
+        let (start, end) = match plan {
+            QueryPlan::FullScan => (DataKey::lower_bound::<E>(), DataKey::upper_bound::<E>()),
+            QueryPlan::Range(start, end) => (Self::to_data_key(start), Self::to_data_key(end)),
+
+            // key lists are sorted so the resume key splits them cleanly
+            plan @ (QueryPlan::Keys(_) | QueryPlan::Index(_)) => {
+                let mut keys = self.candidates_from_plan(plan)?;
+                keys.sort();
+                keys.dedup();
+                if let Some(after) = &after {
+                    keys.retain(|dk| dk > after);
+                }
+
+                return self.with_store(|s| {
+                    let rows = keys
+                        .into_iter()
+                        .filter_map(|dk| s.get(&dk).map(|bytes| (dk, bytes)));
+
+                    Self::visit_rows(rows, meter, visit)
+                })?;
+            }
+        };
+
+        let lower = match after {
+            Some(after) if after >= end => return Ok(None),
+            Some(after) if after >= start => Bound::Excluded(after),
+            _ => Bound::Included(start),
+        };
+
+        self.with_store(|s| {
+            let rows = s.range((lower, Bound::Included(end)));
+
+            Self::visit_rows(rows, meter, visit)
+        })?
+    }
+
+    fn visit_rows(
+        rows: impl Iterator<Item = DataRow>,
+        meter: &mut BudgetMeter,
+        mut visit: impl FnMut(&DataKey, &[u8]) -> Result<bool, Error>,
+    ) -> Result<Option<ResumeToken>, Error> {
+        let mut last: Option<Key> = None;
+        let mut stopped = false;
+
+        for (dk, bytes) in rows {
+            // always make progress, then only stop if there is a row left to resume at
+            if let Some(last) = last
+                && (stopped || meter.is_spent())
+            {
+                return Ok(Some(ResumeToken(last)));
+            }
+
+            meter.tick();
+            stopped = !visit(&dk, &bytes)?;
+            last = Some(dk.key());
+        }
+
+        Ok(None)
+    }
+
+    /// Every row of `E` as it was at `as_of`: the current rows with any that
+    /// changed since swapped for their historical state. Indexes only describe
+    /// the current rows, so this always scans.
+    pub fn rows_as_of(&self, as_of: Timestamp) -> Result<Vec<DataRow>, Error> {
+        let mut rows: BTreeMap<Key, Vec<u8>> = self
+            .rows_from_plan(QueryPlan::FullScan)?
+            .into_iter()
+            .map(|(dk, bytes)| (dk.key(), bytes))
+            .collect();
+
+        for (key, state) in self.db.history_as_of::<E>(as_of)? {
+            match state {
+                Some(bytes) => rows.insert(key, bytes),
+                None => rows.remove(&key),
+            };
+        }
+
+        Ok(rows
+            .into_iter()
+            .map(|(key, bytes)| (DataKey::new::<E>(key), bytes))
+            .collect())
+    }
+
     /// Fetch rows with pagination applied as early as possible (pre-deserialization),
     /// only when no additional filtering or sorting is required by the executor.
     pub fn rows_from_plan_with_pagination(
@@ -147,12 +268,12 @@ where
                     match take {
                         Some(t) => {
                             for entry in base.skip(skip).take(t) {
-                                out.push((entry.key().clone(), entry.value()));
+                                out.push(entry);
                             }
                         }
                         None => {
                             for entry in base.skip(skip) {
-                                out.push((entry.key().clone(), entry.value()));
+                                out.push(entry);
                             }
                         }
                     }
@@ -170,12 +291,12 @@ where
                 match take {
                     Some(t) => {
                         for entry in base.skip(skip).take(t) {
-                            out.push((entry.key().clone(), entry.value()));
+                            out.push(entry);
                         }
                     }
                     None => {
                         for entry in base.skip(skip) {
-                            out.push((entry.key().clone(), entry.value()));
+                            out.push(entry);
                         }
                     }
                 }
@@ -233,15 +354,91 @@ where
     fn load_range(&self, start: DataKey, end: DataKey) -> Result<Vec<DataRow>, Error> {
         self.with_store(|s| {
             s.range((Bound::Included(start), Bound::Included(end)))
-                .map(|e| (e.key().clone(), e.value()))
                 .collect()
         })
     }
 
+    ///
+    /// Relation Helpers
+    ///
+
+    /// Batch-load the rows referenced by each relation field in `fields`.
+    /// Keys are collected from every primary row and deduplicated, so each
+    /// related row is read once regardless of how many rows point at it.
+    /// Rows that are soft deleted or expired at `now` are hidden when the
+    /// target type decodes them, see [`Related::rows`].
+    pub fn load_related(
+        &self,
+        rows: &[(Key, E)],
+        fields: &[String],
+        now: Timestamp,
+    ) -> Result<Related, Error> {
+        let mut related = Related::new();
+
+        for field in fields {
+            let target = E::RELATIONS
+                .iter()
+                .find(|r| r.ident == field)
+                .and_then(|r| r.value.item.relation)
+                .ok_or_else(|| QueryError::InvalidIncludeField(field.clone()))?;
+
+            // same derivation as the generated ENTITY_ID
+            let entity_id = fnv1a_64(target.as_bytes());
+
+            let mut keys = BTreeSet::new();
+            for (_, entity) in rows {
+                if let Some(value) = entity.get_value(field) {
+                    Self::collect_keys(&value, &mut keys);
+                }
+            }
+
+            let mut pending: Vec<DataKey> = keys
+                .into_iter()
+                .map(|key| DataKey::from_entity_id(entity_id, key))
+                .collect();
+            let mut found = Vec::with_capacity(pending.len());
+
+            // the target may live in any data store registered on this canister
+            self.db.with_data(|reg| {
+                reg.for_each(|_, store| {
+                    pending.retain(|dk| match store.get(dk) {
+                        Some(bytes) => {
+                            found.push((dk.clone(), bytes));
+                            false
+                        }
+                        None => true,
+                    });
+                });
+            });
+            found.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+            related.insert(field.clone(), RelatedRows::new(target, found, now));
+        }
+
+        Ok(related)
+    }
+
+    // collect_keys
+    // one/opt relations yield a scalar (or None), many relations yield a list
+    fn collect_keys(value: &Value, keys: &mut BTreeSet<Key>) {
+        match value {
+            Value::List(values) => {
+                for v in values {
+                    Self::collect_keys(v, keys);
+                }
+            }
+            v => {
+                if let Some(key) = v.as_key() {
+                    keys.insert(key);
+                }
+            }
+        }
+    }
+
     /// Deserialize raw data rows into typed entity rows, mapping `DataKey` → `(Key, E)`.
     pub fn deserialize_rows(&self, rows: Vec<DataRow>) -> Result<Vec<(Key, E)>, Error> {
         rows.into_iter()
-            .map(|(k, v)| deserialize::<E>(&v).map(|entry| (k.key(), entry)))
+            .map(|(k, v)| decode_row::<E>(&v).map(|entry| (k.key(), entry)))
             .collect()
     }
 }
@@ -1,18 +1,20 @@
 use crate::{
     Error,
     core::{
-        Key, deserialize,
+        Key,
         traits::{EntityKind, FieldValue},
         view::View,
     },
     db::{
-        Db,
+        Db, DbSession,
         executor::FilterEvaluator,
-        primitives::{FilterDsl, FilterExt, IntoFilterExpr},
-        query::{DeleteQuery, QueryPlan, QueryValidate},
+        primitives::{BudgetExpr, BudgetMeter, FilterDsl, FilterExt, IntoFilterExpr, LimitExt},
+        query::{DeleteQuery, QueryError, QueryPlan, QueryValidate},
         response::Response,
+        store::{ChangeOp, DataKey, decode_row, encode_row},
     },
     obs::metrics,
+    types::Timestamp,
 };
 use std::marker::PhantomData;
 
@@ -24,6 +26,7 @@ use std::marker::PhantomData;
 pub struct DeleteExecutor<E: EntityKind> {
     db: Db<E::Canister>,
     debug: bool,
+    purge: bool,
     _marker: PhantomData<E>,
 }
 
@@ -33,6 +36,7 @@ impl<E: EntityKind> DeleteExecutor<E> {
         Self {
             db,
             debug,
+            purge: false,
             _marker: PhantomData,
         }
     }
@@ -44,6 +48,14 @@ impl<E: EntityKind> DeleteExecutor<E> {
         self
     }
 
+    // purge
+    // permanently remove rows of a soft delete entity, including ones already soft deleted
+    #[must_use]
+    pub const fn purge(mut self) -> Self {
+        self.purge = true;
+        self
+    }
+
     ///
     /// SHORTCUT METHODS
     ///
@@ -96,6 +108,22 @@ impl<E: EntityKind> DeleteExecutor<E> {
         self.execute(query)
     }
 
+    /// Purge up to `batch` rows whose TTL field has passed.
+    pub fn sweep_expired(self, batch: u32) -> Result<Response<E>, Error> {
+        let Some(field) = E::TTL_FIELD else {
+            return Err(QueryError::TtlDisabled(E::PATH.to_string()))?;
+        };
+
+        let query = DeleteQuery::new()
+            .filter(|f| f.lte(field, Timestamp::now()))
+            .limit(batch);
+        let res = self.purge().execute(query)?;
+
+        metrics::with_state_mut(|m| metrics::record_ttl_sweep_for::<E>(m, res.count().into()));
+
+        Ok(res)
+    }
+
     ///
     /// EXECUTION METHODS
     ///
@@ -114,7 +142,6 @@ impl<E: EntityKind> DeleteExecutor<E> {
 
         let ctx = self.db.context::<E>();
         let plan = crate::db::executor::plan_for::<E>(query.filter.as_ref());
-        let keys = ctx.candidates_from_plan(plan)?; // no deserialization here
 
         // query prep
         let limit = query
@@ -124,51 +151,123 @@ impl<E: EntityKind> DeleteExecutor<E> {
             .map(|l| l as usize);
         let filter_simplified = query.filter.as_ref().map(|f| f.clone().simplify());
 
-        let mut res: Vec<(Key, E)> = Vec::with_capacity(limit.unwrap_or(0));
-        ctx.with_store_mut(|s| {
-            for dk in keys {
-                // early limit
-                if let Some(max) = limit
-                    && res.len() >= max
-                {
-                    break;
-                }
-
-                // read value
-                let Some(bytes) = s.get(&dk) else {
-                    continue;
-                };
-
+        let soft = E::SOFT_DELETE && !self.purge;
+
+        // collect matching rows first, hooks must not run while the store is borrowed.
+        // the budget only covers this scan, so leave headroom for the deletes
+        let mut res: Vec<(DataKey, E)> = Vec::with_capacity(limit.unwrap_or(0));
+        let mut meter = BudgetMeter::new(query.budget.as_ref());
+        let resume_key = query.budget.as_ref().and_then(BudgetExpr::resume_key);
+        let resume = if limit == Some(0) {
+            None
+        } else {
+            ctx.scan(plan, resume_key, &mut meter, |dk, bytes| {
                 // deserialize once
-                let Ok(entity) = deserialize::<E>(&bytes) else {
-                    continue;
-                };
+                let entity = decode_row::<E>(bytes)?;
+
+                // already soft deleted rows are only touched by a purge
+                if soft && entity.deleted_at().is_some() {
+                    return Ok(true);
+                }
 
                 // filter check
                 if let Some(ref f) = filter_simplified
                     && !FilterEvaluator::new(&entity).eval(f)
                 {
-                    continue;
+                    return Ok(true);
                 }
 
-                // delete row and remove indexes
-                s.remove(&dk);
-                if !E::INDEXES.is_empty() {
-                    self.remove_indexes(&entity)?;
-                }
+                res.push((dk.clone(), entity));
 
-                // store result (key + deleted entity)
-                res.push((dk.key(), entity));
-            }
+                Ok(limit.is_none_or(|max| res.len() < max))
+            })?
+        };
 
-            Ok::<_, Error>(())
-        })??;
+        // any hook can veto the whole delete before a row is touched
+        let session = self.session();
+        for (_, entity) in &res {
+            entity.before_delete(&session)?;
+        }
 
-        //   canic::cdk::println!("query.delete: deleted keys {deleted_rows:?}");
+        if soft {
+            self.soft_delete(&mut res)?;
+        } else {
+            self.hard_delete(&res)?;
+        }
+
+        for (_, entity) in &res {
+            entity.after_delete(&session)?;
+        }
 
         crate::db::executor::set_rows_from_len(&mut span, res.len());
 
-        Ok(Response(res))
+        Ok(Response::new(
+            res.into_iter()
+                .map(|(dk, entity)| (dk.key(), entity))
+                .collect(),
+        )
+        .with_resume(resume))
+    }
+
+    // hard_delete
+    // removes the data rows and their index entries
+    fn hard_delete(&self, res: &[(DataKey, E)]) -> Result<(), Error> {
+        let ctx = self.db.context::<E>();
+
+        ctx.with_store_mut(|s| {
+            for (dk, _) in res {
+                let old = s.remove(dk);
+                self.db
+                    .record_change::<E>(ChangeOp::Delete, dk.key(), old.as_deref(), None);
+            }
+        })?;
+        if !E::INDEXES.is_empty() {
+            for (_, entity) in res {
+                self.remove_indexes(entity)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    // soft_delete
+    // stamps deleted_at and rewrites the rows, index entries are kept so
+    // unique constraints still apply to soft deleted rows
+    fn soft_delete(&self, res: &mut [(DataKey, E)]) -> Result<(), Error> {
+        let ctx = self.db.context::<E>();
+        let now = Timestamp::now();
+
+        for (dk, entity) in res {
+            let old = entity.clone();
+            entity.set_deleted_at(Some(now));
+            let bytes = encode_row(entity)?;
+
+            for index in E::INDEXES {
+                let store = self.db.with_index(|reg| reg.try_get_store(index.store))?;
+                store.with_borrow_mut(|s| {
+                    s.remove_index_entry(&old, index);
+                    s.insert_index_entry(entity, index)
+                })?;
+            }
+
+            let old_bytes = ctx.with_store_mut(|s| s.insert(dk.clone(), bytes.clone()))?;
+            self.db.record_change::<E>(
+                ChangeOp::Update,
+                dk.key(),
+                old_bytes.as_deref(),
+                Some(&bytes),
+            );
+        }
+
+        Ok(())
+    }
+
+    // session
+    // handle passed to lifecycle hooks
+    const fn session(&self) -> DbSession<E::Canister> {
+        let session = DbSession::new(self.db);
+
+        if self.debug { session.debug() } else { session }
     }
 
     // remove_indexes
@@ -1,11 +1,11 @@
 use crate::{
     core::{
         traits::{EntityKind, FieldValues},
-        value::Value,
+        value::{Value, ValueFamily},
     },
     db::{
         executor::coerce::family::coerce_basic,
-        primitives::{Cmp, FilterClause, FilterExpr},
+        primitives::{Cmp, FieldCmpClause, FilterClause, FilterExpr},
         query::{QueryError, QueryValidate},
     },
 };
@@ -30,6 +30,7 @@ impl<'a> FilterEvaluator<'a> {
             FilterExpr::True => true,
             FilterExpr::False => false,
             FilterExpr::Clause(clause) => self.eval_clause(clause),
+            FilterExpr::FieldCmp(clause) => self.eval_field_cmp(clause),
             FilterExpr::And(children) => children.iter().all(|e| self.eval(e)),
             FilterExpr::Or(children) => children.iter().any(|e| self.eval(e)),
             FilterExpr::Not(inner) => !self.eval(inner),
@@ -43,6 +44,23 @@ impl<'a> FilterEvaluator<'a> {
             .is_some_and(|actual| Self::compare(&actual, clause.cmp, &clause.value))
     }
 
+    // eval_field_cmp
+    // an unset side only matches another unset side, or Ne against a set one
+    fn eval_field_cmp(&self, clause: &FieldCmpClause) -> bool {
+        let (Some(left), Some(right)) = (
+            self.values.get_value(clause.field.as_str()),
+            self.values.get_value(clause.other.as_str()),
+        ) else {
+            return false;
+        };
+
+        match (&left, &right) {
+            (Value::None, Value::None) => matches!(clause.cmp, Cmp::Eq),
+            (Value::None, _) | (_, Value::None) => matches!(clause.cmp, Cmp::Ne),
+            _ => Self::compare(&left, clause.cmp, &right),
+        }
+    }
+
     /// Core comparator dispatch
     fn compare(left: &Value, cmp: Cmp, right: &Value) -> bool {
         // 0) Presence/null checks (RHS ignored)
@@ -90,7 +108,7 @@ impl<E: EntityKind> QueryValidate<E> for FilterExpr {
             Self::True | Self::False => Ok(()),
 
             Self::Clause(c) => {
-                if !E::FIELDS.contains(&c.field.as_str()) {
+                if !E::has_path(&c.field) {
                     return Err(QueryError::InvalidFilterField(c.field.clone()));
                 }
 
@@ -219,6 +237,8 @@ impl<E: EntityKind> QueryValidate<E> for FilterExpr {
                 Ok(())
             }
 
+            Self::FieldCmp(c) => validate_field_cmp::<E>(c),
+
             Self::And(children) | Self::Or(children) => {
                 for expr in children {
                     QueryValidate::<E>::validate(expr)?;
@@ -231,3 +251,46 @@ impl<E: EntityKind> QueryValidate<E> for FilterExpr {
         }
     }
 }
+
+// validate_field_cmp
+// both sides must be known paths, their families come from the field types
+fn validate_field_cmp<E: EntityKind>(c: &FieldCmpClause) -> Result<(), QueryError> {
+    let family = |field: &String| {
+        let family =
+            E::path_family(field).ok_or_else(|| QueryError::InvalidFilterField(field.clone()))?;
+
+        if family == ValueFamily::Unsupported {
+            return Err(QueryError::InvalidFilterValue(format!(
+                "field '{field}' has no comparable type"
+            )));
+        }
+
+        Ok(family)
+    };
+    let (left, right) = (family(&c.field)?, family(&c.other)?);
+
+    if !left.is_comparable_with(right) {
+        return Err(QueryError::InvalidFilterValue(format!(
+            "field '{}' ({left:?}) cannot be compared with field '{}' ({right:?})",
+            c.field, c.other
+        )));
+    }
+
+    match c.cmp {
+        Cmp::Eq | Cmp::Ne => Ok(()),
+        Cmp::Lt | Cmp::Lte | Cmp::Gt | Cmp::Gte => {
+            if left.is_numeric() || left.is_textual() {
+                Ok(())
+            } else {
+                Err(QueryError::InvalidFilterValue(format!(
+                    "field '{}' is not ordered ({left:?}) for {:?}",
+                    c.field, c.cmp
+                )))
+            }
+        }
+        cmp => Err(QueryError::InvalidFilterValue(format!(
+            "field '{}' cannot use {cmp:?} against field '{}'",
+            c.field, c.other
+        ))),
+    }
+}
@@ -7,14 +7,20 @@ use crate::{
     db::{
         Db,
         executor::{FilterEvaluator, plan_for},
-        primitives::{FilterDsl, FilterExpr, FilterExt, IntoFilterExpr, Order, SortExpr},
-        query::{LoadQuery, QueryPlan, QueryValidate},
-        response::Response,
+        primitives::{
+            BudgetExpr, BudgetMeter, FilterDsl, FilterExpr, FilterExt, IntoFilterExpr, Order,
+            SortExpr,
+        },
+        query::{DeletedMode, LoadQuery, QueryError, QueryPlan, QueryValidate},
+        response::{EntityVersion, NextPage, Page, Related, Response},
+        store::decode_row,
     },
     obs::metrics,
+    schema::types::Cardinality,
+    types::Timestamp,
 };
 use canic::log;
-use std::{cmp::Ordering, marker::PhantomData};
+use std::{cmp::Ordering, collections::BTreeMap, marker::PhantomData};
 
 ///
 /// LoadExecutor
@@ -92,6 +98,51 @@ impl<E: EntityKind> LoadExecutor<E> {
         self.execute(query)
     }
 
+    /// Load every row of `E` whose relation fields reference the `T` row with primary key `key`.
+    ///
+    /// One/optional relation fields are matched with `==`, so the planner resolves them
+    /// through a relation index (`rel_index`) when one exists. Many relation fields
+    /// are matched with `contains` and always scan, so they can't set `rel_index`.
+    pub fn referencing<T: EntityKind>(&self, key: impl FieldValue) -> Result<Response<E>, Error> {
+        let value = key.to_value();
+        let fields: Vec<_> = E::RELATIONS
+            .iter()
+            .filter(|f| f.value.item.relation == Some(T::PATH))
+            .collect();
+
+        if fields.is_empty() {
+            return Err(QueryError::InvalidRelation(
+                E::PATH.to_string(),
+                T::PATH.to_string(),
+            ))?;
+        }
+
+        let mut rows: BTreeMap<Key, E> = BTreeMap::new();
+        for field in fields {
+            let query = match field.value.cardinality {
+                Cardinality::Many => LoadQuery::new().filter(|f| f.contains(field.ident, &value)),
+                _ => LoadQuery::new().filter(|f| f.eq(field.ident, &value)),
+            };
+
+            rows.extend(self.execute(query)?);
+        }
+
+        Ok(Response::new(rows.into_iter().collect()))
+    }
+
+    /// Retained prior versions of the row with primary key `key`, oldest first.
+    pub fn history(&self, key: impl Into<Key>) -> Result<Vec<EntityVersion<E>>, Error> {
+        if E::HISTORY_LIMIT.is_none() {
+            return Err(QueryError::HistoryDisabled(E::PATH.to_string()))?;
+        }
+
+        self.db
+            .history::<E>(key.into())?
+            .into_iter()
+            .map(EntityVersion::try_from)
+            .collect()
+    }
+
     pub fn count_all(self) -> Result<u32, Error> {
         let query = LoadQuery::all();
         self.count(query)
@@ -120,9 +171,26 @@ impl<E: EntityKind> LoadExecutor<E> {
 
         self.debug_log(format!("📄 Query plan: {plan:?}"));
 
+        // Budgeted: a single pass in key order that stops when the budget runs out
+        if query.budget.is_some() {
+            let res = self.scan_budgeted(&query, plan)?;
+            self.debug_log(format!(
+                "⛽ Budgeted scan -> {} entities, resume {:?}",
+                res.count(),
+                res.resume()
+            ));
+
+            let related = self.load_includes(&res.rows, &query)?;
+            crate::db::executor::set_rows_from_len(&mut span, res.rows.len());
+
+            return Ok(res.with_related(related));
+        }
+
         // Fast path: pre-pagination
-        let pre_paginated = query.filter.is_none() && query.sort.is_none() && query.limit.is_some();
-        let data_rows = if pre_paginated {
+        let pre_paginated = query.limit.is_some() && Self::is_unfiltered(&query);
+        let data_rows = if let Some(as_of) = query.as_of {
+            ctx.rows_as_of(as_of)?
+        } else if pre_paginated {
             let lim = query.limit.as_ref().unwrap();
             ctx.rows_from_plan_with_pagination(plan, lim.offset, lim.limit)?
         } else {
@@ -141,6 +209,28 @@ impl<E: EntityKind> LoadExecutor<E> {
             rows.len()
         ));
 
+        // Soft delete
+        if E::SOFT_DELETE && query.deleted != DeletedMode::Include {
+            rows.retain(|(_, e)| query.deleted.matches(e.deleted_at().is_some()));
+
+            self.debug_log(format!(
+                "🪦 Applied deleted mode {:?} -> {} entities remaining",
+                query.deleted,
+                rows.len()
+            ));
+        }
+
+        // Expired rows are hidden until the sweeper removes them
+        if E::TTL_FIELD.is_some() {
+            let now = query.as_of.unwrap_or_else(Timestamp::now);
+            rows.retain(|(_, e)| !e.is_expired(now));
+
+            self.debug_log(format!(
+                "⏳ Removed expired rows -> {} entities remaining",
+                rows.len()
+            ));
+        }
+
         // Filtering
         if let Some(f) = &query.filter {
             let simplified = f.clone().simplify();
@@ -173,10 +263,74 @@ impl<E: EntityKind> LoadExecutor<E> {
             ));
         }
 
+        // Includes
+        let related = self.load_includes(&rows, &query)?;
+
         crate::db::executor::set_rows_from_len(&mut span, rows.len());
         self.debug_log(format!("✅ Query complete -> {} final rows", rows.len()));
 
-        Ok(Response(rows))
+        Ok(Response::new(rows).with_related(related))
+    }
+
+    /// Execute a query and return one page of it, with the total number of
+    /// matching rows and where the next page starts. Includes aren't loaded.
+    ///
+    /// Unfiltered queries count the total from keys and only decode the page.
+    /// Otherwise every matching row is decoded to count it, O(n) in the rows
+    /// the plan reads; budget the query to page by resume token instead.
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn page(&self, mut query: LoadQuery) -> Result<Page<E>, Error> {
+        QueryValidate::<E>::validate(&query)?;
+        query.include.clear();
+
+        // budgeted loads page by resume token, and can't know the total
+        if query.budget.is_some() {
+            let res = self.execute(query)?;
+
+            return Ok(Page {
+                next: res.resume().map(NextPage::Resume),
+                rows: res.rows,
+                total: None,
+            });
+        }
+
+        let limit = query.limit.clone().unwrap_or_default();
+        let (rows, total) = if Self::is_unfiltered(&query) {
+            // every stored row matches, so count keys and decode just the page
+            let total = self
+                .db
+                .context::<E>()
+                .count_from_plan(QueryPlan::FullScan)?;
+
+            (self.execute(query)?.rows, total)
+        } else {
+            // count every match, then cut the page out
+            query.limit = None;
+            let mut rows = self.execute(query)?.rows;
+            let total = rows.len();
+            apply_pagination(&mut rows, limit.offset, limit.limit);
+
+            (rows, total)
+        };
+
+        let end = limit.offset as usize + rows.len();
+
+        Ok(Page {
+            rows,
+            total: Some(total as u64),
+            next: (end < total).then_some(NextPage::Offset(end as u32)),
+        })
+    }
+
+    // is_unfiltered
+    // true when every row in key order is a match, so rows can be paged
+    // and counted before they are decoded
+    fn is_unfiltered(query: &LoadQuery) -> bool {
+        query.filter.is_none()
+            && query.sort.is_none()
+            && (!E::SOFT_DELETE || query.deleted == DeletedMode::Include)
+            && E::TTL_FIELD.is_none()
+            && query.as_of.is_none()
     }
 
     /// currently just doing the same as execute()
@@ -188,6 +342,65 @@ impl<E: EntityKind> LoadExecutor<E> {
         Ok(count)
     }
 
+    // scan_budgeted
+    // deserializes and filters inside the scan so the budget covers that work too
+    fn scan_budgeted(&self, query: &LoadQuery, plan: QueryPlan) -> Result<Response<E>, Error> {
+        let ctx = self.db.context::<E>();
+        let budget = query.budget.as_ref();
+        let limit = query
+            .limit
+            .as_ref()
+            .and_then(|l| l.limit)
+            .map(|l| l as usize);
+        let filter = query.filter.as_ref().map(|f| f.clone().simplify());
+        let now = Timestamp::now();
+
+        if limit == Some(0) {
+            return Ok(Response::new(Vec::new()));
+        }
+
+        let mut meter = BudgetMeter::new(budget);
+        let mut rows = Vec::new();
+        let resume = ctx.scan(
+            plan,
+            budget.and_then(BudgetExpr::resume_key),
+            &mut meter,
+            |dk, bytes| {
+                let entity = decode_row::<E>(bytes)?;
+
+                let visible = (!E::SOFT_DELETE
+                    || query.deleted.matches(entity.deleted_at().is_some()))
+                    && !entity.is_expired(now)
+                    && filter
+                        .as_ref()
+                        .is_none_or(|f| FilterEvaluator::new(&entity).eval(f));
+                if visible {
+                    rows.push((dk.key(), entity));
+                }
+
+                Ok(limit.is_none_or(|max| rows.len() < max))
+            },
+        )?;
+
+        Ok(Response::new(rows).with_resume(resume))
+    }
+
+    // load_includes
+    fn load_includes(&self, rows: &[(Key, E)], query: &LoadQuery) -> Result<Related, Error> {
+        if query.include.is_empty() {
+            return Ok(Related::default());
+        }
+
+        let now = query.as_of.unwrap_or_else(Timestamp::now);
+        let related = self
+            .db
+            .context::<E>()
+            .load_related(rows, &query.include, now)?;
+        self.debug_log(format!("🔗 Loaded includes: {:?}", query.include));
+
+        Ok(related)
+    }
+
     // apply_filter
     fn apply_filter(rows: &mut Vec<(Key, E)>, filter: &FilterExpr) {
         rows.retain(|(_, e)| FilterEvaluator::new(e).eval(filter));
@@ -253,14 +466,24 @@ mod tests {
         core::{
             Key, Value,
             traits::{
-                CanisterKind, EntityKind, FieldValues, Path, SanitizeAuto, SanitizeCustom,
-                StoreKind, ValidateAuto, ValidateCustom, View, Visitable,
+                CanisterKind, EntityHooks, EntityKind, FieldValues, Path, SanitizeAuto,
+                SanitizeCustom, StoreKind, ValidateAuto, ValidateCustom, View, Visitable,
             },
+            value::ValueFamily,
         },
-        db::primitives::{Order, SortExpr},
-        schema::node::Index,
+        db::primitives::{BudgetExt, FilterExpr, FilterExt, LimitExt, Order, SortExpr, SortExt},
+        db::query::{LoadQuery, QueryValidate},
+        db::response::NextPage,
+        db::store::{
+            DataKey, DataStore, DataStoreRegistry, IndexStoreRegistry, RowCodec, RowMigration,
+            encode_row,
+        },
+        db::{Db, DbSession},
+        schema::node::{Field, Index},
+        types::Timestamp,
     };
     use serde::{Deserialize, Serialize};
+    use std::cell::RefCell;
 
     #[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
     struct SortableEntity {
@@ -318,6 +541,7 @@ mod tests {
     impl SanitizeCustom for SortableEntity {}
     impl ValidateAuto for SortableEntity {}
     impl ValidateCustom for SortableEntity {}
+    impl EntityHooks for SortableEntity {}
     impl Visitable for SortableEntity {}
 
     impl FieldValues for SortableEntity {
@@ -330,6 +554,14 @@ mod tests {
                 _ => None,
             }
         }
+
+        fn path_family(field: &str) -> Option<ValueFamily> {
+            match field {
+                "id" | "primary" | "secondary" => Some(ValueFamily::Numeric),
+                "optional_blob" => Some(ValueFamily::Blob),
+                _ => None,
+            }
+        }
     }
 
     impl EntityKind for SortableEntity {
@@ -341,6 +573,14 @@ mod tests {
         const PRIMARY_KEY: &'static str = "id";
         const FIELDS: &'static [&'static str] = &["id", "primary", "secondary", "optional_blob"];
         const INDEXES: &'static [&'static Index] = &[];
+        const RELATIONS: &'static [&'static Field] = &[];
+        const SOFT_DELETE: bool = false;
+        const TTL_FIELD: Option<&'static str> = None;
+        const HISTORY_LIMIT: Option<u32> = None;
+        const SCHEMA_VERSION: u32 = 1;
+        const MIGRATIONS: &'static [RowMigration] = &[];
+        const CODEC: RowCodec = RowCodec::DEFAULT;
+        const RETIRED_FIELDS: &'static [&'static str] = &[];
 
         fn key(&self) -> Key {
             self.id.into()
@@ -388,6 +628,46 @@ mod tests {
         assert_eq!(v, vec![20, 30]);
     }
 
+    #[test]
+    fn include_rejects_non_relation_field() {
+        let query = LoadQuery::new().include("primary");
+        let res = QueryValidate::<SortableEntity>::validate(&query);
+
+        assert!(res.is_err(), "include on a non-relation field should fail");
+    }
+
+    #[test]
+    fn deleted_mode_requires_soft_delete() {
+        for query in [
+            LoadQuery::new().with_deleted(),
+            LoadQuery::new().only_deleted(),
+        ] {
+            let res = QueryValidate::<SortableEntity>::validate(&query);
+
+            assert!(res.is_err(), "SortableEntity has no soft delete");
+        }
+    }
+
+    #[test]
+    fn as_of_requires_history() {
+        let query = LoadQuery::new().as_of(Timestamp::from(1_u64));
+        let res = QueryValidate::<SortableEntity>::validate(&query);
+
+        assert!(res.is_err(), "SortableEntity has no history");
+    }
+
+    #[test]
+    fn budget_rejects_sort_and_offset() {
+        for query in [
+            LoadQuery::new().max_rows(10).sort(|s| s.asc("primary")),
+            LoadQuery::new().max_rows(10).offset(5),
+        ] {
+            let res = QueryValidate::<SortableEntity>::validate(&query);
+
+            assert!(res.is_err(), "budgeted loads only run in key order");
+        }
+    }
+
     #[test]
     fn apply_sort_orders_descending() {
         let mut rows = vec![
@@ -444,4 +724,173 @@ mod tests {
         let ids: Vec<u64> = rows.iter().map(|(_, e)| e.id).collect();
         assert_eq!(ids, vec![1, 2, 3]);
     }
+
+    #[test]
+    fn executors_run_against_in_memory_db() {
+        let session = DbSession::new(Db::<SortableCanister>::in_memory());
+        for (id, primary) in [(1, 30), (2, 10), (3, 20)] {
+            session
+                .insert(SortableEntity::new(id, primary, 0, None))
+                .unwrap();
+        }
+
+        let query = LoadQuery::new()
+            .filter(|f| f.gt("primary", 10))
+            .sort(|s| s.asc("primary"));
+        let ids: Vec<u64> = session
+            .load::<SortableEntity>()
+            .execute(query)
+            .unwrap()
+            .entities()
+            .iter()
+            .map(|e| e.id)
+            .collect();
+        assert_eq!(ids, vec![3, 1]);
+
+        session.delete::<SortableEntity>().one(2_u64).unwrap();
+        assert_eq!(session.load::<SortableEntity>().count_all().unwrap(), 2);
+
+        // every in-memory db starts empty
+        let other = DbSession::new(Db::<SortableCanister>::in_memory());
+        assert_eq!(other.load::<SortableEntity>().count_all().unwrap(), 0);
+    }
+
+    #[test]
+    fn filters_compare_fields() {
+        let session = DbSession::new(Db::<SortableCanister>::in_memory());
+        for (id, primary) in [(1, 5), (2, 10), (3, 20)] {
+            session
+                .insert(SortableEntity::new(id, primary, 10, None))
+                .unwrap();
+        }
+
+        let ids = |query: LoadQuery| -> Vec<u64> {
+            session
+                .load::<SortableEntity>()
+                .execute(query)
+                .unwrap()
+                .entities()
+                .iter()
+                .map(|e| e.id)
+                .collect()
+        };
+        assert_eq!(
+            ids(LoadQuery::new().filter(|f| f.lt_field("primary", "secondary"))),
+            vec![1]
+        );
+        assert_eq!(
+            ids(LoadQuery::new().filter(|f| f.gte_field("primary", "secondary"))),
+            vec![2, 3]
+        );
+        assert_eq!(
+            ids(LoadQuery::new().filter(|f| f.ne_field("id", "primary"))),
+            vec![1, 2, 3]
+        );
+
+        for query in [
+            LoadQuery::new().filter(|f| f.eq_field("primary", "missing")),
+            LoadQuery::new().filter(|f| f.lt_field("primary", "optional_blob.0")),
+            LoadQuery::new().filter(|f| f.eq_field("primary", "optional_blob")),
+            LoadQuery::new().filter(|f| f.lt_field("optional_blob", "optional_blob")),
+            LoadQuery::new().filter(|_| "primary ~ secondary".parse::<FilterExpr>().unwrap()),
+        ] {
+            let res = QueryValidate::<SortableEntity>::validate(&query);
+
+            assert!(res.is_err(), "{:?} should not validate", query.filter);
+        }
+    }
+
+    #[test]
+    fn pages_report_total_and_next() {
+        let session = DbSession::new(Db::<SortableCanister>::in_memory());
+        for id in 1..=5 {
+            session.insert(SortableEntity::new(id, 0, 0, None)).unwrap();
+        }
+
+        let query = LoadQuery::new().filter(|f| f.gt("id", 1)).limit(2);
+        let page = session
+            .load::<SortableEntity>()
+            .page(query.clone())
+            .unwrap();
+        let ids: Vec<u64> = page.rows.iter().map(|(_, e)| e.id).collect();
+        assert_eq!(ids, vec![2, 3]);
+        assert_eq!(page.total, Some(4));
+        assert_eq!(page.next, Some(NextPage::Offset(2)));
+
+        let last = session
+            .load::<SortableEntity>()
+            .page(query.page(page.next.unwrap()))
+            .unwrap();
+        let ids: Vec<u64> = last.rows.iter().map(|(_, e)| e.id).collect();
+        assert_eq!(ids, vec![4, 5]);
+        assert!(last.is_last());
+    }
+
+    #[test]
+    fn unfiltered_pages_count_keys() {
+        let session = DbSession::new(Db::<SortableCanister>::in_memory());
+        for id in 1..=5 {
+            session.insert(SortableEntity::new(id, 0, 0, None)).unwrap();
+        }
+
+        let page = session
+            .load::<SortableEntity>()
+            .page(LoadQuery::new().offset(3).limit(1))
+            .unwrap();
+        let ids: Vec<u64> = page.rows.iter().map(|(_, e)| e.id).collect();
+        assert_eq!(ids, vec![4]);
+        assert_eq!(page.total, Some(5));
+        assert_eq!(page.next, Some(NextPage::Offset(4)));
+    }
+
+    thread_local! {
+        // rows written under CBOR keys, before the binary key encoding
+        static LEGACY_DATA: RefCell<DataStore> = {
+            let rows: Vec<_> = (1..=3)
+                .map(|id| {
+                    let entity = SortableEntity::new(id, 0, 0, None);
+                    (DataKey::new::<SortableEntity>(id), encode_row(&entity).unwrap())
+                })
+                .collect();
+
+            RefCell::new(DataStore::with_legacy_keys(&rows).0)
+        };
+
+        static LEGACY_DATA_REGISTRY: DataStoreRegistry = {
+            let mut reg = DataStoreRegistry::new();
+            reg.register(SortableStore::PATH, &LEGACY_DATA);
+            reg
+        };
+
+        static LEGACY_INDEX_REGISTRY: IndexStoreRegistry = IndexStoreRegistry::new();
+    }
+
+    #[test]
+    fn legacy_keys_load_by_key_and_range() {
+        let session = DbSession::new(Db::<SortableCanister>::new(
+            &LEGACY_DATA_REGISTRY,
+            &LEGACY_INDEX_REGISTRY,
+        ));
+        let ids = |query: LoadQuery| -> Vec<u64> {
+            session
+                .load::<SortableEntity>()
+                .execute(query)
+                .unwrap()
+                .entities()
+                .iter()
+                .map(|e| e.id)
+                .collect()
+        };
+
+        assert_eq!(ids(LoadQuery::new().one::<SortableEntity>(2_u64)), vec![2]);
+        assert_eq!(ids(LoadQuery::new()), vec![1, 2, 3]);
+        assert_eq!(ids(LoadQuery::new().filter(|f| f.gte("id", 2))), vec![2, 3]);
+        assert_eq!(ids(LoadQuery::new().max_rows(2)), vec![1, 2]);
+
+        // the stored key still counts as taken
+        assert!(session.insert(SortableEntity::new(2, 0, 0, None)).is_err());
+
+        LEGACY_DATA.with_borrow_mut(DataStore::rekey);
+        assert_eq!(ids(LoadQuery::new()), vec![1, 2, 3]);
+    }
 }
@@ -37,8 +37,20 @@ pub enum ExecutorError {
     #[error("data key not found: {0}")]
     KeyNotFound(DataKey),
 
+    #[error("before_save hook changed the primary key: {0}")]
+    KeyChanged(DataKey),
+
+    #[error("data key is not soft deleted: {0}")]
+    NotDeleted(DataKey),
+
     #[error("index constraint violation: {0} ({1})")]
     IndexViolation(String, String),
+
+    #[error("{0} rejected by hook: {1}")]
+    HookRejected(String, String),
+
+    #[error("field '{1}' of {0} can't be set")]
+    FieldNotEditable(String, String),
 }
 
 impl ExecutorError {
@@ -46,6 +58,12 @@ impl ExecutorError {
     pub fn index_violation(path: &str, index_fields: &[&str]) -> Self {
         Self::IndexViolation(path.to_string(), index_fields.join(", "))
     }
+
+    /// Error for a lifecycle hook to veto a save or delete with.
+    #[must_use]
+    pub fn hook_rejected(path: &str, reason: impl Into<String>) -> Self {
+        Self::HookRejected(path.to_string(), reason.into())
+    }
 }
 
 impl From<ExecutorError> for Error {
@@ -1,15 +1,24 @@
 use crate::{
     Error,
-    core::{deserialize, sanitize, serialize, traits::EntityKind, validate},
+    core::{
+        Key, Value, deserialize, sanitize,
+        traits::{CreateView, EntityKind, UpdateView},
+        validate,
+        value::{apply_values, field_names},
+    },
     db::{
-        Db,
+        Db, DbSession,
         executor::ExecutorError,
-        query::{SaveMode, SaveQuery},
-        store::DataKey,
+        query::{QueryError, SaveMode, SaveQuery},
+        store::{DataKey, decode_row, encode_row, row_version},
     },
     obs::metrics,
 };
-use std::marker::PhantomData;
+use serde::{Serialize, de::DeserializeOwned};
+use std::{
+    collections::{BTreeMap, HashSet},
+    marker::PhantomData,
+};
 
 ///
 /// SaveExecutor
@@ -82,6 +91,106 @@ impl<E: EntityKind> SaveExecutor<E> {
         Ok(saved_view)
     }
 
+    /// Merge an update view into the stored row, saving it as an update.
+    pub fn patch(&self, key: impl Into<Key>, patch: E::UpdateViewType) -> Result<E, Error>
+    where
+        E: UpdateView,
+    {
+        let data_key = DataKey::new::<E>(key);
+        let bytes = self
+            .db
+            .context::<E>()
+            .with_store(|store| store.get(&data_key))?
+            .ok_or(ExecutorError::KeyNotFound(data_key))?;
+
+        let mut entity = decode_row::<E>(&bytes)?;
+        entity.merge(patch);
+
+        self.save_entity(SaveMode::Update, entity)
+    }
+
+    /// Insert a row from field values, the primary key and any field a `Create`
+    /// view has; the rest keep their defaults.
+    pub fn insert_values(&self, values: &BTreeMap<String, Value>) -> Result<E, Error>
+    where
+        E: CreateView,
+        E::ViewType: Serialize + DeserializeOwned,
+        E::CreateViewType: Serialize,
+    {
+        Self::check_editable(values, true)?;
+        let view = apply_values(&E::default().to_view(), values)?;
+
+        self.insert(E::from_view(view))
+    }
+
+    /// Set fields of the stored row from values, saving it as an update.
+    /// Only fields a `Create` view has can be set.
+    pub fn update_values(
+        &self,
+        key: impl Into<Key>,
+        values: &BTreeMap<String, Value>,
+    ) -> Result<E, Error>
+    where
+        E: CreateView,
+        E::ViewType: Serialize + DeserializeOwned,
+        E::CreateViewType: Serialize,
+    {
+        Self::check_editable(values, false)?;
+
+        let data_key = DataKey::new::<E>(key);
+        let bytes = self
+            .db
+            .context::<E>()
+            .with_store(|store| store.get(&data_key))?
+            .ok_or(ExecutorError::KeyNotFound(data_key))?;
+        let view = apply_values(&decode_row::<E>(&bytes)?.to_view(), values)?;
+
+        self.save_entity(SaveMode::Update, E::from_view(view))
+    }
+
+    // check_editable
+    // system fields are never set by hand, and the primary key only on insert
+    fn check_editable(values: &BTreeMap<String, Value>, with_key: bool) -> Result<(), Error>
+    where
+        E: CreateView,
+        E::CreateViewType: Serialize,
+    {
+        let editable = field_names(&E::CreateViewType::default())?;
+
+        for field in values.keys() {
+            let is_key = with_key && field == E::PRIMARY_KEY;
+            if E::FIELDS.contains(&field.as_str()) && !is_key && !editable.contains(field) {
+                return Err(
+                    ExecutorError::FieldNotEditable(E::PATH.to_string(), field.clone()).into(),
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Clear `deleted_at` on a soft deleted row, saving it as an update.
+    pub fn restore(&self, key: impl Into<Key>) -> Result<E, Error> {
+        if !E::SOFT_DELETE {
+            return Err(QueryError::SoftDeleteDisabled(E::PATH.to_string()))?;
+        }
+
+        let data_key = DataKey::new::<E>(key);
+        let bytes = self
+            .db
+            .context::<E>()
+            .with_store(|store| store.get(&data_key))?
+            .ok_or_else(|| ExecutorError::KeyNotFound(data_key.clone()))?;
+
+        let mut entity = decode_row::<E>(&bytes)?;
+        if entity.deleted_at().is_none() {
+            return Err(ExecutorError::NotDeleted(data_key))?;
+        }
+        entity.set_deleted_at(None);
+
+        self.save_entity(SaveMode::Update, entity)
+    }
+
     // execute
     // serializes the save query to pass to save_entity
     pub fn execute(&self, query: SaveQuery) -> Result<E, Error> {
@@ -96,10 +205,7 @@ impl<E: EntityKind> SaveExecutor<E> {
         let mut span = metrics::Span::<E>::new(metrics::ExecKind::Save);
         let key = entity.key();
         let ctx = self.db.context::<E>();
-
-        // sanitize & validate
-        sanitize(&mut entity);
-        validate(&entity)?;
+        let session = self.session();
 
         // debug
         //   debug!(self.debug, "query.{mode}: {} ({key:?}) ", E::PATH);
@@ -113,11 +219,11 @@ impl<E: EntityKind> SaveExecutor<E> {
         let old_result = ctx.with_store(|store| store.get(&data_key))?;
 
         // did anything change?
-        let old = match (mode, old_result) {
+        let old = match (mode, &old_result) {
             (SaveMode::Insert | SaveMode::Replace, None) => None,
 
             (SaveMode::Update | SaveMode::Replace, Some(old_bytes)) => {
-                let old = deserialize::<E>(&old_bytes)?;
+                let old = decode_row::<E>(old_bytes)?;
                 Some(old)
             }
 
@@ -126,21 +232,49 @@ impl<E: EntityKind> SaveExecutor<E> {
             (SaveMode::Update, None) => return Err(ExecutorError::KeyNotFound(data_key))?,
         };
 
+        // hook may veto or modify the entity, but not move it to another key
+        entity.before_save(&session, old.as_ref())?;
+        if entity.key() != key {
+            return Err(ExecutorError::KeyChanged(data_key))?;
+        }
+
+        // sanitize & validate
+        sanitize(&mut entity);
+        validate(&entity)?;
+
         // now we can serialize
-        let bytes = serialize(&entity)?;
+        let bytes = encode_row(&entity)?;
+
+        // an old row at an older version was indexed by its unmigrated values
+        let stale = old_result
+            .as_deref()
+            .is_some_and(|bytes| row_version(bytes).0 != E::SCHEMA_VERSION);
 
         // replace indexes, fail if there are any unique violations
-        self.replace_indexes(old.as_ref(), &entity)?;
+        self.replace_indexes(old.as_ref(), stale, &entity)?;
 
-        // insert data row
+        // log the change, then insert data row
+        self.db
+            .record_change::<E>(mode.into(), key, old_result.as_deref(), Some(&bytes));
         ctx.with_store_mut(|store| store.insert(data_key.clone(), bytes))?;
         span.set_rows(1);
 
+        entity.after_save(&session, old.as_ref())?;
+
         Ok(entity)
     }
 
+    // session
+    // handle passed to lifecycle hooks
+    const fn session(&self) -> DbSession<E::Canister> {
+        let session = DbSession::new(self.db);
+
+        if self.debug { session.debug() } else { session }
+    }
+
     // replace_indexes: two-phase (validate, then mutate) to avoid partial updates
-    fn replace_indexes(&self, old: Option<&E>, new: &E) -> Result<(), Error> {
+    // `stale` drops every entry of the row, as `old` is migrated and its keys may differ
+    fn replace_indexes(&self, old: Option<&E>, stale: bool, new: &E) -> Result<(), Error> {
         use crate::db::store::IndexKey;
 
         // Phase 1: validate uniqueness for all indexes without mutating
@@ -173,7 +307,9 @@ impl<E: EntityKind> SaveExecutor<E> {
         for index in E::INDEXES {
             let store = self.db.with_index(|reg| reg.try_get_store(index.store))?;
             store.with_borrow_mut(|s| {
-                if let Some(old) = old {
+                if stale {
+                    s.remove_keys::<E>(index, &HashSet::from([new.key()]));
+                } else if let Some(old) = old {
                     s.remove_index_entry(old, index);
                 }
                 s.insert_index_entry(new, index)?;
@@ -0,0 +1,138 @@
+use crate::{core::Key, db::query::DeleteQuery};
+use candid::CandidType;
+use canic::impl_storable_unbounded;
+use derive_more::Display;
+use serde::{Deserialize, Serialize};
+
+///
+/// Job
+///
+/// A unit of maintenance work that runs a chunk at a time, continuing from
+/// `cursor` (the last row processed) on the next chunk.
+///
+
+#[derive(CandidType, Clone, Debug, Deserialize, Serialize)]
+pub struct Job {
+    pub id: u64,
+    pub kind: JobKind,
+    pub status: JobStatus,
+    pub stage: JobStage,
+    pub cursor: Option<Key>,
+    pub chunks: u64,
+    pub rows: u64,
+    pub failures: u32,
+    pub errors: Vec<String>,
+    pub created_at: u64,
+    pub updated_at: u64,
+}
+
+impl Job {
+    /// Consecutive failed chunks before a job is marked failed.
+    pub const MAX_FAILURES: u32 = 3;
+
+    /// Most recent errors kept on the job.
+    pub const MAX_ERRORS: usize = 10;
+
+    #[must_use]
+    pub const fn new(id: u64, kind: JobKind, now: u64) -> Self {
+        Self {
+            id,
+            kind,
+            status: JobStatus::Running,
+            stage: JobStage::Prepare,
+            cursor: None,
+            chunks: 0,
+            rows: 0,
+            failures: 0,
+            errors: Vec::new(),
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    pub fn record_error(&mut self, err: impl ToString) {
+        if self.errors.len() == Self::MAX_ERRORS {
+            self.errors.remove(0);
+        }
+        self.errors.push(err.to_string());
+        self.failures += 1;
+
+        if self.failures >= Self::MAX_FAILURES {
+            self.status = JobStatus::Failed;
+        }
+    }
+}
+
+impl_storable_unbounded!(Job);
+
+///
+/// JobKind
+///
+/// Reindex  : rebuild every index of `entity` from its rows
+/// Delete   : delete every row matching `query`; its limit is ignored
+/// TtlSweep : purge the expired rows of a ttl entity
+/// Migrate  : rewrite rows stored at an older schema version of `entity`
+///
+
+#[derive(CandidType, Clone, Debug, Deserialize, Serialize)]
+pub enum JobKind {
+    Reindex {
+        entity: String,
+    },
+    Delete {
+        entity: String,
+        query: Box<DeleteQuery>,
+    },
+    TtlSweep {
+        entity: String,
+    },
+    Migrate {
+        entity: String,
+    },
+}
+
+impl JobKind {
+    /// Path of the entity the job works on.
+    #[must_use]
+    pub fn entity(&self) -> &str {
+        match self {
+            Self::Reindex { entity }
+            | Self::Delete { entity, .. }
+            | Self::TtlSweep { entity }
+            | Self::Migrate { entity } => entity,
+        }
+    }
+}
+
+///
+/// JobStatus
+///
+
+#[derive(CandidType, Clone, Copy, Debug, Deserialize, Display, Eq, PartialEq, Serialize)]
+pub enum JobStatus {
+    Running,
+    Paused,
+    Completed,
+    Cancelled,
+    Failed,
+}
+
+impl JobStatus {
+    #[must_use]
+    pub const fn is_finished(self) -> bool {
+        matches!(self, Self::Completed | Self::Cancelled | Self::Failed)
+    }
+}
+
+///
+/// JobStage
+///
+/// Prepare : one-off work before rows are processed, e.g. clearing old index entries
+/// Process : walking the rows from `cursor`
+///
+
+#[derive(CandidType, Clone, Copy, Debug, Deserialize, Display, Eq, PartialEq, Serialize)]
+pub enum JobStage {
+    Prepare,
+    Process,
+}
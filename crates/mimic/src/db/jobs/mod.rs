@@ -0,0 +1,127 @@
+mod job;
+mod runner;
+mod store;
+
+pub use job::*;
+pub use runner::*;
+pub use store::*;
+
+use crate::{
+    Error,
+    core::traits::CanisterKind,
+    db::{Db, DbError, primitives::BudgetExpr},
+};
+use canic::utils::time;
+use thiserror::Error as ThisError;
+
+///
+/// JobError
+///
+
+#[derive(Debug, ThisError)]
+pub enum JobError {
+    #[error("job {0} not found")]
+    JobNotFound(u64),
+
+    #[error("job {0} is {1}, cannot {2}")]
+    InvalidTransition(u64, JobStatus, String),
+
+    #[error("entity '{0}' has no job runner in this canister")]
+    UnknownEntity(String),
+}
+
+impl From<JobError> for Error {
+    fn from(err: JobError) -> Self {
+        DbError::from(err).into()
+    }
+}
+
+/// Instructions a single scheduled chunk may use, well under the message limit.
+pub const CHUNK_INSTRUCTIONS: u64 = 4_000_000_000;
+
+/// Every job, oldest first.
+pub fn list<C: CanisterKind>(db: Db<C>) -> Result<Vec<Job>, Error> {
+    db.with_job_store(|jobs| jobs.list())
+}
+
+/// Queue a new running job, checking that `runners` can handle its entity.
+pub fn start<C: CanisterKind>(
+    db: Db<C>,
+    runners: &[(&str, JobRunner<C>)],
+    kind: JobKind,
+) -> Result<Job, Error> {
+    if !runners.iter().any(|(path, _)| *path == kind.entity()) {
+        Err(JobError::UnknownEntity(kind.entity().to_string()))?;
+    }
+
+    db.with_job_store(|jobs| jobs.create(kind, time::now_secs()))
+}
+
+/// Stop running a job until it is resumed.
+pub fn pause<C: CanisterKind>(db: Db<C>, id: u64) -> Result<Job, Error> {
+    transition(db, id, "pause", |status| {
+        (status == JobStatus::Running).then_some(JobStatus::Paused)
+    })
+}
+
+/// Carry on with a paused job from where it stopped.
+pub fn resume<C: CanisterKind>(db: Db<C>, id: u64) -> Result<Job, Error> {
+    transition(db, id, "resume", |status| {
+        (status == JobStatus::Paused).then_some(JobStatus::Running)
+    })
+}
+
+/// Stop a job for good. Rows it already processed stay processed.
+pub fn cancel<C: CanisterKind>(db: Db<C>, id: u64) -> Result<Job, Error> {
+    transition(db, id, "cancel", |status| {
+        (!status.is_finished()).then_some(JobStatus::Cancelled)
+    })
+}
+
+/// Run one chunk of the running job that has had the fewest chunks so far.
+/// Returns whether any job is still running afterwards.
+pub fn step<C: CanisterKind>(
+    db: Db<C>,
+    runners: &[(&str, JobRunner<C>)],
+    budget: &BudgetExpr,
+) -> Result<bool, Error> {
+    let Some(mut job) = db.with_job_store(|jobs| jobs.next_running())? else {
+        return Ok(false);
+    };
+
+    if let Some((_, runner)) = runners.iter().find(|(path, _)| *path == job.kind.entity()) {
+        match runner(db, &mut job, budget) {
+            Ok(()) => job.failures = 0,
+            Err(e) => job.record_error(e),
+        }
+    } else {
+        job.record_error(JobError::UnknownEntity(job.kind.entity().to_string()));
+        job.status = JobStatus::Failed;
+    }
+    job.chunks += 1;
+    job.updated_at = time::now_secs();
+
+    db.with_job_store(|jobs| {
+        jobs.insert(job.id, job);
+
+        jobs.next_running().is_some()
+    })
+}
+
+// transition
+fn transition<C: CanisterKind>(
+    db: Db<C>,
+    id: u64,
+    action: &str,
+    next: impl FnOnce(JobStatus) -> Option<JobStatus>,
+) -> Result<Job, Error> {
+    db.with_job_store(|jobs| {
+        let mut job = jobs.get(&id).ok_or(JobError::JobNotFound(id))?;
+        job.status = next(job.status)
+            .ok_or_else(|| JobError::InvalidTransition(id, job.status, action.to_string()))?;
+        job.updated_at = time::now_secs();
+        jobs.insert(id, job.clone());
+
+        Ok(job)
+    })?
+}
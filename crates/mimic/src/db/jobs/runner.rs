@@ -0,0 +1,189 @@
+use crate::{
+    Error,
+    core::traits::EntityKind,
+    db::{
+        Db,
+        executor::DeleteExecutor,
+        jobs::{Job, JobKind, JobStage, JobStatus},
+        primitives::{BudgetExpr, BudgetMeter, FilterExt, ResumeToken},
+        query::{DeleteQuery, QueryError, QueryPlan},
+        store::{IndexId, decode_row, migrate_row, row_version},
+    },
+    obs::metrics,
+    types::Timestamp,
+};
+use std::collections::HashSet;
+
+///
+/// JobRunner
+///
+/// Runs one chunk of a job against a concrete entity type, see [`run_chunk`].
+///
+
+pub type JobRunner<C> = fn(Db<C>, &mut Job, &BudgetExpr) -> Result<(), Error>;
+
+/// Run one chunk of `job` for entity `E`, spending at most `budget` and
+/// carrying on from the job's cursor.
+pub fn run_chunk<E: EntityKind>(
+    db: Db<E::Canister>,
+    job: &mut Job,
+    budget: &BudgetExpr,
+) -> Result<(), Error> {
+    let budget = BudgetExpr {
+        resume: job.cursor.map(ResumeToken),
+        ..budget.clone()
+    };
+
+    match &job.kind {
+        JobKind::Reindex { .. } => reindex::<E>(db, job, &budget),
+        JobKind::Migrate { .. } => migrate::<E>(db, job, &budget),
+
+        JobKind::Delete { query, .. } => {
+            let query = DeleteQuery {
+                limit: None,
+                budget: Some(budget),
+                ..(**query).clone()
+            };
+            let res = DeleteExecutor::<E>::new(db, false).execute(query)?;
+            advance(job, res.count().into(), res.resume());
+
+            Ok(())
+        }
+
+        JobKind::TtlSweep { .. } => {
+            let field = E::TTL_FIELD.ok_or_else(|| QueryError::TtlDisabled(E::PATH.to_string()))?;
+            let mut query = DeleteQuery::new().filter(|f| f.lte(field, Timestamp::now()));
+            query.budget = Some(budget);
+
+            let res = DeleteExecutor::<E>::new(db, false).purge().execute(query)?;
+            metrics::with_state_mut(|m| metrics::record_ttl_sweep_for::<E>(m, res.count().into()));
+            advance(job, res.count().into(), res.resume());
+
+            Ok(())
+        }
+    }
+}
+
+// advance
+// moves the cursor on, finishing the job once there's nothing left to resume
+fn advance(job: &mut Job, rows: u64, resume: Option<ResumeToken>) {
+    job.rows += rows;
+    job.cursor = resume.map(|token| token.0);
+
+    if job.cursor.is_none() {
+        job.status = JobStatus::Completed;
+    }
+}
+
+// reindex
+// clears the entity's index entries, then re-inserts them row by row
+fn reindex<E: EntityKind>(
+    db: Db<E::Canister>,
+    job: &mut Job,
+    budget: &BudgetExpr,
+) -> Result<(), Error> {
+    let mut meter = BudgetMeter::new(Some(budget));
+
+    let stores = E::INDEXES
+        .iter()
+        .map(|index| {
+            db.with_index(|reg| reg.try_get_store(index.store))
+                .map(|store| (store, *index))
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+
+    if job.stage == JobStage::Prepare {
+        for (store, index) in &stores {
+            let id = IndexId::new::<E>(index);
+            if !store.with_borrow_mut(|s| s.clear_index(id, &mut meter)) {
+                return Ok(());
+            }
+        }
+        job.stage = JobStage::Process;
+    }
+
+    // the scan itself is unbounded, each row charges its scan and index work to
+    // the meter and stops the chunk once it's spent
+    let mut rows = 0;
+    let per_row = 1 + u32::try_from(stores.len()).unwrap_or(u32::MAX);
+    let resume = db.context::<E>().scan(
+        QueryPlan::FullScan,
+        job.cursor,
+        &mut BudgetMeter::new(None),
+        |_, bytes| {
+            let entity = decode_row::<E>(bytes)?;
+            for (store, index) in &stores {
+                store.with_borrow_mut(|s| s.insert_index_entry(&entity, index))?;
+            }
+            rows += 1;
+            meter.charge(per_row);
+
+            Ok(!meter.is_spent())
+        },
+    )?;
+    advance(job, rows, resume);
+
+    Ok(())
+}
+
+// migrate
+// rewrites rows stored at an older schema version, the change log and history
+// are left alone as the row's value hasn't changed
+fn migrate<E: EntityKind>(
+    db: Db<E::Canister>,
+    job: &mut Job,
+    budget: &BudgetExpr,
+) -> Result<(), Error> {
+    let mut meter = BudgetMeter::new(Some(budget));
+    let ctx = db.context::<E>();
+
+    // collect first, the data store is borrowed during the scan, but charge the
+    // index and write work that follows to the meter as each row is collected
+    let mut rows = 0;
+    let mut migrated = Vec::new();
+    let mut stale = HashSet::new();
+    let indexes = u32::try_from(E::INDEXES.len()).unwrap_or(u32::MAX);
+    let resume = ctx.scan(
+        QueryPlan::FullScan,
+        job.cursor,
+        &mut BudgetMeter::new(None),
+        |dk, bytes| {
+            let mut work = 1;
+
+            // rows only re-encoded with another codec keep their indexed values
+            if row_version(bytes).0 != E::SCHEMA_VERSION {
+                stale.insert(dk.key());
+                work += indexes;
+            }
+            if let Some(bytes) = migrate_row::<E>(bytes)? {
+                migrated.push((dk.clone(), bytes));
+                work += indexes + 1;
+            }
+            rows += 1;
+            meter.charge(work);
+
+            Ok(!meter.is_spent())
+        },
+    )?;
+
+    // a migration can change indexed values, and the old ones can't be rebuilt,
+    // so drop every entry of these rows before adding the new ones
+    if !stale.is_empty() {
+        for index in E::INDEXES {
+            let store = db.with_index(|reg| reg.try_get_store(index.store))?;
+            store.with_borrow_mut(|s| s.remove_keys::<E>(index, &stale));
+        }
+    }
+
+    for (dk, bytes) in migrated {
+        let entity = decode_row::<E>(&bytes)?;
+        for index in E::INDEXES {
+            let store = db.with_index(|reg| reg.try_get_store(index.store))?;
+            store.with_borrow_mut(|s| s.insert_index_entry(&entity, index))?;
+        }
+        ctx.with_store_mut(|s| s.insert(dk, bytes))?;
+    }
+    advance(job, rows, resume);
+
+    Ok(())
+}
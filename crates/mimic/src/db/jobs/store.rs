@@ -0,0 +1,106 @@
+use crate::db::jobs::{Job, JobKind, JobStatus};
+use canic::cdk::structures::{BTreeMap, DefaultMemoryImpl, memory::VirtualMemory};
+use derive_more::{Deref, DerefMut};
+
+///
+/// JobStore
+///
+/// Every job ever started, keyed by id. Finished jobs are kept so their
+/// outcome can still be read.
+///
+
+#[derive(Deref, DerefMut)]
+pub struct JobStore(BTreeMap<u64, Job, VirtualMemory<DefaultMemoryImpl>>);
+
+impl JobStore {
+    #[must_use]
+    pub fn init(memory: VirtualMemory<DefaultMemoryImpl>) -> Self {
+        Self(BTreeMap::init(memory))
+    }
+
+    /// Create a running job and return it.
+    pub fn create(&mut self, kind: JobKind, now: u64) -> Job {
+        let id = self.last_key_value().map_or(1, |(id, _)| id + 1);
+        let job = Job::new(id, kind, now);
+        self.insert(id, job.clone());
+
+        job
+    }
+
+    #[must_use]
+    pub fn list(&self) -> Vec<Job> {
+        self.iter().map(|entry| entry.value()).collect()
+    }
+
+    /// The running job that has had the fewest chunks, so jobs take turns.
+    #[must_use]
+    pub fn next_running(&self) -> Option<Job> {
+        self.iter()
+            .map(|entry| entry.value())
+            .filter(|job| job.status == JobStatus::Running)
+            .min_by_key(|job| (job.chunks, job.id))
+    }
+}
+
+///
+/// TESTS
+///
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::query::DeleteQuery;
+    use canic::cdk::structures::memory::{MemoryId, MemoryManager};
+
+    fn store() -> JobStore {
+        let manager = MemoryManager::init(DefaultMemoryImpl::default());
+
+        JobStore::init(manager.get(MemoryId::new(0)))
+    }
+
+    fn kind() -> JobKind {
+        JobKind::Delete {
+            entity: "a".to_string(),
+            query: Box::new(DeleteQuery::new()),
+        }
+    }
+
+    #[test]
+    fn create_assigns_sequential_ids() {
+        let mut jobs = store();
+
+        assert_eq!(jobs.create(kind(), 0).id, 1);
+        assert_eq!(jobs.create(kind(), 0).id, 2);
+        assert_eq!(jobs.list().len(), 2);
+    }
+
+    #[test]
+    fn next_running_takes_turns() {
+        let mut jobs = store();
+        let mut first = jobs.create(kind(), 0);
+        let second = jobs.create(kind(), 0);
+
+        assert_eq!(jobs.next_running().map(|j| j.id), Some(first.id));
+
+        first.chunks = 1;
+        jobs.insert(first.id, first.clone());
+        assert_eq!(jobs.next_running().map(|j| j.id), Some(second.id));
+
+        let mut paused = second;
+        paused.status = JobStatus::Paused;
+        jobs.insert(paused.id, paused);
+        assert_eq!(jobs.next_running().map(|j| j.id), Some(first.id));
+    }
+
+    #[test]
+    fn repeated_failures_fail_the_job() {
+        let mut job = Job::new(1, kind(), 0);
+        for _ in 0..Job::MAX_FAILURES {
+            assert_eq!(job.status, JobStatus::Running);
+            job.record_error("boom");
+        }
+
+        assert_eq!(job.status, JobStatus::Failed);
+        assert_eq!(job.errors.len(), Job::MAX_FAILURES as usize);
+    }
+}
@@ -1,4 +1,5 @@
 pub mod executor;
+pub mod jobs;
 pub mod primitives;
 pub mod query;
 pub mod response;
@@ -7,17 +8,22 @@ pub mod store;
 use crate::{
     Error,
     core::{
-        SerializeError, ValidateError,
-        traits::{CanisterKind, EntityKind},
+        Key, SerializeError, ValidateError,
+        traits::{CanisterKind, EntityKind, FieldValue, UpdateView},
     },
     db::{
         executor::{Context, DeleteExecutor, ExecutorError, LoadExecutor, SaveExecutor},
+        jobs::{JobError, JobStore},
         query::QueryError,
-        response::ResponseError,
-        store::{DataStoreRegistry, IndexStoreRegistry, StoreError},
+        response::{Response, ResponseError},
+        store::{
+            ChangeLogStore, ChangeOp, ChangePage, DataKey, DataStoreRegistry, HistoryRecord,
+            HistoryStore, IndexStoreRegistry, StoreError,
+        },
     },
+    types::Timestamp,
 };
-use std::{marker::PhantomData, thread::LocalKey};
+use std::{cell::RefCell, collections::BTreeMap, marker::PhantomData, thread::LocalKey};
 use thiserror::Error as ThisError;
 
 ///
@@ -29,6 +35,9 @@ pub enum DbError {
     #[error(transparent)]
     ExecutorError(#[from] ExecutorError),
 
+    #[error(transparent)]
+    JobError(#[from] JobError),
+
     #[error(transparent)]
     QueryError(#[from] QueryError),
 
@@ -57,8 +66,10 @@ pub enum DbError {
 ///
 
 pub struct Db<C: CanisterKind> {
-    data: &'static LocalKey<DataStoreRegistry>,
-    index: &'static LocalKey<IndexStoreRegistry>,
+    registries: Registries,
+    change_log: Option<&'static LocalKey<RefCell<ChangeLogStore>>>,
+    history: Option<&'static LocalKey<RefCell<HistoryStore>>>,
+    jobs: Option<&'static LocalKey<RefCell<JobStore>>>,
     _marker: PhantomData<C>,
 }
 
@@ -69,12 +80,63 @@ impl<C: CanisterKind> Db<C> {
         index: &'static LocalKey<IndexStoreRegistry>,
     ) -> Self {
         Self {
-            data,
-            index,
+            registries: Registries::Local { data, index },
+            change_log: None,
+            history: None,
+            jobs: None,
             _marker: PhantomData,
         }
     }
 
+    /// A database whose data and index stores live on the heap and are created
+    /// as entities use them, so executors can run in plain `cargo test`
+    /// without `mimic_build` codegen. Change log, history and job stores
+    /// aren't available.
+    ///
+    /// Each call leaks a fresh set of stores, which the `Db` holds directly,
+    /// so it can't be sent to another thread; it's meant for tests, and needs
+    /// the `in-memory` feature.
+    #[cfg(any(test, feature = "in-memory"))]
+    #[must_use]
+    pub fn in_memory() -> Self {
+        let registries = Box::leak(Box::new((
+            DataStoreRegistry::in_memory(),
+            IndexStoreRegistry::in_memory(),
+        )));
+
+        Self {
+            registries: Registries::Heap(registries),
+            change_log: None,
+            history: None,
+            jobs: None,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Record every mutation in the given change log store.
+    #[must_use]
+    pub const fn with_change_log(
+        mut self,
+        change_log: &'static LocalKey<RefCell<ChangeLogStore>>,
+    ) -> Self {
+        self.change_log = Some(change_log);
+        self
+    }
+
+    /// Keep prior versions of `history` entities in the given history store.
+    #[must_use]
+    pub const fn with_history(mut self, history: &'static LocalKey<RefCell<HistoryStore>>) -> Self {
+        self.history = Some(history);
+        self
+    }
+
+    /// Keep maintenance job state in the given job store.
+    #[must_use]
+    pub const fn with_jobs(mut self, jobs: &'static LocalKey<RefCell<JobStore>>) -> Self {
+        self.jobs = Some(jobs);
+        self
+    }
+
     #[must_use]
     pub const fn context<E>(&self) -> Context<'_, E>
     where
@@ -84,16 +146,73 @@ impl<C: CanisterKind> Db<C> {
     }
 
     pub fn with_data<R>(&self, f: impl FnOnce(&DataStoreRegistry) -> R) -> R {
-        self.data.with(|reg| f(reg))
+        match self.registries {
+            Registries::Local { data, .. } => data.with(|reg| f(reg)),
+            #[cfg(any(test, feature = "in-memory"))]
+            Registries::Heap((data, _)) => f(data),
+        }
     }
 
     pub fn with_index<R>(&self, f: impl FnOnce(&IndexStoreRegistry) -> R) -> R {
-        self.index.with(|reg| f(reg))
+        match self.registries {
+            Registries::Local { index, .. } => index.with(|reg| f(reg)),
+            #[cfg(any(test, feature = "in-memory"))]
+            Registries::Heap((_, index)) => f(index),
+        }
+    }
+
+    /// Append a change for entity `E` to the change log, and keep the replaced
+    /// bytes as a prior version if `E` has history. Either is a no-op if the
+    /// store is missing.
+    pub fn record_change<E: EntityKind>(
+        &self,
+        op: ChangeOp,
+        key: Key,
+        old: Option<&[u8]>,
+        new: Option<&[u8]>,
+    ) {
+        if let Some(change_log) = self.change_log {
+            change_log.with_borrow_mut(|log| log.append(E::PATH, key, op, old, new));
+        }
+
+        if let (Some(limit), Some(history)) = (E::HISTORY_LIMIT, self.history) {
+            history.with_borrow_mut(|h| h.append(&DataKey::new::<E>(key), old, limit));
+        }
+    }
+
+    /// Retained prior versions of one `E` row, oldest first.
+    pub fn history<E: EntityKind>(&self, key: Key) -> Result<Vec<HistoryRecord>, Error> {
+        let history = self.history.ok_or(StoreError::HistoryNotFound)?;
+
+        Ok(history.with_borrow(|h| h.versions(&DataKey::new::<E>(key))))
+    }
+
+    /// State at `as_of` of every `E` row that has changed since, see [`HistoryStore::states_as_of`].
+    pub fn history_as_of<E: EntityKind>(
+        &self,
+        as_of: Timestamp,
+    ) -> Result<BTreeMap<Key, Option<Vec<u8>>>, Error> {
+        let history = self.history.ok_or(StoreError::HistoryNotFound)?;
+
+        Ok(history.with_borrow(|h| h.states_as_of(E::ENTITY_ID, as_of.get()))?)
+    }
+
+    pub fn with_job_store<R>(&self, f: impl FnOnce(&mut JobStore) -> R) -> Result<R, Error> {
+        let jobs = self.jobs.ok_or(StoreError::JobStoreNotFound)?;
+
+        Ok(jobs.with_borrow_mut(f))
+    }
+
+    /// Page through the change log, starting after sequence number `since`.
+    pub fn changes(&self, since: u64, limit: usize) -> Result<ChangePage, Error> {
+        let change_log = self.change_log.ok_or(StoreError::ChangeLogNotFound)?;
+
+        Ok(change_log.with_borrow(|log| log.page(since, limit)))
     }
 }
 
 // Manual Copy + Clone implementations.
-// Safe because Db only contains &'static LocalKey<_> handles,
+// Safe because Db only contains &'static handles,
 // duplicating them does not duplicate the contents.
 impl<C: CanisterKind> Copy for Db<C> {}
 
@@ -103,6 +222,23 @@ impl<C: CanisterKind> Clone for Db<C> {
     }
 }
 
+///
+/// Registries
+/// a canister's thread-local registries, or heap ones from `Db::in_memory`;
+/// the heap registries aren't `Sync`, so neither is a `Db` built with the
+/// `in-memory` feature and the generated `DB` is a `const`
+///
+
+#[derive(Clone, Copy)]
+enum Registries {
+    Local {
+        data: &'static LocalKey<DataStoreRegistry>,
+        index: &'static LocalKey<IndexStoreRegistry>,
+    },
+    #[cfg(any(test, feature = "in-memory"))]
+    Heap(&'static (DataStoreRegistry, IndexStoreRegistry)),
+}
+
 ///
 /// DbSession
 /// database plus a debug boolean, as we don't want to store the bool
@@ -159,6 +295,19 @@ impl<C: CanisterKind> DbSession<C> {
         DeleteExecutor::new(self.db, self.debug)
     }
 
+    //
+    // Relations
+    //
+
+    /// Load every `S` row that references the `T` row with primary key `key`.
+    pub fn referencing<S, T>(&self, key: impl FieldValue) -> Result<Response<S>, Error>
+    where
+        S: EntityKind<Canister = C>,
+        T: EntityKind,
+    {
+        self.load::<S>().referencing::<T>(key)
+    }
+
     //
     // High-level save shortcuts
     //
@@ -184,6 +333,22 @@ impl<C: CanisterKind> DbSession<C> {
         self.save::<E>().update(entity)
     }
 
+    /// Merge an update view into an existing row.
+    pub fn patch<E>(&self, key: impl Into<Key>, patch: E::UpdateViewType) -> Result<E, Error>
+    where
+        E: EntityKind<Canister = C> + UpdateView,
+    {
+        self.save::<E>().patch(key, patch)
+    }
+
+    /// Restore a soft deleted row.
+    pub fn restore<E>(&self, key: impl Into<Key>) -> Result<E, Error>
+    where
+        E: EntityKind<Canister = C>,
+    {
+        self.save::<E>().restore(key)
+    }
+
     pub fn insert_view<E>(&self, view: E::ViewType) -> Result<E::ViewType, Error>
     where
         E: EntityKind<Canister = C>,
@@ -0,0 +1,56 @@
+use crate::db::primitives::budget::{BudgetExpr, ResumeToken};
+
+///
+/// BudgetSlot
+///
+
+pub trait BudgetSlot {
+    fn budget_slot(&mut self) -> &mut Option<BudgetExpr>;
+}
+
+///
+/// BudgetExt
+///
+
+impl<T: BudgetSlot> BudgetExt for T {}
+
+pub trait BudgetExt: BudgetSlot + Sized {
+    /// Stop after scanning `rows` rows.
+    #[must_use]
+    fn max_rows(self, rows: u32) -> Self {
+        self.update_budget(|b| b.max_rows(rows))
+    }
+
+    /// Stop once the query has used `instructions` instructions.
+    #[must_use]
+    fn max_instructions(self, instructions: u64) -> Self {
+        self.update_budget(|b| b.max_instructions(instructions))
+    }
+
+    /// Continue a budgeted query from where the previous call stopped.
+    #[must_use]
+    fn resume(self, token: ResumeToken) -> Self {
+        self.update_budget(|b| b.resume(token))
+    }
+
+    #[must_use]
+    fn resume_opt(self, opt: Option<ResumeToken>) -> Self {
+        if let Some(token) = opt {
+            self.resume(token)
+        } else {
+            self
+        }
+    }
+
+    #[doc(hidden)]
+    #[must_use]
+    fn update_budget(self, f: impl FnOnce(BudgetExpr) -> BudgetExpr) -> Self {
+        let mut me = self;
+
+        let slot = me.budget_slot();
+        let expr = f(slot.take().unwrap_or_default());
+        *slot = Some(expr);
+
+        me
+    }
+}
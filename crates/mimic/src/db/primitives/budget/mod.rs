@@ -0,0 +1,162 @@
+mod ext;
+
+pub use ext::*;
+
+use crate::core::Key;
+use candid::CandidType;
+use canic::cdk::api::performance_counter;
+use serde::{Deserialize, Serialize};
+
+///
+/// BudgetExpr
+///
+/// Caps the work a single query call may do. Once either cap is reached the
+/// query stops and returns what it has so far, along with a [`ResumeToken`]
+/// to pass back through `resume` on the next call.
+///
+/// Instructions are counted from the start of the query, not the message.
+///
+
+#[derive(CandidType, Clone, Debug, Default, Deserialize, Serialize)]
+pub struct BudgetExpr {
+    pub max_rows: Option<u32>,
+    pub max_instructions: Option<u64>,
+    pub resume: Option<ResumeToken>,
+}
+
+impl BudgetExpr {
+    #[must_use]
+    pub const fn max_rows(mut self, rows: u32) -> Self {
+        self.max_rows = Some(rows);
+        self
+    }
+
+    #[must_use]
+    pub const fn max_instructions(mut self, instructions: u64) -> Self {
+        self.max_instructions = Some(instructions);
+        self
+    }
+
+    #[must_use]
+    pub const fn resume(mut self, token: ResumeToken) -> Self {
+        self.resume = Some(token);
+        self
+    }
+
+    #[must_use]
+    pub const fn resume_key(&self) -> Option<Key> {
+        match &self.resume {
+            Some(token) => Some(token.0),
+            None => None,
+        }
+    }
+}
+
+///
+/// ResumeToken
+///
+/// Primary key of the last row a budgeted query scanned; the next call
+/// continues from the row after it.
+///
+
+#[derive(CandidType, Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ResumeToken(pub Key);
+
+///
+/// BudgetMeter
+///
+/// Tracks a [`BudgetExpr`] while rows are scanned. Callers doing more than a
+/// read per row charge that work too, so `max_rows` bounds all of it.
+///
+
+#[derive(Debug)]
+pub struct BudgetMeter {
+    max_rows: Option<u32>,
+    max_instructions: Option<u64>,
+    rows: u32,
+    start: u64,
+}
+
+impl BudgetMeter {
+    #[must_use]
+    pub fn new(budget: Option<&BudgetExpr>) -> Self {
+        let max_rows = budget.and_then(|b| b.max_rows);
+        let max_instructions = budget.and_then(|b| b.max_instructions);
+
+        // only touch the counter when it's needed, it traps outside a canister
+        let start = if max_instructions.is_some() {
+            performance_counter(1)
+        } else {
+            0
+        };
+
+        Self {
+            max_rows,
+            max_instructions,
+            rows: 0,
+            start,
+        }
+    }
+
+    /// Count one scanned row.
+    pub const fn tick(&mut self) {
+        self.charge(1);
+    }
+
+    /// Count `units` of row-sized work, such as an index entry or a write.
+    pub const fn charge(&mut self, units: u32) {
+        self.rows = self.rows.saturating_add(units);
+    }
+
+    #[must_use]
+    pub fn is_spent(&self) -> bool {
+        if self.max_rows.is_some_and(|max| self.rows >= max) {
+            return true;
+        }
+
+        self.max_instructions
+            .is_some_and(|max| performance_counter(1).saturating_sub(self.start) >= max)
+    }
+}
+
+///
+/// TESTS
+///
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unbounded_meter_is_never_spent() {
+        let mut meter = BudgetMeter::new(None);
+        for _ in 0..1_000 {
+            meter.tick();
+        }
+
+        assert!(!meter.is_spent());
+    }
+
+    #[test]
+    fn row_budget_is_spent_after_max_rows() {
+        let budget = BudgetExpr::default().max_rows(2);
+        let mut meter = BudgetMeter::new(Some(&budget));
+
+        meter.tick();
+        assert!(!meter.is_spent());
+        meter.tick();
+        assert!(meter.is_spent());
+    }
+
+    #[test]
+    fn charged_work_counts_against_max_rows() {
+        let budget = BudgetExpr::default().max_rows(4);
+        let mut meter = BudgetMeter::new(Some(&budget));
+
+        meter.tick();
+        meter.charge(2);
+        assert!(!meter.is_spent());
+        meter.charge(1);
+        assert!(meter.is_spent());
+    }
+}
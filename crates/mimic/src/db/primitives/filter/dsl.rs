@@ -1,6 +1,6 @@
 use crate::{
     core::traits::FieldValue,
-    db::primitives::filter::{Cmp, FilterClause, FilterExpr},
+    db::primitives::filter::{Cmp, FieldCmpClause, FilterClause, FilterExpr},
 };
 
 ///
@@ -20,6 +20,16 @@ macro_rules! cmp_fns {
     }
 }
 
+macro_rules! field_cmp_fns {
+    ($( $name:ident => $cmp:ident ),*) => {
+        $(
+            pub fn $name(self, field: impl AsRef<str>, other: impl AsRef<str>) -> FilterExpr {
+                FilterExpr::FieldCmp(FieldCmpClause::new(field.as_ref(), Cmp::$cmp, other.as_ref()))
+            }
+        )*
+    }
+}
+
 impl FilterDsl {}
 
 impl FilterDsl {
@@ -44,6 +54,21 @@ impl FilterDsl {
         ends_with_ci => EndsWithCi
     }
 
+    //
+    // ───────────────────────────────────────────────
+    // FIELD COMPARATORS (field against another field)
+    // ───────────────────────────────────────────────
+    //
+
+    field_cmp_fns! {
+        eq_field => Eq,
+        ne_field => Ne,
+        lt_field => Lt,
+        lte_field => Lte,
+        gt_field => Gt,
+        gte_field => Gte
+    }
+
     //
     // ───────────────────────────────────────────────
     // LOGICAL COMBINATORS
@@ -15,6 +15,7 @@ use std::ops::{BitAnd, BitOr, Not};
 /// Expressions can be:
 /// - `True` or `False` constants
 /// - Single clauses comparing a field with a value
+/// - Field clauses comparing a field with another field
 /// - Composite expressions: `And`, `Or`, and negation `Not`.
 ///
 
@@ -24,9 +25,10 @@ pub enum FilterExpr {
     True,
     False,
     Clause(FilterClause),
-    And(Vec<FilterExpr>),
-    Or(Vec<FilterExpr>),
-    Not(Box<FilterExpr>),
+    FieldCmp(FieldCmpClause),
+    And(Vec<Self>),
+    Or(Vec<Self>),
+    Not(Box<Self>),
 }
 
 impl FilterExpr {
@@ -125,7 +127,7 @@ impl FilterExpr {
                     // De Morgan's: NOT(OR(...)) == AND(NOT(...))
                     Self::And(children.into_iter().map(|c| c.not().simplify()).collect())
                 }
-                x @ Self::Clause(_) => Self::Not(Box::new(x.simplify())),
+                x @ (Self::Clause(_) | Self::FieldCmp(_)) => Self::Not(Box::new(x.simplify())),
             },
 
             Self::And(children) => {
@@ -313,6 +315,29 @@ impl FilterClause {
     }
 }
 
+///
+/// FieldCmpClause
+/// compares two fields of the same row: `field cmp other`
+///
+
+#[derive(CandidType, Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct FieldCmpClause {
+    pub field: String,
+    pub cmp: Cmp,
+    pub other: String,
+}
+
+impl FieldCmpClause {
+    #[must_use]
+    pub fn new(field: &str, cmp: Cmp, other: &str) -> Self {
+        Self {
+            field: field.to_string(),
+            cmp,
+            other: other.to_string(),
+        }
+    }
+}
+
 ///
 /// TESTS
 ///
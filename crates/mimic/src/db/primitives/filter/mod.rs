@@ -2,10 +2,12 @@ mod cmp;
 mod dsl;
 mod expr;
 mod ext;
+mod syntax;
 mod types;
 
 pub use cmp::*;
 pub use dsl::*;
 pub use expr::*;
 pub use ext::*;
+pub use syntax::*;
 pub use types::*;
@@ -0,0 +1,1248 @@
+//! Text syntax for filters. `FilterExpr` parses from a compact expression
+//! language and `Display`s back into it, so the two round-trip.
+//!
+//! ```text
+//! level >= 2 and (name ~ "ncon" or tags any_in ["a", "b"]) and not deleted is_some
+//! ```
+//!
+//! - `or` binds looser than `and`, which binds looser than `not`; parentheses group
+//! - a clause is `field op value`, or `field op` for `is_none`, `is_some`, `is_empty`
+//!   and `is_not_empty`; a field is an identifier or a dotted path (`stats.level`),
+//!   anything else goes in backticks
+//! - the value can be another field instead, for `field op other`
+//!   (`current_hp < max_hp`); a field that reads as a value word goes in backticks
+//! - operators are `=`, `!=`, `<`, `<=`, `>`, `>=`, `~` (contains), or the
+//!   snake_case name of any `Cmp` (`eq`, `starts_with_ci`, `map_contains_entry`, ...)
+//! - values are `"text"`, `true`, `false`, `none`, `unit`, `unsupported`, numbers
+//!   (`5` is a `Uint`, `-5` and `+5` an `Int`, `1.5` a `Float64`), `0x..` blobs,
+//!   `[..]` lists, and `kind(..)` for every other `Value`, named after the variant:
+//!   `ulid("..")`, `principal("aaaaa-aa")`, `decimal(1.25)`, `timestamp(1700000000)`,
+//!   `date("2024-01-31")`, `enum("Path::Variant")`, `uint128(..)`, `int_big(..)`, ...
+//!
+//! `Display` prints an `And` or `Or` with fewer than two children as its
+//! simplest equivalent, so those don't survive a round trip structurally.
+
+use crate::{
+    core::value::{Value, ValueEnum},
+    db::primitives::filter::{Cmp, FieldCmpClause, FilterClause, FilterExpr},
+    types::{
+        Account, Date, Decimal, Duration, E8s, E18s, Float32, Float64, Int, Int128, Nat, Nat128,
+        Principal, Subaccount, Timestamp, Ulid,
+    },
+};
+use std::{
+    fmt::{self, Display, Write as _},
+    str::FromStr,
+};
+use thiserror::Error as ThisError;
+
+///
+/// FilterParseError
+/// `column` counts characters from 1
+///
+
+#[derive(Clone, Debug, Eq, PartialEq, ThisError)]
+#[error("{kind} at column {column}")]
+pub struct FilterParseError {
+    pub column: usize,
+    pub kind: FilterParseErrorKind,
+}
+
+impl FilterParseError {
+    fn new(src: &str, offset: usize, kind: FilterParseErrorKind) -> Self {
+        Self {
+            column: src[..offset].chars().count() + 1,
+            kind,
+        }
+    }
+}
+
+///
+/// FilterParseErrorKind
+///
+
+#[derive(Clone, Debug, Eq, PartialEq, ThisError)]
+pub enum FilterParseErrorKind {
+    #[error("unexpected character '{0}'")]
+    UnexpectedChar(char),
+
+    #[error("unterminated {0}")]
+    Unterminated(&'static str),
+
+    #[error("invalid escape '\\{0}'")]
+    InvalidEscape(char),
+
+    #[error("expected {expected}, found {found}")]
+    Expected {
+        expected: &'static str,
+        found: String,
+    },
+
+    #[error("unknown operator '{0}'")]
+    UnknownOperator(String),
+
+    #[error("unknown literal '{0}'")]
+    UnknownLiteral(String),
+
+    #[error("invalid {kind} literal '{text}'")]
+    InvalidLiteral { kind: String, text: String },
+}
+
+// words that can't be used as bare field names
+const RESERVED: [&str; 5] = ["and", "or", "not", "true", "false"];
+
+// bare words that are values
+const VALUE_WORDS: [&str; 5] = ["true", "false", "none", "unit", "unsupported"];
+
+// value kinds written as kind(..)
+const LITERAL_KINDS: [&str; 18] = [
+    "account",
+    "date",
+    "decimal",
+    "duration",
+    "e8s",
+    "e18s",
+    "enum",
+    "float32",
+    "float64",
+    "int",
+    "int128",
+    "int_big",
+    "principal",
+    "subaccount",
+    "timestamp",
+    "uint128",
+    "uint_big",
+    "ulid",
+];
+
+const CMP_WORDS: [(Cmp, &str); 31] = [
+    (Cmp::Eq, "eq"),
+    (Cmp::Ne, "ne"),
+    (Cmp::Lt, "lt"),
+    (Cmp::Lte, "lte"),
+    (Cmp::Gt, "gt"),
+    (Cmp::Gte, "gte"),
+    (Cmp::In, "in"),
+    (Cmp::NotIn, "not_in"),
+    (Cmp::AllIn, "all_in"),
+    (Cmp::AnyIn, "any_in"),
+    (Cmp::Contains, "contains"),
+    (Cmp::StartsWith, "starts_with"),
+    (Cmp::EndsWith, "ends_with"),
+    (Cmp::EqCi, "eq_ci"),
+    (Cmp::NeCi, "ne_ci"),
+    (Cmp::InCi, "in_ci"),
+    (Cmp::AnyInCi, "any_in_ci"),
+    (Cmp::AllInCi, "all_in_ci"),
+    (Cmp::ContainsCi, "contains_ci"),
+    (Cmp::StartsWithCi, "starts_with_ci"),
+    (Cmp::EndsWithCi, "ends_with_ci"),
+    (Cmp::IsNone, "is_none"),
+    (Cmp::IsSome, "is_some"),
+    (Cmp::IsEmpty, "is_empty"),
+    (Cmp::IsNotEmpty, "is_not_empty"),
+    (Cmp::MapContainsKey, "map_contains_key"),
+    (Cmp::MapContainsValue, "map_contains_value"),
+    (Cmp::MapContainsEntry, "map_contains_entry"),
+    (Cmp::MapNotContainsKey, "map_not_contains_key"),
+    (Cmp::MapNotContainsValue, "map_not_contains_value"),
+    (Cmp::MapNotContainsEntry, "map_not_contains_entry"),
+];
+
+// longer symbols first so `<=` isn't read as `<`
+const CMP_SYMBOLS: [(&str, Cmp); 8] = [
+    ("==", Cmp::Eq),
+    ("!=", Cmp::Ne),
+    ("<=", Cmp::Lte),
+    (">=", Cmp::Gte),
+    ("=", Cmp::Eq),
+    ("<", Cmp::Lt),
+    (">", Cmp::Gt),
+    ("~", Cmp::Contains),
+];
+
+// cmp_word
+fn cmp_word(cmp: Cmp) -> &'static str {
+    CMP_WORDS
+        .iter()
+        .find_map(|(c, word)| (*c == cmp).then_some(*word))
+        .expect("every Cmp has a word")
+}
+
+// cmp_from_word
+fn cmp_from_word(word: &str) -> Option<Cmp> {
+    CMP_WORDS
+        .iter()
+        .find_map(|(cmp, w)| (*w == word).then_some(*cmp))
+}
+
+// is_unary
+// comparators whose right-hand side is `Value::Unit` and may be left out
+const fn is_unary(cmp: Cmp) -> bool {
+    matches!(
+        cmp,
+        Cmp::IsNone | Cmp::IsSome | Cmp::IsEmpty | Cmp::IsNotEmpty
+    )
+}
+
+// is_ident
+// a plain identifier, or a dotted path of them (`stats.level`, `tags.0`)
+fn is_ident(s: &str) -> bool {
+    let word = |part: &str| !part.is_empty() && part.chars().all(is_word_char);
+
+    s.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_') && s.split('.').all(word)
+}
+
+// is_word_char
+const fn is_word_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_'
+}
+
+///
+/// DISPLAY
+///
+
+impl Display for Cmp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            Self::Eq => "=",
+            Self::Ne => "!=",
+            Self::Lt => "<",
+            Self::Lte => "<=",
+            Self::Gt => ">",
+            Self::Gte => ">=",
+            Self::Contains => "~",
+            _ => cmp_word(*self),
+        };
+
+        f.write_str(s)
+    }
+}
+
+impl Display for FilterClause {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", FieldName(&self.field), self.cmp)?;
+
+        if is_unary(self.cmp) && self.value == Value::Unit {
+            Ok(())
+        } else {
+            write!(f, " {}", Literal(&self.value))
+        }
+    }
+}
+
+impl Display for FieldCmpClause {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} {}",
+            FieldName(&self.field),
+            self.cmp,
+            FieldName(&self.other)
+        )
+    }
+}
+
+impl Display for FilterExpr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::True => f.write_str("true"),
+            Self::False => f.write_str("false"),
+            Self::Clause(clause) => clause.fmt(f),
+            Self::FieldCmp(clause) => clause.fmt(f),
+            Self::And(children) => write_joined(f, children, "and"),
+            Self::Or(children) => write_joined(f, children, "or"),
+            Self::Not(inner) if is_compound(inner) => write!(f, "not ({inner})"),
+            Self::Not(inner) => write!(f, "not {inner}"),
+        }
+    }
+}
+
+// unwrap_single
+// an And/Or with one child displays as that child
+fn unwrap_single(expr: &FilterExpr) -> &FilterExpr {
+    match expr {
+        FilterExpr::And(children) | FilterExpr::Or(children) if children.len() == 1 => {
+            unwrap_single(&children[0])
+        }
+        _ => expr,
+    }
+}
+
+// is_compound
+fn is_compound(expr: &FilterExpr) -> bool {
+    matches!(
+        unwrap_single(expr),
+        FilterExpr::And(children) | FilterExpr::Or(children) if children.len() > 1
+    )
+}
+
+// write_joined
+// nested groups are parenthesised, except an `and` inside an `or`, which
+// precedence already groups
+fn write_joined(f: &mut fmt::Formatter<'_>, children: &[FilterExpr], op: &str) -> fmt::Result {
+    match children {
+        [] if op == "and" => f.write_str("true"),
+        [] => f.write_str("false"),
+        [child] => child.fmt(f),
+        _ => {
+            for (i, child) in children.iter().enumerate() {
+                if i > 0 {
+                    write!(f, " {op} ")?;
+                }
+
+                let group = is_compound(child)
+                    && (op == "and" || matches!(unwrap_single(child), FilterExpr::Or(_)));
+                if group {
+                    write!(f, "({child})")?;
+                } else {
+                    child.fmt(f)?;
+                }
+            }
+
+            Ok(())
+        }
+    }
+}
+
+///
+/// FieldName
+/// a field, bare when it can't be read as anything else
+///
+
+struct FieldName<'a>(&'a str);
+
+impl Display for FieldName<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = self.0;
+
+        if is_ident(name) && !RESERVED.contains(&name) && !VALUE_WORDS.contains(&name) {
+            f.write_str(name)
+        } else {
+            Quoted(name, '`').fmt(f)
+        }
+    }
+}
+
+///
+/// Quoted
+/// a string in `quote`s, with escapes
+///
+
+struct Quoted<'a>(&'a str, char);
+
+impl Display for Quoted<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Self(s, quote) = *self;
+
+        f.write_char(quote)?;
+        for c in s.chars() {
+            match c {
+                '\\' => f.write_str("\\\\")?,
+                '\n' => f.write_str("\\n")?,
+                '\r' => f.write_str("\\r")?,
+                '\t' => f.write_str("\\t")?,
+                c if c == quote => write!(f, "\\{c}")?,
+                c if c.is_control() => write!(f, "\\u{{{:x}}}", u32::from(c))?,
+                c => f.write_char(c)?,
+            }
+        }
+
+        f.write_char(quote)
+    }
+}
+
+///
+/// Literal
+/// a `Value` as it is written in a filter
+///
+
+struct Literal<'a>(&'a Value);
+
+impl Display for Literal<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            Value::Account(v) => write!(f, "account(\"{v}\")"),
+            Value::Blob(bytes) => {
+                f.write_str("0x")?;
+                bytes.iter().try_for_each(|b| write!(f, "{b:02x}"))
+            }
+            Value::Bool(v) => write!(f, "{v}"),
+            Value::Date(v) => write!(f, "date(\"{v}\")"),
+            Value::Decimal(v) => write!(f, "decimal({v})"),
+            Value::Duration(v) => write!(f, "duration({})", v.get()),
+            Value::Enum(v) if v.path.is_empty() => write!(f, "enum({})", Quoted(&v.variant, '"')),
+            Value::Enum(v) => {
+                let name = format!("{}::{}", v.path, v.variant);
+                write!(f, "enum({})", Quoted(&name, '"'))
+            }
+            Value::E8s(v) => write!(f, "e8s({})", v.get()),
+            Value::E18s(v) => write!(f, "e18s({})", v.get()),
+            Value::Float32(v) => write!(f, "float32({})", v.get()),
+            Value::Float64(v) => {
+                let s = v.get().to_string();
+                if s.contains(['.', 'e', 'E']) {
+                    f.write_str(&s)
+                } else {
+                    write!(f, "{s}.0")
+                }
+            }
+            Value::Int(v) => write!(f, "{v:+}"),
+            Value::Int128(v) => write!(f, "int128({})", v.get()),
+            Value::IntBig(v) => write!(f, "int_big({v})"),
+            Value::List(items) => {
+                f.write_char('[')?;
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(", ")?;
+                    }
+                    Literal(item).fmt(f)?;
+                }
+                f.write_char(']')
+            }
+            Value::None => f.write_str("none"),
+            Value::Principal(v) => write!(f, "principal(\"{v}\")"),
+            Value::Subaccount(v) => write!(f, "subaccount(\"{v}\")"),
+            Value::Text(s) => Quoted(s, '"').fmt(f),
+            Value::Timestamp(v) => write!(f, "timestamp({})", v.get()),
+            Value::Uint(v) => write!(f, "{v}"),
+            Value::Uint128(v) => write!(f, "uint128({})", v.get()),
+            Value::UintBig(v) => write!(f, "uint_big({v})"),
+            Value::Ulid(v) => write!(f, "ulid(\"{v}\")"),
+            Value::Unit => f.write_str("unit"),
+            Value::Unsupported => f.write_str("unsupported"),
+        }
+    }
+}
+
+///
+/// PARSING
+///
+
+impl FromStr for FilterExpr {
+    type Err = FilterParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let tokens = Lexer { src: s, pos: 0 }.tokens()?;
+        let mut parser = Parser {
+            src: s,
+            tokens,
+            pos: 0,
+        };
+
+        let expr = parser.or()?;
+        if *parser.peek() != Tok::End {
+            return Err(parser.unexpected("'and', 'or' or end of input"));
+        }
+
+        Ok(expr)
+    }
+}
+
+///
+/// Tok
+///
+
+#[derive(Clone, Debug, PartialEq)]
+enum Tok {
+    Ident(String),
+    Field(String),
+    Str(String),
+    Num(String),
+    Blob(Vec<u8>),
+    Op(Cmp),
+    LParen,
+    RParen,
+    LBracket,
+    RBracket,
+    Comma,
+    End,
+}
+
+impl Display for Tok {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Ident(s) | Self::Num(s) => write!(f, "'{s}'"),
+            Self::Field(s) => Quoted(s, '`').fmt(f),
+            Self::Str(_) => f.write_str("a string"),
+            Self::Blob(_) => f.write_str("a blob"),
+            Self::Op(cmp) => write!(f, "'{cmp}'"),
+            Self::LParen => f.write_str("'('"),
+            Self::RParen => f.write_str("')'"),
+            Self::LBracket => f.write_str("'['"),
+            Self::RBracket => f.write_str("']'"),
+            Self::Comma => f.write_str("','"),
+            Self::End => f.write_str("end of input"),
+        }
+    }
+}
+
+///
+/// Token
+/// `at` is a byte offset into the source
+///
+
+#[derive(Clone, Debug)]
+struct Token {
+    tok: Tok,
+    at: usize,
+}
+
+///
+/// Lexer
+///
+
+struct Lexer<'a> {
+    src: &'a str,
+    pos: usize,
+}
+
+impl Lexer<'_> {
+    fn rest(&self) -> &str {
+        &self.src[self.pos..]
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += c.len_utf8();
+
+        Some(c)
+    }
+
+    fn skip_while(&mut self, f: impl Fn(char) -> bool) -> &str {
+        let start = self.pos;
+        while self.peek().is_some_and(&f) {
+            self.bump();
+        }
+
+        &self.src[start..self.pos]
+    }
+
+    fn error(&self, at: usize, kind: FilterParseErrorKind) -> FilterParseError {
+        FilterParseError::new(self.src, at, kind)
+    }
+
+    fn tokens(mut self) -> Result<Vec<Token>, FilterParseError> {
+        let mut tokens = Vec::new();
+
+        loop {
+            self.skip_while(char::is_whitespace);
+            let at = self.pos;
+            let Some(c) = self.peek() else {
+                tokens.push(Token { tok: Tok::End, at });
+                return Ok(tokens);
+            };
+
+            let tok = match c {
+                '(' | ')' | '[' | ']' | ',' => {
+                    self.bump();
+                    match c {
+                        '(' => Tok::LParen,
+                        ')' => Tok::RParen,
+                        '[' => Tok::LBracket,
+                        ']' => Tok::RBracket,
+                        _ => Tok::Comma,
+                    }
+                }
+                '"' => Tok::Str(self.quoted('"', "string")?),
+                '`' => Tok::Field(self.quoted('`', "field name")?),
+                '+' | '-' if self.rest()[1..].starts_with(|c: char| c.is_ascii_digit()) => {
+                    self.number()?
+                }
+                c if c.is_ascii_digit() => self.number()?,
+                c if c.is_ascii_alphabetic() || c == '_' => Tok::Ident(self.word()),
+                c => {
+                    let (sym, cmp) = CMP_SYMBOLS
+                        .iter()
+                        .find(|(sym, _)| self.rest().starts_with(sym))
+                        .ok_or_else(|| self.error(at, FilterParseErrorKind::UnexpectedChar(c)))?;
+                    self.pos += sym.len();
+
+                    Tok::Op(*cmp)
+                }
+            };
+            tokens.push(Token { tok, at });
+        }
+    }
+
+    // word
+    // an identifier, with any `.segment`s that follow it
+    fn word(&mut self) -> String {
+        let start = self.pos;
+        loop {
+            self.skip_while(is_word_char);
+            if !(self.rest().starts_with('.') && self.rest()[1..].starts_with(is_word_char)) {
+                return self.src[start..self.pos].to_string();
+            }
+            self.bump();
+        }
+    }
+
+    // quoted
+    fn quoted(&mut self, quote: char, what: &'static str) -> Result<String, FilterParseError> {
+        let start = self.pos;
+        self.bump();
+
+        let mut out = String::new();
+        loop {
+            let at = self.pos;
+            match self.bump() {
+                None => {
+                    return Err(self.error(start, FilterParseErrorKind::Unterminated(what)));
+                }
+                Some('\\') => {
+                    let c = self
+                        .escape()
+                        .ok_or_else(|| self.error(at, self.escape_error(at)))?;
+                    out.push(c);
+                }
+                Some(c) if c == quote => return Ok(out),
+                Some(c) => out.push(c),
+            }
+        }
+    }
+
+    // escape
+    fn escape(&mut self) -> Option<char> {
+        match self.bump()? {
+            'n' => Some('\n'),
+            'r' => Some('\r'),
+            't' => Some('\t'),
+            '0' => Some('\0'),
+            c @ ('\\' | '"' | '`') => Some(c),
+            'u' => {
+                if self.bump()? != '{' {
+                    return None;
+                }
+                let hex = self.skip_while(|c| c.is_ascii_hexdigit()).to_string();
+                if self.bump()? != '}' {
+                    return None;
+                }
+
+                u32::from_str_radix(&hex, 16).ok().and_then(char::from_u32)
+            }
+            _ => None,
+        }
+    }
+
+    // escape_error
+    fn escape_error(&self, at: usize) -> FilterParseErrorKind {
+        match self.src[at + 1..].chars().next() {
+            Some(c) => FilterParseErrorKind::InvalidEscape(c),
+            None => FilterParseErrorKind::Unterminated("string"),
+        }
+    }
+
+    // number
+    // digits may be separated by underscores
+    fn number(&mut self) -> Result<Tok, FilterParseError> {
+        let at = self.pos;
+        let digits = |c: char| c.is_ascii_digit() || c == '_';
+
+        if self.rest().starts_with("0x") {
+            self.pos += 2;
+            let hex = self
+                .skip_while(|c| c.is_ascii_hexdigit() || c == '_')
+                .replace('_', "");
+
+            return decode_hex(&hex).map(Tok::Blob).ok_or_else(|| {
+                self.error(
+                    at,
+                    FilterParseErrorKind::InvalidLiteral {
+                        kind: "blob".to_string(),
+                        text: self.src[at..self.pos].to_string(),
+                    },
+                )
+            });
+        }
+
+        if matches!(self.peek(), Some('+' | '-')) {
+            self.bump();
+        }
+        self.skip_while(digits);
+        if self.peek() == Some('.') && self.rest()[1..].starts_with(|c: char| c.is_ascii_digit()) {
+            self.bump();
+            self.skip_while(digits);
+        }
+        if matches!(self.peek(), Some('e' | 'E')) {
+            let mark = self.pos;
+            self.bump();
+            if matches!(self.peek(), Some('+' | '-')) {
+                self.bump();
+            }
+            if self.skip_while(|c| c.is_ascii_digit()).is_empty() {
+                self.pos = mark;
+            }
+        }
+
+        Ok(Tok::Num(self.src[at..self.pos].to_string()))
+    }
+}
+
+///
+/// Parser
+/// recursive descent, one method per precedence level
+///
+
+struct Parser<'a> {
+    src: &'a str,
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> &Tok {
+        &self.tokens[self.pos].tok
+    }
+
+    fn peek_next(&self) -> &Tok {
+        let next = (self.pos + 1).min(self.tokens.len() - 1);
+
+        &self.tokens[next].tok
+    }
+
+    fn at(&self) -> usize {
+        self.tokens[self.pos].at
+    }
+
+    // advance
+    // never moves past the End token
+    fn advance(&mut self) -> Token {
+        let token = self.tokens[self.pos].clone();
+        if self.pos + 1 < self.tokens.len() {
+            self.pos += 1;
+        }
+
+        token
+    }
+
+    fn keyword(&mut self, word: &str) -> bool {
+        let found = matches!(self.peek(), Tok::Ident(w) if w == word);
+        if found {
+            self.advance();
+        }
+
+        found
+    }
+
+    fn expect(&mut self, tok: &Tok, expected: &'static str) -> Result<(), FilterParseError> {
+        if self.peek() == tok {
+            self.advance();
+            Ok(())
+        } else {
+            Err(self.unexpected(expected))
+        }
+    }
+
+    fn error(&self, at: usize, kind: FilterParseErrorKind) -> FilterParseError {
+        FilterParseError::new(self.src, at, kind)
+    }
+
+    fn unexpected(&self, expected: &'static str) -> FilterParseError {
+        self.error(
+            self.at(),
+            FilterParseErrorKind::Expected {
+                expected,
+                found: self.peek().to_string(),
+            },
+        )
+    }
+
+    fn or(&mut self) -> Result<FilterExpr, FilterParseError> {
+        let mut children = vec![self.and()?];
+        while self.keyword("or") {
+            children.push(self.and()?);
+        }
+
+        Ok(if children.len() == 1 {
+            children.remove(0)
+        } else {
+            FilterExpr::Or(children)
+        })
+    }
+
+    fn and(&mut self) -> Result<FilterExpr, FilterParseError> {
+        let mut children = vec![self.not()?];
+        while self.keyword("and") {
+            children.push(self.not()?);
+        }
+
+        Ok(if children.len() == 1 {
+            children.remove(0)
+        } else {
+            FilterExpr::And(children)
+        })
+    }
+
+    fn not(&mut self) -> Result<FilterExpr, FilterParseError> {
+        if self.keyword("not") {
+            Ok(FilterExpr::Not(Box::new(self.not()?)))
+        } else {
+            self.primary()
+        }
+    }
+
+    fn primary(&mut self) -> Result<FilterExpr, FilterParseError> {
+        match self.peek().clone() {
+            Tok::LParen => {
+                self.advance();
+                let expr = self.or()?;
+                self.expect(&Tok::RParen, "')'")?;
+
+                Ok(expr)
+            }
+            Tok::Ident(word) if word == "true" => {
+                self.advance();
+                Ok(FilterExpr::True)
+            }
+            Tok::Ident(word) if word == "false" => {
+                self.advance();
+                Ok(FilterExpr::False)
+            }
+            Tok::Ident(field) | Tok::Field(field) if !RESERVED.contains(&field.as_str()) => {
+                self.advance();
+                self.clause(field)
+            }
+            // a backticked keyword is still a field
+            Tok::Field(field) => {
+                self.advance();
+                self.clause(field)
+            }
+            _ => Err(self.unexpected("a field, 'not', 'true', 'false' or '('")),
+        }
+    }
+
+    fn clause(&mut self, field: String) -> Result<FilterExpr, FilterParseError> {
+        let cmp = match self.peek() {
+            Tok::Op(cmp) => *cmp,
+            Tok::Ident(word) => cmp_from_word(word).ok_or_else(|| {
+                self.error(
+                    self.at(),
+                    FilterParseErrorKind::UnknownOperator(word.clone()),
+                )
+            })?,
+            _ => return Err(self.unexpected("an operator")),
+        };
+        self.advance();
+
+        if !is_unary(cmp)
+            && let Some(other) = self.field_ref()
+        {
+            self.advance();
+            return Ok(FilterExpr::FieldCmp(FieldCmpClause { field, cmp, other }));
+        }
+
+        let value = if is_unary(cmp) && !self.starts_value() {
+            Value::Unit
+        } else {
+            self.value()?
+        };
+
+        Ok(FilterExpr::Clause(FilterClause { field, cmp, value }))
+    }
+
+    // field_ref
+    // a field on the right of a clause, where a value would otherwise go
+    fn field_ref(&self) -> Option<String> {
+        match self.peek() {
+            Tok::Field(field) => Some(field.clone()),
+            Tok::Ident(word)
+                if !RESERVED.contains(&word.as_str())
+                    && !VALUE_WORDS.contains(&word.as_str())
+                    && *self.peek_next() != Tok::LParen =>
+            {
+                Some(word.clone())
+            }
+            _ => None,
+        }
+    }
+
+    fn starts_value(&self) -> bool {
+        match self.peek() {
+            Tok::Str(_) | Tok::Num(_) | Tok::Blob(_) | Tok::LBracket => true,
+            Tok::Ident(word) => {
+                VALUE_WORDS.contains(&word.as_str()) || *self.peek_next() == Tok::LParen
+            }
+            _ => false,
+        }
+    }
+
+    fn value(&mut self) -> Result<Value, FilterParseError> {
+        if !matches!(
+            self.peek(),
+            Tok::Ident(_) | Tok::Str(_) | Tok::Num(_) | Tok::Blob(_) | Tok::LBracket
+        ) {
+            return Err(self.unexpected("a value"));
+        }
+        let Token { tok, at } = self.advance();
+
+        match tok {
+            Tok::Str(s) => Ok(Value::Text(s)),
+            Tok::Num(text) => parse_number(&text).ok_or_else(|| {
+                self.error(
+                    at,
+                    FilterParseErrorKind::InvalidLiteral {
+                        kind: "number".to_string(),
+                        text,
+                    },
+                )
+            }),
+            Tok::Blob(bytes) => Ok(Value::Blob(bytes)),
+            Tok::LBracket => self.list(),
+            Tok::Ident(word) => match word.as_str() {
+                "true" => Ok(Value::Bool(true)),
+                "false" => Ok(Value::Bool(false)),
+                "none" => Ok(Value::None),
+                "unit" => Ok(Value::Unit),
+                "unsupported" => Ok(Value::Unsupported),
+                kind if LITERAL_KINDS.contains(&kind) && *self.peek() == Tok::LParen => {
+                    self.typed(word)
+                }
+                _ => Err(self.error(at, FilterParseErrorKind::UnknownLiteral(word))),
+            },
+            _ => unreachable!("checked above"),
+        }
+    }
+
+    // list
+    // a trailing comma is allowed
+    fn list(&mut self) -> Result<Value, FilterParseError> {
+        let mut items = Vec::new();
+        while *self.peek() != Tok::RBracket {
+            items.push(self.value()?);
+
+            match self.peek() {
+                Tok::Comma => {
+                    self.advance();
+                }
+                Tok::RBracket => {}
+                _ => return Err(self.unexpected("',' or ']'")),
+            }
+        }
+        self.advance();
+
+        Ok(Value::List(items))
+    }
+
+    // typed
+    // kind(..), the argument a string or a number
+    fn typed(&mut self, kind: String) -> Result<Value, FilterParseError> {
+        self.advance();
+
+        let at = self.at();
+        let text = match self.peek() {
+            Tok::Str(s) | Tok::Num(s) => s.clone(),
+            _ => return Err(self.unexpected("a string or number")),
+        };
+        self.advance();
+        self.expect(&Tok::RParen, "')'")?;
+
+        parse_typed(&kind, &text)
+            .ok_or_else(|| self.error(at, FilterParseErrorKind::InvalidLiteral { kind, text }))
+    }
+}
+
+// parse_number
+// a bare number: Float64 with a fraction or exponent, Int with a sign, else Uint
+fn parse_number(text: &str) -> Option<Value> {
+    let n = text.replace('_', "");
+
+    if n.contains(['.', 'e', 'E']) {
+        Float64::try_new(n.parse().ok()?).map(Value::Float64)
+    } else if n.starts_with(['+', '-']) {
+        n.parse().ok().map(Value::Int)
+    } else {
+        n.parse().ok().map(Value::Uint)
+    }
+}
+
+// parse_typed
+fn parse_typed(kind: &str, text: &str) -> Option<Value> {
+    let n = text.replace('_', "");
+    let n = n.strip_prefix('+').unwrap_or(&n);
+
+    let value = match kind {
+        "account" => Value::Account(Account::from_str(text).ok()?),
+        "date" => Value::Date(Date::parse(text)?),
+        "decimal" => Value::Decimal(Decimal::from_str(n).ok()?),
+        "duration" => Value::Duration(Duration::from_str(n).ok()?),
+        "e8s" => Value::E8s(E8s::from_atomic(n.parse().ok()?)),
+        "e18s" => Value::E18s(E18s::from_atomic(n.parse().ok()?)),
+        "enum" => {
+            let (path, variant) = text.rsplit_once("::").unwrap_or(("", text));
+            Value::Enum(ValueEnum::new(path, variant))
+        }
+        "float32" => Value::Float32(Float32::try_new(n.parse().ok()?)?),
+        "float64" => Value::Float64(Float64::try_new(n.parse().ok()?)?),
+        "int" => Value::Int(n.parse().ok()?),
+        "int128" => Value::Int128(Int128::from_str(n).ok()?),
+        "int_big" => Value::IntBig(Int::from_str(n).ok()?),
+        "principal" => Value::Principal(Principal::from_str(text).ok()?),
+        "subaccount" => {
+            Value::Subaccount(Subaccount::from_array(decode_hex(text)?.try_into().ok()?))
+        }
+        "timestamp" => Value::Timestamp(Timestamp::from_str(n).ok()?),
+        "uint128" => Value::Uint128(Nat128::from_str(n).ok()?),
+        "uint_big" => Value::UintBig(Nat::from_str(n).ok()?),
+        "ulid" => Value::Ulid(Ulid::from_str(text).ok()?),
+        _ => return None,
+    };
+
+    Some(value)
+}
+
+// decode_hex
+fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) {
+        return None;
+    }
+
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+///
+/// TESTS
+///
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(s: &str) -> FilterExpr {
+        s.parse()
+            .unwrap_or_else(|e| panic!("failed to parse {s:?}: {e}"))
+    }
+
+    fn clause(field: &str, cmp: Cmp, value: Value) -> FilterExpr {
+        FilterExpr::Clause(FilterClause {
+            field: field.to_string(),
+            cmp,
+            value,
+        })
+    }
+
+    fn round_trip(expr: &FilterExpr) {
+        let text = expr.to_string();
+
+        assert_eq!(&parse(&text), expr, "round trip of {text:?}");
+    }
+
+    fn error(s: &str) -> FilterParseError {
+        s.parse::<FilterExpr>().unwrap_err()
+    }
+
+    #[test]
+    fn parses_example() {
+        let expr = parse(
+            r#"level >= 2 and (name ~ "ncon" or tags any_in ["a","b"]) and not deleted is_some"#,
+        );
+
+        assert_eq!(
+            expr,
+            FilterExpr::And(vec![
+                clause("level", Cmp::Gte, Value::Uint(2)),
+                FilterExpr::Or(vec![
+                    clause("name", Cmp::Contains, Value::Text("ncon".to_string())),
+                    clause("tags", Cmp::AnyIn, Value::from_list(&["a", "b"])),
+                ]),
+                FilterExpr::Not(Box::new(clause("deleted", Cmp::IsSome, Value::Unit))),
+            ])
+        );
+        assert_eq!(
+            expr.to_string(),
+            r#"level >= 2 and (name ~ "ncon" or tags any_in ["a", "b"]) and not deleted is_some"#
+        );
+    }
+
+    #[test]
+    fn every_cmp_round_trips() {
+        for (cmp, word) in CMP_WORDS {
+            let value = match cmp {
+                Cmp::MapContainsEntry | Cmp::MapNotContainsEntry => {
+                    Value::List(vec![Value::Text("k".to_string()), Value::Uint(1)])
+                }
+                cmp if is_unary(cmp) => Value::Unit,
+                _ => Value::Text("x".to_string()),
+            };
+            let expr = clause("f", cmp, value.clone());
+
+            round_trip(&expr);
+            let text = format!("f {word} {}", Literal(&value));
+            assert_eq!(parse(&text), expr, "{text}");
+        }
+
+        assert_eq!(parse("a == 1"), clause("a", Cmp::Eq, Value::Uint(1)));
+        assert_eq!(
+            parse("a is_some none"),
+            clause("a", Cmp::IsSome, Value::None)
+        );
+    }
+
+    #[test]
+    fn every_value_round_trips() {
+        let values = vec![
+            Value::Account(Account::dummy(1)),
+            Value::Blob(vec![0, 1, 254, 255]),
+            Value::Blob(vec![]),
+            Value::Bool(true),
+            Value::Bool(false),
+            Value::Date(Date::new(2024, 1, 31)),
+            Value::Decimal(Decimal::new(-12_345, 2)),
+            Value::Duration(Duration::from_secs(90)),
+            Value::Enum(ValueEnum::new("design::Color", "Red")),
+            Value::Enum(ValueEnum::new("", "Loose")),
+            Value::E8s(E8s::from_atomic(150_000_000)),
+            Value::E18s(E18s::from_atomic(u128::MAX)),
+            Value::Float32(Float32::try_new(1.5).unwrap()),
+            Value::Float64(Float64::try_new(2.0).unwrap()),
+            Value::Float64(Float64::try_new(-0.125).unwrap()),
+            Value::Float64(Float64::try_new(1e300).unwrap()),
+            Value::Int(-3),
+            Value::Int(4),
+            Value::Int128(Int128::from(i128::MIN)),
+            Value::IntBig(Int::from(-1_234_567)),
+            Value::List(vec![Value::Uint(1), Value::List(vec![]), Value::None]),
+            Value::None,
+            Value::Principal(Principal::dummy(2)),
+            Value::Subaccount(Subaccount::dummy(3)),
+            Value::Text("q\"uo`te\\ \n\t\r \u{1} é".to_string()),
+            Value::Timestamp(Timestamp::from(1_700_000_000)),
+            Value::Uint(u64::MAX),
+            Value::Uint128(Nat128::from(u128::MAX)),
+            Value::UintBig(Nat::from(9_876_543)),
+            Value::Ulid(Ulid::from_parts(1_700_000_000_000, 42)),
+            Value::Unit,
+            Value::Unsupported,
+        ];
+
+        for value in values {
+            round_trip(&clause("f", Cmp::Eq, value));
+        }
+    }
+
+    #[test]
+    fn precedence_and_grouping() {
+        let a = || clause("a", Cmp::Eq, Value::Uint(1));
+        let b = || clause("b", Cmp::Eq, Value::Uint(2));
+        let c = || clause("c", Cmp::Eq, Value::Uint(3));
+
+        assert_eq!(
+            parse("a = 1 or b = 2 and c = 3"),
+            FilterExpr::Or(vec![a(), FilterExpr::And(vec![b(), c()])])
+        );
+        assert_eq!(
+            parse("not a = 1 and b = 2"),
+            FilterExpr::And(vec![FilterExpr::Not(Box::new(a())), b()])
+        );
+
+        for expr in [
+            FilterExpr::And(vec![FilterExpr::Or(vec![a(), b()]), c()]),
+            FilterExpr::And(vec![FilterExpr::And(vec![a(), b()]), c()]),
+            FilterExpr::Or(vec![FilterExpr::Or(vec![a(), b()]), c()]),
+            FilterExpr::Not(Box::new(FilterExpr::Or(vec![a(), b()]))),
+            FilterExpr::Not(Box::new(FilterExpr::Not(Box::new(a())))),
+            FilterExpr::Or(vec![FilterExpr::True, FilterExpr::False]),
+        ] {
+            round_trip(&expr);
+        }
+
+        // single children display as the child, keeping the grouping right
+        let nested = FilterExpr::And(vec![
+            FilterExpr::And(vec![FilterExpr::Or(vec![a(), b()])]),
+            c(),
+        ]);
+        assert_eq!(nested.to_string(), "(a = 1 or b = 2) and c = 3");
+        assert_eq!(FilterExpr::And(vec![]).to_string(), "true");
+    }
+
+    #[test]
+    fn quoted_fields() {
+        let expr = clause("not", Cmp::IsNone, Value::Unit);
+        assert_eq!(expr.to_string(), "`not` is_none");
+        round_trip(&expr);
+
+        round_trip(&clause("with space", Cmp::Lt, Value::Int(-1)));
+        round_trip(&clause("trailing.", Cmp::Lt, Value::Int(-1)));
+
+        let expr = clause("stats.tags.0", Cmp::Eq, Value::Text("a".to_string()));
+        assert_eq!(expr.to_string(), r#"stats.tags.0 = "a""#);
+        round_trip(&expr);
+    }
+
+    #[test]
+    fn field_comparisons() {
+        let cmp = |field: &str, cmp: Cmp, other: &str| {
+            FilterExpr::FieldCmp(FieldCmpClause::new(field, cmp, other))
+        };
+
+        assert_eq!(
+            parse("current_hp < max_hp and ends_at > `starts_at`"),
+            FilterExpr::And(vec![
+                cmp("current_hp", Cmp::Lt, "max_hp"),
+                cmp("ends_at", Cmp::Gt, "starts_at"),
+            ])
+        );
+        assert_eq!(parse("a = date"), cmp("a", Cmp::Eq, "date"));
+        assert_eq!(parse("a = none"), clause("a", Cmp::Eq, Value::None));
+        assert_eq!(error("a is_none b").column, 11);
+
+        for expr in [
+            cmp("stats.level", Cmp::Gte, "point.0"),
+            cmp("a", Cmp::Eq, "none"),
+            cmp("unit", Cmp::Ne, "with space"),
+            FilterExpr::Not(Box::new(cmp("a", Cmp::StartsWithCi, "b"))),
+        ] {
+            round_trip(&expr);
+        }
+        assert_eq!(cmp("a", Cmp::Eq, "none").to_string(), "a = `none`");
+    }
+
+    #[test]
+    fn errors_have_columns() {
+        let err = error("a = 1 and (b = ");
+        assert_eq!(err.column, 16);
+        assert!(matches!(err.kind, FilterParseErrorKind::Expected { .. }));
+
+        let err = error("a is_bigger 1");
+        assert_eq!(err.column, 3);
+        assert_eq!(
+            err.kind,
+            FilterParseErrorKind::UnknownOperator("is_bigger".to_string())
+        );
+
+        let err = error(r#"é = "open"#);
+        assert_eq!(err.column, 1);
+        assert_eq!(err.kind, FilterParseErrorKind::UnexpectedChar('é'));
+
+        let err = error(r#"a = "open"#);
+        assert_eq!(err.column, 5);
+        assert_eq!(err.kind, FilterParseErrorKind::Unterminated("string"));
+
+        let err = error(r#"a = ulid("nope")"#);
+        assert_eq!(err.column, 10);
+        assert!(matches!(
+            err.kind,
+            FilterParseErrorKind::InvalidLiteral { ref kind, .. } if kind == "ulid"
+        ));
+
+        let err = error("a = 1 b = 2");
+        assert_eq!(err.column, 7);
+        assert_eq!(
+            err.to_string(),
+            "expected 'and', 'or' or end of input, found 'b' at column 7"
+        );
+
+        assert_eq!(error("a = [1 2]").column, 8);
+        assert_eq!(error("a = 0x123").column, 5);
+        assert_eq!(error(r#"a = "\q""#).column, 6);
+        assert_eq!(error("a = wat(1)").column, 5);
+    }
+}
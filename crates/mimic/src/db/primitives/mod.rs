@@ -1,7 +1,9 @@
+pub mod budget;
 pub mod filter;
 pub mod limit;
 pub mod sort;
 
+pub use budget::*;
 pub use filter::*;
 pub use limit::*;
 pub use sort::*;
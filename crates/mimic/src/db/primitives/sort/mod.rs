@@ -57,7 +57,7 @@ impl SortExpr {
 impl<E: EntityKind> QueryValidate<E> for SortExpr {
     fn validate(&self) -> Result<(), QueryError> {
         for (field, _) in self.iter() {
-            if !E::FIELDS.contains(&field.as_str()) {
+            if !E::has_path(field) {
                 return Err(QueryError::InvalidSortField(field.clone()));
             }
         }
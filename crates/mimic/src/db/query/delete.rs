@@ -1,7 +1,9 @@
 use crate::{
     core::traits::{EntityKind, FieldValue},
     db::{
-        primitives::{FilterExpr, FilterExt, FilterSlot, LimitExpr, LimitSlot},
+        primitives::{
+            BudgetExpr, BudgetSlot, FilterExpr, FilterExt, FilterSlot, LimitExpr, LimitSlot,
+        },
         query::{QueryError, QueryValidate},
     },
 };
@@ -17,6 +19,7 @@ use serde::Serialize;
 pub struct DeleteQuery {
     pub filter: Option<FilterExpr>,
     pub limit: Option<LimitExpr>,
+    pub budget: Option<BudgetExpr>,
 }
 
 impl DeleteQuery {
@@ -53,6 +56,12 @@ impl LimitSlot for DeleteQuery {
     }
 }
 
+impl BudgetSlot for DeleteQuery {
+    fn budget_slot(&mut self) -> &mut Option<BudgetExpr> {
+        &mut self.budget
+    }
+}
+
 impl<E: EntityKind> QueryValidate<E> for DeleteQuery {
     fn validate(&self) -> Result<(), QueryError> {
         if let Some(filter) = &self.filter {
@@ -1,13 +1,45 @@
 use crate::{
     core::traits::{EntityKind, FieldValue},
     db::{
-        primitives::{FilterExpr, FilterSlot, LimitExpr, LimitSlot, SortExpr, SortSlot},
+        primitives::{
+            BudgetExpr, BudgetSlot, FilterExpr, FilterSlot, LimitExpr, LimitSlot, SortExpr,
+            SortSlot,
+        },
         query::{QueryError, QueryValidate, prelude::*},
     },
+    types::Timestamp,
 };
 use candid::CandidType;
 use serde::{Deserialize, Serialize};
 
+///
+/// DeletedMode
+///
+/// Which rows of a soft delete entity a load returns.
+/// Exclude : only live rows
+/// Include : live and soft deleted rows
+/// Only    : only soft deleted rows
+///
+
+#[derive(CandidType, Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub enum DeletedMode {
+    #[default]
+    Exclude,
+    Include,
+    Only,
+}
+
+impl DeletedMode {
+    #[must_use]
+    pub const fn matches(self, deleted: bool) -> bool {
+        match self {
+            Self::Exclude => !deleted,
+            Self::Include => true,
+            Self::Only => deleted,
+        }
+    }
+}
+
 ///
 /// LoadQuery
 ///
@@ -17,6 +49,10 @@ pub struct LoadQuery {
     pub filter: Option<FilterExpr>,
     pub limit: Option<LimitExpr>,
     pub sort: Option<SortExpr>,
+    pub include: Vec<String>,
+    pub deleted: DeletedMode,
+    pub as_of: Option<Timestamp>,
+    pub budget: Option<BudgetExpr>,
 }
 
 impl LoadQuery {
@@ -27,7 +63,13 @@ impl LoadQuery {
 
     #[must_use]
     pub const fn is_empty(&self) -> bool {
-        self.filter.is_none() && self.limit.is_none() && self.sort.is_none()
+        self.filter.is_none()
+            && self.limit.is_none()
+            && self.sort.is_none()
+            && self.include.is_empty()
+            && matches!(self.deleted, DeletedMode::Exclude)
+            && self.as_of.is_none()
+            && self.budget.is_none()
     }
 
     ///
@@ -54,6 +96,50 @@ impl LoadQuery {
     pub fn all() -> Self {
         Self::default()
     }
+
+    ///
+    /// INCLUDES
+    ///
+
+    /// Eager-load the rows referenced by a relation field alongside the primary rows.
+    #[must_use]
+    pub fn include(mut self, field: impl AsRef<str>) -> Self {
+        let field = field.as_ref();
+        if !self.include.iter().any(|f| f == field) {
+            self.include.push(field.to_string());
+        }
+
+        self
+    }
+
+    ///
+    /// SOFT DELETE
+    ///
+
+    /// Return soft deleted rows alongside live ones.
+    #[must_use]
+    pub const fn with_deleted(mut self) -> Self {
+        self.deleted = DeletedMode::Include;
+        self
+    }
+
+    /// Return only soft deleted rows.
+    #[must_use]
+    pub const fn only_deleted(mut self) -> Self {
+        self.deleted = DeletedMode::Only;
+        self
+    }
+
+    ///
+    /// HISTORY
+    ///
+
+    /// Read rows as they were at `timestamp`, rebuilt from the entity's history.
+    #[must_use]
+    pub const fn as_of(mut self, timestamp: Timestamp) -> Self {
+        self.as_of = Some(timestamp);
+        self
+    }
 }
 
 impl FilterSlot for LoadQuery {
@@ -68,6 +154,12 @@ impl LimitSlot for LoadQuery {
     }
 }
 
+impl BudgetSlot for LoadQuery {
+    fn budget_slot(&mut self) -> &mut Option<BudgetExpr> {
+        &mut self.budget
+    }
+}
+
 impl SortSlot for LoadQuery {
     fn sort_slot(&mut self) -> &mut Option<SortExpr> {
         &mut self.sort
@@ -85,6 +177,30 @@ impl<E: EntityKind> QueryValidate<E> for LoadQuery {
         if let Some(sort) = &self.sort {
             QueryValidate::<E>::validate(sort)?;
         }
+        for field in &self.include {
+            if !E::RELATIONS.iter().any(|r| r.ident == field) {
+                return Err(QueryError::InvalidIncludeField(field.clone()));
+            }
+        }
+        if self.deleted != DeletedMode::Exclude && !E::SOFT_DELETE {
+            return Err(QueryError::SoftDeleteDisabled(E::PATH.to_string()));
+        }
+        if self.as_of.is_some() && E::HISTORY_LIMIT.is_none() {
+            return Err(QueryError::HistoryDisabled(E::PATH.to_string()));
+        }
+
+        // budgeted loads return rows in key order, a page at a time
+        if self.budget.is_some() {
+            if self.sort.is_some() {
+                return Err(QueryError::BudgetUnsupported("sort".to_string()));
+            }
+            if self.limit.as_ref().is_some_and(|l| l.offset > 0) {
+                return Err(QueryError::BudgetUnsupported("offset".to_string()));
+            }
+            if self.as_of.is_some() {
+                return Err(QueryError::BudgetUnsupported("as_of".to_string()));
+            }
+        }
 
         Ok(())
     }
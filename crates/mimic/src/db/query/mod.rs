@@ -15,6 +15,7 @@ pub use save::*;
 pub mod prelude {
     pub use crate::db::{
         primitives::{
+            budget::BudgetExt as _,
             filter::{FilterDsl, FilterExt as _},
             limit::LimitExt as _,
             sort::SortExt as _,
@@ -34,9 +35,15 @@ pub enum QueryError {
     #[error("invalid filter field '{0}'")]
     InvalidFilterField(String),
 
+    #[error("invalid include field '{0}'")]
+    InvalidIncludeField(String),
+
     #[error("invalid index field '{0}'")]
     InvalidIndexField(String),
 
+    #[error("entity '{0}' has no relation to '{1}'")]
+    InvalidRelation(String, String),
+
     #[error("invalid sort field '{0}'")]
     InvalidSortField(String),
 
@@ -45,6 +52,18 @@ pub enum QueryError {
 
     #[error("invalid comparator usage: {0}")]
     InvalidComparator(String),
+
+    #[error("soft delete is not enabled for entity '{0}'")]
+    SoftDeleteDisabled(String),
+
+    #[error("ttl is not enabled for entity '{0}'")]
+    TtlDisabled(String),
+
+    #[error("history is not enabled for entity '{0}'")]
+    HistoryDisabled(String),
+
+    #[error("budgeted queries cannot use {0}")]
+    BudgetUnsupported(String),
 }
 
 impl From<QueryError> for Error {
@@ -1,8 +1,17 @@
+mod page;
+mod related;
+mod version;
+
+pub use page::*;
+pub use related::*;
+pub use version::*;
+
 use crate::{
     Error, ThisError,
     core::{Key, traits::EntityKind},
-    db::DbError,
+    db::{DbError, primitives::ResumeToken},
 };
+use std::collections::HashMap;
 
 ///
 /// ResponseError
@@ -12,6 +21,12 @@ use crate::{
 pub enum ResponseError {
     #[error("expected one or more rows, found 0 (entity {0})")]
     NoRowsFound(String),
+
+    #[error("relation '{0}' was not included in the query")]
+    RelationNotIncluded(String),
+
+    #[error("relation '{0}' targets {1}, not {2}")]
+    RelationTargetMismatch(String, String, String),
 }
 
 impl From<ResponseError> for Error {
@@ -23,25 +38,53 @@ impl From<ResponseError> for Error {
 ///
 /// Response
 ///
+/// Primary rows plus any rows eager-loaded via `LoadQuery::include`, and a
+/// token to continue from if a budgeted query stopped early.
+///
 
 #[derive(Debug)]
-pub struct Response<E: EntityKind>(pub Vec<(Key, E)>);
+pub struct Response<E: EntityKind> {
+    pub rows: Vec<(Key, E)>,
+    pub related: Related,
+    pub resume: Option<ResumeToken>,
+}
 
 impl<E> Response<E>
 where
     E: EntityKind,
 {
+    #[must_use]
+    pub fn new(rows: Vec<(Key, E)>) -> Self {
+        Self {
+            rows,
+            related: Related::default(),
+            resume: None,
+        }
+    }
+
+    #[must_use]
+    pub fn with_related(mut self, related: Related) -> Self {
+        self.related = related;
+        self
+    }
+
+    #[must_use]
+    pub const fn with_resume(mut self, resume: Option<ResumeToken>) -> Self {
+        self.resume = resume;
+        self
+    }
+
     // count
     // not len, as it returns a u32 so could get confusing
     #[must_use]
     #[allow(clippy::cast_possible_truncation)]
     pub const fn count(&self) -> u32 {
-        self.0.len() as u32
+        self.rows.len() as u32
     }
 
     #[must_use]
     pub const fn is_empty(&self) -> bool {
-        self.0.is_empty()
+        self.rows.is_empty()
     }
 
     ///
@@ -50,7 +93,7 @@ where
 
     #[must_use]
     pub fn key(&self) -> Option<Key> {
-        self.0.first().map(|(key, _)| *key)
+        self.rows.first().map(|(key, _)| *key)
     }
 
     pub fn try_key(&self) -> Result<Key, Error> {
@@ -63,11 +106,11 @@ where
 
     #[must_use]
     pub fn keys(&self) -> Vec<Key> {
-        self.0.iter().map(|(key, _)| *key).collect()
+        self.rows.iter().map(|(key, _)| *key).collect()
     }
 
     pub fn keys_iter(self) -> impl Iterator<Item = Key> {
-        self.0.into_iter().map(|(key, _)| key)
+        self.rows.into_iter().map(|(key, _)| key)
     }
 
     ///
@@ -76,7 +119,7 @@ where
 
     #[must_use]
     pub fn pk(&self) -> Option<E::PrimaryKey> {
-        self.0.first().map(|(_, e)| e.primary_key())
+        self.rows.first().map(|(_, e)| e.primary_key())
     }
 
     pub fn try_pk(&self) -> Result<E::PrimaryKey, Error> {
@@ -89,11 +132,11 @@ where
 
     #[must_use]
     pub fn pks(&self) -> Vec<E::PrimaryKey> {
-        self.0.iter().map(|(_, e)| e.primary_key()).collect()
+        self.rows.iter().map(|(_, e)| e.primary_key()).collect()
     }
 
     pub fn pks_iter(self) -> impl Iterator<Item = E::PrimaryKey> {
-        self.0.into_iter().map(|(_, e)| e.primary_key())
+        self.rows.into_iter().map(|(_, e)| e.primary_key())
     }
 
     ///
@@ -102,7 +145,7 @@ where
 
     #[must_use]
     pub fn entity(self) -> Option<E> {
-        self.0.into_iter().next().map(|(_, e)| e)
+        self.rows.into_iter().next().map(|(_, e)| e)
     }
 
     pub fn try_entity(self) -> Result<E, Error> {
@@ -115,11 +158,11 @@ where
 
     #[must_use]
     pub fn entities(self) -> Vec<E> {
-        self.0.into_iter().map(|(_, e)| e).collect()
+        self.rows.into_iter().map(|(_, e)| e).collect()
     }
 
     pub fn entities_iter(self) -> impl Iterator<Item = E> {
-        self.0.into_iter().map(|(_, e)| e)
+        self.rows.into_iter().map(|(_, e)| e)
     }
 
     ///
@@ -143,6 +186,40 @@ where
     pub fn views_iter(self) -> impl Iterator<Item = E::ViewType> {
         self.entities().into_iter().map(|e| e.to_view())
     }
+
+    ///
+    /// Resume
+    ///
+
+    /// Where to continue a budgeted query, `None` once every row has been scanned.
+    #[must_use]
+    pub const fn resume(&self) -> Option<ResumeToken> {
+        self.resume
+    }
+
+    #[must_use]
+    pub const fn is_complete(&self) -> bool {
+        self.resume.is_none()
+    }
+
+    ///
+    /// Related
+    ///
+
+    #[must_use]
+    pub const fn related(&self) -> &Related {
+        &self.related
+    }
+
+    /// Decode the rows eager-loaded for relation `field` as entity `R`.
+    pub fn related_rows<R: EntityKind>(&self, field: &str) -> Result<Vec<(Key, R)>, Error> {
+        self.related.rows::<R>(field)
+    }
+
+    /// Related rows keyed by primary key, for joining back onto the primary rows.
+    pub fn related_map<R: EntityKind>(&self, field: &str) -> Result<HashMap<Key, R>, Error> {
+        Ok(self.related.rows::<R>(field)?.into_iter().collect())
+    }
 }
 
 impl<E: EntityKind> IntoIterator for Response<E> {
@@ -150,6 +227,6 @@ impl<E: EntityKind> IntoIterator for Response<E> {
     type IntoIter = std::vec::IntoIter<Self::Item>;
 
     fn into_iter(self) -> Self::IntoIter {
-        self.0.into_iter()
+        self.rows.into_iter()
     }
 }
@@ -0,0 +1,63 @@
+use crate::{
+    core::Key,
+    db::{primitives::ResumeToken, query::LoadQuery},
+};
+use candid::CandidType;
+use serde::{Deserialize, Serialize};
+
+///
+/// Page
+///
+/// One page of a load, as returned by `LoadExecutor::page`, with the number
+/// of rows the whole query matches and where the next page starts.
+/// `total` is `None` for budgeted loads, which stop before seeing every row.
+///
+
+#[derive(Debug)]
+pub struct Page<T> {
+    pub rows: Vec<(Key, T)>,
+    pub total: Option<u64>,
+    pub next: Option<NextPage>,
+}
+
+impl<T> Page<T> {
+    #[must_use]
+    pub fn map<U>(self, f: impl Fn(T) -> U) -> Page<U> {
+        Page {
+            rows: self.rows.into_iter().map(|(k, v)| (k, f(v))).collect(),
+            total: self.total,
+            next: self.next,
+        }
+    }
+
+    #[must_use]
+    pub const fn is_last(&self) -> bool {
+        self.next.is_none()
+    }
+}
+
+///
+/// NextPage
+///
+/// Where the page after this one starts: an offset for plain loads, or a
+/// resume token for budgeted ones. See [`LoadQuery::page`].
+///
+
+#[derive(CandidType, Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum NextPage {
+    Offset(u32),
+    Resume(ResumeToken),
+}
+
+impl LoadQuery {
+    /// The same query, moved on to `next`.
+    #[must_use]
+    pub fn page(mut self, next: NextPage) -> Self {
+        match next {
+            NextPage::Offset(offset) => self.limit.get_or_insert_default().offset = offset,
+            NextPage::Resume(token) => self.budget.get_or_insert_default().resume = Some(token),
+        }
+
+        self
+    }
+}
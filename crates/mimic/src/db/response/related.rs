@@ -0,0 +1,94 @@
+use crate::{
+    Error,
+    core::{Key, traits::EntityKind},
+    db::{
+        response::ResponseError,
+        store::{DataRow, decode_row},
+    },
+    types::Timestamp,
+};
+use std::collections::BTreeMap;
+
+///
+/// Related
+///
+/// Rows eager-loaded through `LoadQuery::include`, keyed by relation field.
+/// Rows are kept as raw bytes because the target type is only known by path
+/// at execution time; they are decoded on access via [`Related::rows`].
+///
+
+#[derive(Debug, Default)]
+pub struct Related(BTreeMap<String, RelatedRows>);
+
+impl Related {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&mut self, field: impl Into<String>, rows: RelatedRows) {
+        self.0.insert(field.into(), rows);
+    }
+
+    #[must_use]
+    pub fn get(&self, field: &str) -> Option<&RelatedRows> {
+        self.0.get(field)
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Decode the rows loaded for `field` as entity `R`, hiding soft deleted
+    /// and expired rows the same way a load of `R` would.
+    pub fn rows<R: EntityKind>(&self, field: &str) -> Result<Vec<(Key, R)>, Error> {
+        let related = self
+            .get(field)
+            .ok_or_else(|| ResponseError::RelationNotIncluded(field.to_string()))?;
+
+        if related.path != R::PATH {
+            return Err(ResponseError::RelationTargetMismatch(
+                field.to_string(),
+                related.path.to_string(),
+                R::PATH.to_string(),
+            ))?;
+        }
+
+        let mut rows = related
+            .rows
+            .iter()
+            .map(|(k, v)| decode_row::<R>(v).map(|entity| (k.key(), entity)))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if R::SOFT_DELETE {
+            rows.retain(|(_, e)| e.deleted_at().is_none());
+        }
+        if R::TTL_FIELD.is_some() {
+            rows.retain(|(_, e)| !e.is_expired(related.now));
+        }
+
+        Ok(rows)
+    }
+}
+
+///
+/// RelatedRows
+///
+/// `rows` are the stored rows as read, `now` is the time expiry is checked
+/// against (the query's `as_of`, if it had one).
+///
+
+#[derive(Debug)]
+pub struct RelatedRows {
+    pub path: &'static str,
+    pub rows: Vec<DataRow>,
+    pub now: Timestamp,
+}
+
+impl RelatedRows {
+    #[must_use]
+    pub const fn new(path: &'static str, rows: Vec<DataRow>, now: Timestamp) -> Self {
+        Self { path, rows, now }
+    }
+}
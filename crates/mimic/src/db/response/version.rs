@@ -0,0 +1,35 @@
+use crate::{
+    Error,
+    core::traits::EntityKind,
+    db::store::{HistoryRecord, decode_row},
+    types::Timestamp,
+};
+
+///
+/// EntityVersion
+///
+/// A prior version of a row, as returned by `LoadExecutor::history`.
+/// `entity` is `None` for the version recorded when the row was created, and
+/// replacing the row with an older `entity` rolls it back.
+///
+
+#[derive(Debug)]
+pub struct EntityVersion<E: EntityKind> {
+    pub version: u64,
+    pub replaced_at: Timestamp,
+    pub entity: Option<E>,
+}
+
+impl<E: EntityKind> TryFrom<HistoryRecord> for EntityVersion<E> {
+    type Error = Error;
+
+    fn try_from(record: HistoryRecord) -> Result<Self, Self::Error> {
+        let entity = record.bytes.as_deref().map(decode_row::<E>).transpose()?;
+
+        Ok(Self {
+            version: record.version,
+            replaced_at: Timestamp::from(record.replaced_at),
+            entity,
+        })
+    }
+}
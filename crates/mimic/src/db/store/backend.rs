@@ -0,0 +1,329 @@
+use canic::cdk::structures::{
+    BTreeMap as StableBTreeMap, DefaultMemoryImpl, Memory, memory::VirtualMemory,
+    storable::Storable,
+};
+#[cfg(any(test, feature = "in-memory"))]
+use std::collections::BTreeMap;
+use std::{cell::RefCell, ops::Bound, thread::LocalKey};
+
+///
+/// StoreBackend
+///
+/// The ordered key-value map underneath a `DataStore` or `IndexStore`.
+/// Ranges take explicit bounds and iterators are boxed, so every backend
+/// hands out the same iterator type.
+///
+
+pub trait StoreBackend<K, V> {
+    fn get(&self, key: &K) -> Option<V>;
+
+    fn contains_key(&self, key: &K) -> bool;
+
+    fn insert(&mut self, key: K, value: V) -> Option<V>;
+
+    fn remove(&mut self, key: &K) -> Option<V>;
+
+    fn len(&self) -> u64;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Remove every entry.
+    fn clear(&mut self);
+
+    /// Entries within `range`, in key order.
+    fn range(&self, range: (Bound<K>, Bound<K>)) -> Box<dyn Iterator<Item = (K, V)> + '_>;
+
+    /// Keys within `range`, in key order, without reading the values.
+    fn range_keys(&self, range: (Bound<K>, Bound<K>)) -> Box<dyn Iterator<Item = K> + '_>;
+
+    /// Every entry, in key order.
+    fn iter(&self) -> Box<dyn Iterator<Item = (K, V)> + '_> {
+        self.range((Bound::Unbounded, Bound::Unbounded))
+    }
+}
+
+// stable memory, as used by canisters
+impl<K, V, M> StoreBackend<K, V> for StableBTreeMap<K, V, M>
+where
+    K: Storable + Ord + Clone,
+    V: Storable + Clone,
+    M: Memory,
+{
+    fn get(&self, key: &K) -> Option<V> {
+        (**self).get(key)
+    }
+
+    fn contains_key(&self, key: &K) -> bool {
+        (**self).contains_key(key)
+    }
+
+    fn insert(&mut self, key: K, value: V) -> Option<V> {
+        (**self).insert(key, value)
+    }
+
+    fn remove(&mut self, key: &K) -> Option<V> {
+        (**self).remove(key)
+    }
+
+    fn len(&self) -> u64 {
+        (**self).len()
+    }
+
+    fn clear(&mut self) {
+        Self::clear(self);
+    }
+
+    fn range(&self, range: (Bound<K>, Bound<K>)) -> Box<dyn Iterator<Item = (K, V)> + '_> {
+        Box::new(
+            (**self)
+                .range(range)
+                .map(|entry| (entry.key().clone(), entry.value())),
+        )
+    }
+
+    fn range_keys(&self, range: (Bound<K>, Bound<K>)) -> Box<dyn Iterator<Item = K> + '_> {
+        Box::new((**self).keys_range(range))
+    }
+}
+
+///
+/// Backend
+///
+/// The map under a `DataStore` or `IndexStore`: stable memory, or with the
+/// `in-memory` feature, the heap. An enum rather than a trait object, so
+/// point reads and writes are dispatched statically; ranges and iterators
+/// are still boxed, as `StoreBackend` hands them out.
+///
+
+pub enum Backend<K, V>
+where
+    K: Storable + Ord + Clone,
+    V: Storable + Clone,
+{
+    Stable(StableBTreeMap<K, V, VirtualMemory<DefaultMemoryImpl>>),
+    #[cfg(any(test, feature = "in-memory"))]
+    Heap(HeapBackend<K, V>),
+}
+
+// dispatch
+// runs `$body` against whichever map backs `$self`
+macro_rules! dispatch {
+    ($self:expr, $map:ident => $body:expr) => {
+        match $self {
+            Self::Stable($map) => $body,
+            #[cfg(any(test, feature = "in-memory"))]
+            Self::Heap($map) => $body,
+        }
+    };
+}
+
+// the same methods as `StoreBackend`, inherent so callers don't need the trait
+impl<K, V> Backend<K, V>
+where
+    K: Storable + Ord + Clone,
+    V: Storable + Clone,
+{
+    pub fn get(&self, key: &K) -> Option<V> {
+        dispatch!(self, map => StoreBackend::get(map, key))
+    }
+
+    pub fn contains_key(&self, key: &K) -> bool {
+        dispatch!(self, map => StoreBackend::contains_key(map, key))
+    }
+
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        dispatch!(self, map => StoreBackend::insert(map, key, value))
+    }
+
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        dispatch!(self, map => StoreBackend::remove(map, key))
+    }
+
+    pub fn len(&self) -> u64 {
+        dispatch!(self, map => StoreBackend::len(map))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn clear(&mut self) {
+        dispatch!(self, map => StoreBackend::clear(map));
+    }
+
+    pub fn range(&self, range: (Bound<K>, Bound<K>)) -> Box<dyn Iterator<Item = (K, V)> + '_> {
+        dispatch!(self, map => StoreBackend::range(map, range))
+    }
+
+    pub fn range_keys(&self, range: (Bound<K>, Bound<K>)) -> Box<dyn Iterator<Item = K> + '_> {
+        dispatch!(self, map => StoreBackend::range_keys(map, range))
+    }
+
+    pub fn iter(&self) -> Box<dyn Iterator<Item = (K, V)> + '_> {
+        self.range((Bound::Unbounded, Bound::Unbounded))
+    }
+}
+
+impl<'a, K, V> IntoIterator for &'a Backend<K, V>
+where
+    K: Storable + Ord + Clone,
+    V: Storable + Clone,
+{
+    type Item = (K, V);
+    type IntoIter = Box<dyn Iterator<Item = (K, V)> + 'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+///
+/// HeapBackend
+///
+/// A `StoreBackend` on the heap, for running the executors in plain unit tests.
+/// Only built with the `in-memory` feature.
+///
+
+#[cfg(any(test, feature = "in-memory"))]
+pub struct HeapBackend<K, V>(BTreeMap<K, V>);
+
+#[cfg(any(test, feature = "in-memory"))]
+impl<K, V> Default for HeapBackend<K, V> {
+    fn default() -> Self {
+        Self(BTreeMap::new())
+    }
+}
+
+#[cfg(any(test, feature = "in-memory"))]
+impl<K: Ord + Clone, V: Clone> StoreBackend<K, V> for HeapBackend<K, V> {
+    fn get(&self, key: &K) -> Option<V> {
+        self.0.get(key).cloned()
+    }
+
+    fn contains_key(&self, key: &K) -> bool {
+        self.0.contains_key(key)
+    }
+
+    fn insert(&mut self, key: K, value: V) -> Option<V> {
+        self.0.insert(key, value)
+    }
+
+    fn remove(&mut self, key: &K) -> Option<V> {
+        self.0.remove(key)
+    }
+
+    fn len(&self) -> u64 {
+        self.0.len() as u64
+    }
+
+    fn clear(&mut self) {
+        self.0.clear();
+    }
+
+    fn range(&self, range: (Bound<K>, Bound<K>)) -> Box<dyn Iterator<Item = (K, V)> + '_> {
+        if is_empty_range(&range) {
+            return Box::new(std::iter::empty());
+        }
+
+        Box::new(self.0.range(range).map(|(k, v)| (k.clone(), v.clone())))
+    }
+
+    fn range_keys(&self, range: (Bound<K>, Bound<K>)) -> Box<dyn Iterator<Item = K> + '_> {
+        if is_empty_range(&range) {
+            return Box::new(std::iter::empty());
+        }
+
+        Box::new(self.0.range(range).map(|(k, _)| k.clone()))
+    }
+}
+
+// std's BTreeMap panics on inverted or empty-exclusive ranges, where the
+// stable one yields nothing
+#[cfg(any(test, feature = "in-memory"))]
+fn is_empty_range<K: Ord>(range: &(Bound<K>, Bound<K>)) -> bool {
+    match range {
+        (Bound::Included(start), Bound::Included(end)) => start > end,
+        (
+            Bound::Included(start) | Bound::Excluded(start),
+            Bound::Excluded(end) | Bound::Included(end),
+        ) => start >= end,
+        _ => false,
+    }
+}
+
+///
+/// StoreHandle
+///
+/// Where a registered store lives: a canister's thread-local cell, or with
+/// the `in-memory` feature, a heap cell created on demand by an in-memory
+/// registry.
+///
+
+pub enum StoreHandle<T: 'static> {
+    Local(&'static LocalKey<RefCell<T>>),
+    #[cfg(any(test, feature = "in-memory"))]
+    Heap(&'static RefCell<T>),
+}
+
+impl<T: 'static> StoreHandle<T> {
+    pub fn with_borrow<R>(&self, f: impl FnOnce(&T) -> R) -> R {
+        match self {
+            Self::Local(key) => key.with_borrow(f),
+            #[cfg(any(test, feature = "in-memory"))]
+            Self::Heap(cell) => f(&cell.borrow()),
+        }
+    }
+
+    pub fn with_borrow_mut<R>(&self, f: impl FnOnce(&mut T) -> R) -> R {
+        match self {
+            Self::Local(key) => key.with_borrow_mut(f),
+            #[cfg(any(test, feature = "in-memory"))]
+            Self::Heap(cell) => f(&mut cell.borrow_mut()),
+        }
+    }
+}
+
+impl<T: 'static> Clone for StoreHandle<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T: 'static> Copy for StoreHandle<T> {}
+
+///
+/// TESTS
+///
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn heap_ranges_match_stable_semantics() {
+        let mut heap = HeapBackend::<u64, u64>::default();
+        for i in 0..10 {
+            heap.insert(i, i * 10);
+        }
+
+        let keys: Vec<_> = heap
+            .range_keys((Bound::Included(3), Bound::Excluded(6)))
+            .collect();
+        assert_eq!(keys, vec![3, 4, 5]);
+
+        // inverted and empty ranges yield nothing instead of panicking
+        assert_eq!(
+            heap.range((Bound::Included(6), Bound::Included(3))).count(),
+            0
+        );
+        assert_eq!(
+            heap.range((Bound::Excluded(4), Bound::Excluded(4))).count(),
+            0
+        );
+
+        heap.clear();
+        assert!(heap.is_empty());
+    }
+}
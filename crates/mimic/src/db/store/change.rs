@@ -0,0 +1,191 @@
+use crate::{core::Key, db::query::SaveMode};
+use candid::CandidType;
+use canic::{
+    cdk::structures::{BTreeMap, DefaultMemoryImpl, memory::VirtualMemory},
+    impl_storable_unbounded,
+    utils::time,
+};
+use derive_more::{Deref, DerefMut, Display};
+use serde::{Deserialize, Serialize};
+
+///
+/// ChangeLogStore
+///
+/// Append-only log of every mutation made through the executors, keyed by a
+/// monotonically increasing sequence number starting at 1.
+///
+
+#[derive(Deref, DerefMut)]
+pub struct ChangeLogStore(BTreeMap<u64, ChangeRecord, VirtualMemory<DefaultMemoryImpl>>);
+
+impl ChangeLogStore {
+    /// Upper bound on the number of records returned by a single page.
+    pub const MAX_PAGE_SIZE: usize = 1_000;
+
+    #[must_use]
+    pub fn init(memory: VirtualMemory<DefaultMemoryImpl>) -> Self {
+        Self(BTreeMap::init(memory))
+    }
+
+    /// Sequence number of the most recent record, or 0 if the log is empty.
+    #[must_use]
+    pub fn last_seq(&self) -> u64 {
+        self.last_key_value().map_or(0, |(seq, _)| seq)
+    }
+
+    /// Append a change and return its sequence number.
+    pub fn append(
+        &mut self,
+        path: &str,
+        key: Key,
+        op: ChangeOp,
+        old: Option<&[u8]>,
+        new: Option<&[u8]>,
+    ) -> u64 {
+        let seq = self.last_seq() + 1;
+        let record = ChangeRecord {
+            seq,
+            path: path.to_string(),
+            key,
+            op,
+            timestamp: time::now_secs(),
+            old: old.map(<[u8]>::to_vec),
+            new: new.map(<[u8]>::to_vec),
+        };
+        self.insert(seq, record);
+
+        seq
+    }
+
+    /// Records with a sequence number greater than `since`, oldest first.
+    #[must_use]
+    pub fn page(&self, since: u64, limit: usize) -> ChangePage {
+        let limit = limit.min(Self::MAX_PAGE_SIZE);
+        let mut changes = Vec::with_capacity(limit);
+        let mut has_more = false;
+
+        for entry in self.range(since.saturating_add(1)..) {
+            if changes.len() == limit {
+                has_more = true;
+                break;
+            }
+            changes.push(entry.value());
+        }
+
+        let last_seq = changes.last().map_or(since, |c: &ChangeRecord| c.seq);
+
+        ChangePage {
+            changes,
+            last_seq,
+            has_more,
+        }
+    }
+}
+
+///
+/// ChangeOp
+///
+
+#[derive(CandidType, Clone, Copy, Debug, Deserialize, Display, Eq, PartialEq, Serialize)]
+pub enum ChangeOp {
+    Insert,
+    Update,
+    Replace,
+    Delete,
+}
+
+impl From<SaveMode> for ChangeOp {
+    fn from(mode: SaveMode) -> Self {
+        match mode {
+            SaveMode::Insert => Self::Insert,
+            SaveMode::Replace => Self::Replace,
+            SaveMode::Update => Self::Update,
+        }
+    }
+}
+
+///
+/// ChangeRecord
+///
+/// `old` and `new` hold the serialized entity before and after the change;
+/// `old` is empty on insert and `new` is empty on delete. `timestamp` is in
+/// seconds, the same clock as history and ttl timestamps.
+///
+
+#[derive(CandidType, Clone, Debug, Deserialize, Serialize)]
+pub struct ChangeRecord {
+    pub seq: u64,
+    pub path: String,
+    pub key: Key,
+    pub op: ChangeOp,
+    pub timestamp: u64,
+    pub old: Option<Vec<u8>>,
+    pub new: Option<Vec<u8>>,
+}
+
+impl_storable_unbounded!(ChangeRecord);
+
+///
+/// ChangePage
+///
+/// Pass `last_seq` back as `since` to continue reading.
+///
+
+#[derive(CandidType, Clone, Debug, Default, Deserialize, Serialize)]
+pub struct ChangePage {
+    pub changes: Vec<ChangeRecord>,
+    pub last_seq: u64,
+    pub has_more: bool,
+}
+
+///
+/// TESTS
+///
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use canic::cdk::structures::memory::{MemoryId, MemoryManager};
+
+    fn store() -> ChangeLogStore {
+        let manager = MemoryManager::init(DefaultMemoryImpl::default());
+
+        ChangeLogStore::init(manager.get(MemoryId::new(0)))
+    }
+
+    #[test]
+    fn append_assigns_sequential_numbers() {
+        let mut log = store();
+        assert_eq!(log.last_seq(), 0);
+
+        let first = log.append("a", Key::Uint(1), ChangeOp::Insert, None, Some(&[1]));
+        let second = log.append("a", Key::Uint(1), ChangeOp::Delete, Some(&[1]), None);
+
+        assert_eq!((first, second), (1, 2));
+        assert_eq!(log.last_seq(), 2);
+    }
+
+    #[test]
+    fn page_resumes_from_last_seq() {
+        let mut log = store();
+        for i in 0..5 {
+            log.append("a", Key::Uint(i), ChangeOp::Insert, None, Some(&[0]));
+        }
+
+        let page = log.page(0, 2);
+        let seqs: Vec<_> = page.changes.iter().map(|c| c.seq).collect();
+        assert_eq!(seqs, vec![1, 2]);
+        assert_eq!(page.last_seq, 2);
+        assert!(page.has_more);
+
+        let page = log.page(page.last_seq, 10);
+        let seqs: Vec<_> = page.changes.iter().map(|c| c.seq).collect();
+        assert_eq!(seqs, vec![3, 4, 5]);
+        assert!(!page.has_more);
+
+        // nothing new keeps the cursor where it was
+        let page = log.page(5, 10);
+        assert!(page.changes.is_empty());
+        assert_eq!(page.last_seq, 5);
+    }
+}
@@ -0,0 +1,175 @@
+//! The `Compact` row format: the CBOR body with each top-level text key that
+//! names an entity field replaced by the field's [`field_id`], a hash of its
+//! name, so rows stay readable when fields are added or reordered. Fields that
+//! are removed or renamed are kept as retired names, which older rows are
+//! decoded with. Other keys and all values are copied as they are.
+
+use mimic_common::field_id::field_id;
+use minicbor::{
+    Decoder, Encoder,
+    data::Type,
+    decode::{self, Error},
+};
+
+/// Rewrite a CBOR body so the names in `fields` become their field ids.
+pub fn encode(body: &[u8], fields: &[&str]) -> Result<Vec<u8>, Error> {
+    rewrite(body, |d, e| {
+        if d.datatype()? != Type::String {
+            return Ok(false);
+        }
+
+        let key = d.str()?;
+        if fields.contains(&key) {
+            e.u16(field_id(key))
+        } else {
+            e.str(key)
+        }
+        .expect("writing to a Vec can't fail");
+
+        Ok(true)
+    })
+}
+
+/// Restore field names in a body written by [`encode`]. Ids that match no
+/// field in `fields` are looked up in `retired`, so rows written before a
+/// field was removed or renamed come back with its old name for the entity's
+/// migrations to read.
+pub fn decode(body: &[u8], fields: &[&str], retired: &[&str]) -> Result<Vec<u8>, Error> {
+    rewrite(body, |d, e| {
+        if !matches!(d.datatype()?, Type::U8 | Type::U16) {
+            return Ok(false);
+        }
+
+        let id = d.u16()?;
+        let field = fields
+            .iter()
+            .chain(retired)
+            .find(|field| field_id(field) == id)
+            .ok_or_else(|| {
+                Error::message(format!(
+                    "no field with id {id:#06x}, list removed fields in codec(retired)"
+                ))
+            })?;
+        e.str(field).expect("writing to a Vec can't fail");
+
+        Ok(true)
+    })
+}
+
+// rewrite
+// copies a top-level map, letting `key` rewrite each key; keys it leaves
+// alone (returns false for) are copied byte for byte like the values
+fn rewrite(
+    body: &[u8],
+    mut key: impl FnMut(&mut Decoder, &mut Encoder<Vec<u8>>) -> Result<bool, Error>,
+) -> Result<Vec<u8>, Error> {
+    let mut d = Decoder::new(body);
+    let len = d
+        .map()?
+        .ok_or_else(|| decode::Error::message("row is not a definite-length map"))?;
+    let mut e = Encoder::new(Vec::with_capacity(body.len()));
+    e.map(len).expect("writing to a Vec can't fail");
+
+    for _ in 0..len {
+        let start = d.position();
+        if !key(&mut d, &mut e)? {
+            d.set_position(start);
+            copy_item(&mut d, &mut e, body)?;
+        }
+        copy_item(&mut d, &mut e, body)?;
+    }
+
+    if d.position() != body.len() {
+        return Err(Error::message("trailing bytes after row"));
+    }
+
+    Ok(e.into_writer())
+}
+
+// copy_item
+fn copy_item(d: &mut Decoder, e: &mut Encoder<Vec<u8>>, body: &[u8]) -> Result<(), Error> {
+    let start = d.position();
+    d.skip()?;
+    e.writer_mut().extend_from_slice(&body[start..d.position()]);
+
+    Ok(())
+}
+
+///
+/// TESTS
+///
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::serialize;
+    use serde::Serialize;
+
+    #[derive(Serialize)]
+    struct Row {
+        name: String,
+        score: u32,
+        extra: Vec<u8>,
+    }
+
+    #[test]
+    fn known_fields_shrink_and_round_trip() {
+        let body = serialize(&Row {
+            name: "ada".to_string(),
+            score: 7,
+            extra: vec![1, 2],
+        })
+        .unwrap();
+        let fields = ["name", "score"];
+
+        let compact = encode(&body, &fields).unwrap();
+        assert!(compact.len() < body.len());
+        assert_eq!(decode(&compact, &fields, &[]).unwrap(), body);
+    }
+
+    #[test]
+    fn ids_survive_reordered_fields() {
+        let body = serialize(&Row {
+            name: "ada".to_string(),
+            score: 7,
+            extra: vec![],
+        })
+        .unwrap();
+        let compact = encode(&body, &["name", "score"]).unwrap();
+
+        assert_eq!(
+            decode(&compact, &["extra", "score", "name"], &[]).unwrap(),
+            body
+        );
+    }
+
+    #[test]
+    fn unknown_id_fails() {
+        let body = serialize(&Row {
+            name: String::new(),
+            score: 0,
+            extra: vec![],
+        })
+        .unwrap();
+        let compact = encode(&body, &["name", "score", "extra"]).unwrap();
+
+        assert!(decode(&compact, &["name"], &[]).is_err());
+    }
+
+    #[test]
+    fn retired_fields_keep_their_names() {
+        let body = serialize(&Row {
+            name: "ada".to_string(),
+            score: 7,
+            extra: vec![],
+        })
+        .unwrap();
+        let compact = encode(&body, &["name", "score"]).unwrap();
+
+        // `score` was since renamed to `points`
+        assert_eq!(
+            decode(&compact, &["name", "points"], &["score"]).unwrap(),
+            body
+        );
+    }
+}
@@ -0,0 +1,205 @@
+//! A small LZ77 block compressor in the LZ4 block layout, prefixed with the
+//! uncompressed length: `[len: u32 BE] [sequence]*`.
+//!
+//! Each sequence is a token (literal length << 4 | match length - 4), any
+//! extra length bytes, the literals, then a little-endian `u16` match offset
+//! and extra match length bytes. The last sequence has literals only.
+
+const MIN_MATCH: usize = 4;
+const HASH_LOG: u32 = 12;
+const MAX_OFFSET: usize = u16::MAX as usize;
+
+// uncompressed length prefix
+const LEN_PREFIX: usize = 4;
+
+// longest output a corrupt length prefix can make us reserve up front
+const MAX_EXPANSION: usize = 255;
+
+/// Compress `input`. The output can be larger than the input for data with
+/// no repeats; callers decide whether to keep it.
+#[must_use]
+#[allow(clippy::cast_possible_truncation)]
+pub fn compress(input: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(LEN_PREFIX + input.len() / 2);
+    out.extend_from_slice(&(input.len() as u32).to_be_bytes());
+
+    // position + 1 of the last occurrence of each hashed 4-byte window
+    let mut table = vec![0usize; 1 << HASH_LOG];
+    let mut anchor = 0;
+    let mut pos = 0;
+
+    while pos + MIN_MATCH <= input.len() {
+        let window = u32::from_le_bytes(input[pos..pos + MIN_MATCH].try_into().unwrap());
+        let slot = (window.wrapping_mul(2_654_435_761) >> (32 - HASH_LOG)) as usize;
+        let candidate = table[slot];
+        table[slot] = pos + 1;
+
+        if let Some(start) = candidate.checked_sub(1)
+            && pos - start <= MAX_OFFSET
+            && input[start..start + MIN_MATCH] == input[pos..pos + MIN_MATCH]
+        {
+            let mut len = MIN_MATCH;
+            while pos + len < input.len() && input[start + len] == input[pos + len] {
+                len += 1;
+            }
+
+            write_sequence(&mut out, &input[anchor..pos], Some((pos - start, len)));
+            pos += len;
+            anchor = pos;
+        } else {
+            pos += 1;
+        }
+    }
+
+    write_sequence(&mut out, &input[anchor..], None);
+
+    out
+}
+
+/// Decompress a block written by [`compress`], or `None` if it is malformed.
+#[must_use]
+pub fn decompress(block: &[u8]) -> Option<Vec<u8>> {
+    let len = decompressed_len(block)?;
+    let mut input = &block[LEN_PREFIX..];
+    let mut out = Vec::with_capacity(len.min(input.len().saturating_mul(MAX_EXPANSION)));
+
+    loop {
+        let (&token, rest) = input.split_first()?;
+        input = rest;
+
+        let literals = read_len(&mut input, usize::from(token >> 4))?;
+        if literals > input.len() {
+            return None;
+        }
+        let (lit, rest) = input.split_at(literals);
+        out.extend_from_slice(lit);
+        input = rest;
+
+        // the last sequence carries no match, and every block ends with one
+        if input.is_empty() {
+            return (out.len() == len).then_some(out);
+        }
+
+        let [lo, hi, rest @ ..] = input else {
+            return None;
+        };
+        let offset = usize::from(u16::from_le_bytes([*lo, *hi]));
+        input = rest;
+        let match_len = read_len(&mut input, usize::from(token & 0x0F))? + MIN_MATCH;
+
+        if offset == 0 || offset > out.len() || out.len() + match_len > len {
+            return None;
+        }
+        // byte by byte, a match may overlap the bytes it produces
+        let start = out.len() - offset;
+        for i in 0..match_len {
+            out.push(out[start + i]);
+        }
+    }
+}
+
+/// The uncompressed length recorded in a block.
+#[must_use]
+pub fn decompressed_len(block: &[u8]) -> Option<usize> {
+    let prefix = block.get(..LEN_PREFIX)?;
+
+    Some(u32::from_be_bytes(prefix.try_into().ok()?) as usize)
+}
+
+// write_sequence
+#[allow(clippy::cast_possible_truncation)]
+fn write_sequence(out: &mut Vec<u8>, literals: &[u8], matched: Option<(usize, usize)>) {
+    let match_extra = matched.map_or(0, |(_, len)| len - MIN_MATCH);
+    let token = (literals.len().min(15) << 4) | match_extra.min(15);
+    out.push(token as u8);
+    write_len(out, literals.len());
+    out.extend_from_slice(literals);
+
+    if let Some((offset, _)) = matched {
+        out.extend_from_slice(&(offset as u16).to_le_bytes());
+        write_len(out, match_extra);
+    }
+}
+
+// write_len
+// lengths of 15 and over continue in 255-valued bytes after the token
+#[allow(clippy::cast_possible_truncation)]
+fn write_len(out: &mut Vec<u8>, len: usize) {
+    if len < 15 {
+        return;
+    }
+
+    let mut rest = len - 15;
+    while rest >= 255 {
+        out.push(255);
+        rest -= 255;
+    }
+    out.push(rest as u8);
+}
+
+// read_len
+fn read_len(input: &mut &[u8], nibble: usize) -> Option<usize> {
+    let mut len = nibble;
+    if nibble < 15 {
+        return Some(len);
+    }
+
+    loop {
+        let (&byte, rest) = input.split_first()?;
+        *input = rest;
+        len = len.checked_add(usize::from(byte))?;
+        if byte != 255 {
+            return Some(len);
+        }
+    }
+}
+
+///
+/// TESTS
+///
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip(input: &[u8]) -> Vec<u8> {
+        let block = compress(input);
+        assert_eq!(decompress(&block).as_deref(), Some(input));
+
+        block
+    }
+
+    #[test]
+    fn round_trips_edge_cases() {
+        round_trip(&[]);
+        round_trip(b"abc");
+        round_trip(&[7; 1]);
+        round_trip(&(0..=255).collect::<Vec<u8>>());
+    }
+
+    #[test]
+    fn repetitive_input_shrinks() {
+        let input = b"the quick brown fox ".repeat(200);
+        let block = round_trip(&input);
+
+        assert!(block.len() < input.len() / 10);
+    }
+
+    #[test]
+    fn long_literal_and_match_runs_round_trip() {
+        let mut input: Vec<u8> = (0..1000u32).map(|i| (i * 7919 % 251) as u8).collect();
+        input.extend(std::iter::repeat_n(b'x', 5000));
+        input.extend_from_slice(&input.clone()[..300]);
+
+        round_trip(&input);
+    }
+
+    #[test]
+    fn malformed_blocks_are_rejected() {
+        let block = compress(&b"hello hello hello hello".repeat(4));
+
+        assert!(decompress(&block[..block.len() - 1]).is_none());
+        assert!(decompress(&[0, 0, 0, 9, 0x10, b'a']).is_none());
+        assert!(decompress(&[0, 0]).is_none());
+    }
+}
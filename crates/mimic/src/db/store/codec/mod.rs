@@ -0,0 +1,166 @@
+mod compact;
+mod lz;
+
+use crate::schema::types::RowFormat;
+use std::borrow::Cow;
+
+///
+/// RowCodec
+///
+/// How an entity writes its rows: the serialization `format`, and whether to
+/// compress them. The codec a row was written with is kept in its header,
+/// so rows stay readable after an entity's codec changes.
+///
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct RowCodec {
+    pub format: RowFormat,
+    pub compress: bool,
+}
+
+impl RowCodec {
+    pub const DEFAULT: Self = Self::new(RowFormat::Cbor, false);
+
+    // tag bit set on rows that were compressed
+    const COMPRESSED: u8 = 0x80;
+
+    #[must_use]
+    pub const fn new(format: RowFormat, compress: bool) -> Self {
+        Self { format, compress }
+    }
+
+    /// The byte stored in a row header for this codec.
+    #[must_use]
+    pub const fn tag(self) -> u8 {
+        let format = match self.format {
+            RowFormat::Cbor => 0,
+            RowFormat::Compact => 1,
+        };
+
+        if self.compress {
+            format | Self::COMPRESSED
+        } else {
+            format
+        }
+    }
+
+    #[must_use]
+    pub const fn from_tag(tag: u8) -> Option<Self> {
+        let format = match tag & !Self::COMPRESSED {
+            0 => RowFormat::Cbor,
+            1 => RowFormat::Compact,
+            _ => return None,
+        };
+
+        Some(Self::new(format, tag & Self::COMPRESSED != 0))
+    }
+
+    /// Encode a CBOR row body. Returns the codec actually used, which leaves
+    /// out compression when it wouldn't make the row smaller.
+    pub(crate) fn encode(self, body: Vec<u8>, fields: &[&str]) -> Result<(Self, Vec<u8>), String> {
+        let body = match self.format {
+            RowFormat::Cbor => body,
+            RowFormat::Compact => compact::encode(&body, fields).map_err(|e| e.to_string())?,
+        };
+
+        if self.compress {
+            let compressed = lz::compress(&body);
+            if compressed.len() < body.len() {
+                return Ok((self, compressed));
+            }
+        }
+
+        Ok((Self::new(self.format, false), body))
+    }
+
+    /// Decode a stored payload back into a CBOR row body, see [`compact::decode`]
+    /// for how `fields` and `retired` are used.
+    pub(crate) fn decode<'a>(
+        self,
+        payload: &'a [u8],
+        fields: &[&str],
+        retired: &[&str],
+    ) -> Result<Cow<'a, [u8]>, String> {
+        let body = if self.compress {
+            Cow::Owned(lz::decompress(payload).ok_or("corrupt compressed row")?)
+        } else {
+            Cow::Borrowed(payload)
+        };
+
+        match self.format {
+            RowFormat::Cbor => Ok(body),
+            RowFormat::Compact => compact::decode(&body, fields, retired)
+                .map(Cow::Owned)
+                .map_err(|e| e.to_string()),
+        }
+    }
+
+    /// Size of a payload before compression.
+    #[must_use]
+    pub fn uncompressed_len(self, payload: &[u8]) -> usize {
+        if self.compress {
+            lz::decompressed_len(payload).unwrap_or(payload.len())
+        } else {
+            payload.len()
+        }
+    }
+}
+
+///
+/// TESTS
+///
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{deserialize, serialize};
+    use serde::{Deserialize, Serialize};
+
+    #[test]
+    fn tags_round_trip() {
+        for format in [RowFormat::Cbor, RowFormat::Compact] {
+            for compress in [false, true] {
+                let codec = RowCodec::new(format, compress);
+
+                assert_eq!(RowCodec::from_tag(codec.tag()), Some(codec));
+            }
+        }
+
+        assert_eq!(RowCodec::DEFAULT.tag(), 0);
+        assert_eq!(RowCodec::from_tag(0x7F), None);
+    }
+
+    #[test]
+    fn incompressible_rows_are_kept_plain() {
+        let codec = RowCodec::new(RowFormat::Cbor, true);
+        let (used, payload) = codec.encode(vec![1, 2, 3], &[]).unwrap();
+
+        assert_eq!(used, RowCodec::DEFAULT);
+        assert_eq!(payload, vec![1, 2, 3]);
+    }
+
+    #[derive(Debug, Deserialize, PartialEq, Serialize)]
+    struct UserV1 {
+        id: u64,
+        name: String,
+    }
+
+    #[test]
+    fn v1_rows_decode_after_a_rename() {
+        let v1 = UserV1 {
+            id: 7,
+            name: "ada".to_string(),
+        };
+        let codec = RowCodec::new(RowFormat::Compact, false);
+        let (_, payload) = codec
+            .encode(serialize(&v1).unwrap(), &["id", "name"])
+            .unwrap();
+
+        // version 2 renamed `name` to `title`
+        let fields = ["id", "title"];
+        assert!(codec.decode(&payload, &fields, &[]).is_err());
+
+        let body = codec.decode(&payload, &fields, &["name"]).unwrap();
+        assert_eq!(deserialize::<UserV1>(&body).unwrap(), v1);
+    }
+}
@@ -1,15 +1,23 @@
 use crate::{
     core::{Key, traits::EntityKind},
-    db::store::StoreRegistry,
+    db::store::{Backend, StoreRegistry},
 };
 use candid::CandidType;
-use canic::{
-    cdk::structures::{BTreeMap, DefaultMemoryImpl, memory::VirtualMemory},
-    impl_storable_bounded,
+use canic::cdk::structures::{
+    BTreeMap, DefaultMemoryImpl,
+    memory::VirtualMemory,
+    storable::{Bound, Storable},
 };
 use derive_more::{Deref, DerefMut};
 use serde::{Deserialize, Serialize};
-use std::fmt::{self, Display};
+use std::{
+    borrow::Cow,
+    fmt::{self, Display},
+    ops::Bound as RangeBound,
+};
+
+// keys rewritten per range read by `DataStore::rekey`
+const REKEY_BATCH: usize = 256;
 
 ///
 /// DataStoreRegistry
@@ -24,6 +32,21 @@ impl DataStoreRegistry {
     pub fn new() -> Self {
         Self(StoreRegistry::new())
     }
+
+    /// A registry of heap stores, created as entities ask for them.
+    #[cfg(any(test, feature = "in-memory"))]
+    #[must_use]
+    pub fn in_memory() -> Self {
+        Self(StoreRegistry::in_memory(DataStore::in_memory))
+    }
+
+    /// Rewrite the keys of every store, see [`DataStore::rekey`].
+    #[must_use]
+    pub fn rekey(&self) -> u64 {
+        self.iter()
+            .map(|(_, store)| store.with_borrow_mut(DataStore::rekey))
+            .sum()
+    }
 }
 
 ///
@@ -31,17 +54,52 @@ impl DataStoreRegistry {
 ///
 
 #[derive(Deref, DerefMut)]
-pub struct DataStore(BTreeMap<DataKey, Vec<u8>, VirtualMemory<DefaultMemoryImpl>>);
+pub struct DataStore(Backend<DataKey, Vec<u8>>);
 
 impl DataStore {
     #[must_use]
     pub fn init(memory: VirtualMemory<DefaultMemoryImpl>) -> Self {
-        Self(BTreeMap::init(memory))
+        Self(Backend::Stable(BTreeMap::init(memory)))
     }
 
+    #[cfg(any(test, feature = "in-memory"))]
+    #[must_use]
+    pub fn in_memory() -> Self {
+        Self(Backend::Heap(crate::db::store::HeapBackend::default()))
+    }
+
+    /// Rewrite every key in the current encoding, returning how many there were.
+    ///
+    /// Stable maps compare decoded keys, so CBOR keys written before
+    /// `Key::ENCODING_VERSION` are still found, but would otherwise keep their
+    /// old bytes for good. Run once on upgrade, see `SchemaUpgrade::rekey`.
+    pub fn rekey(&mut self) -> u64 {
+        let mut rekeyed = 0;
+        let mut after = RangeBound::Unbounded;
+
+        loop {
+            let keys: Vec<DataKey> = self
+                .range_keys((after, RangeBound::Unbounded))
+                .take(REKEY_BATCH)
+                .collect();
+            let Some(last) = keys.last().cloned() else {
+                return rekeyed;
+            };
+
+            for key in keys {
+                if let Some(value) = self.remove(&key) {
+                    self.insert(key, value);
+                    rekeyed += 1;
+                }
+            }
+            after = RangeBound::Excluded(last);
+        }
+    }
+
+    #[must_use]
     pub fn memory_bytes(&self) -> u64 {
         self.iter()
-            .map(|entry| u64::from(DataKey::STORABLE_MAX_SIZE) + entry.value().len() as u64)
+            .map(|(key, value)| key.entry_size_bytes(value.len() as u64))
             .sum()
     }
 }
@@ -65,7 +123,8 @@ pub struct DataKey {
 }
 
 impl DataKey {
-    pub const STORABLE_MAX_SIZE: u32 = 160;
+    /// Version byte + entity id + the largest encoded `Key` (without its own version byte).
+    pub const STORABLE_MAX_SIZE: u32 = 1 + 8 + Key::STORABLE_MAX_SIZE - 1;
 
     #[must_use]
     pub fn new<E: EntityKind>(key: impl Into<Key>) -> Self {
@@ -75,6 +134,15 @@ impl DataKey {
         }
     }
 
+    /// Build a key for an entity that is only known by its `ENTITY_ID` at runtime.
+    #[must_use]
+    pub fn from_entity_id(entity_id: u64, key: impl Into<Key>) -> Self {
+        Self {
+            entity_id,
+            key: key.into(),
+        }
+    }
+
     #[must_use]
     pub const fn lower_bound<E: EntityKind>() -> Self {
         Self {
@@ -104,10 +172,10 @@ impl DataKey {
     }
 
     /// Compute the on-disk size used by a single data entry from its value length.
-    /// Includes the bounded `DataKey` size and the value bytes.
+    /// Includes the encoded size of this key and the value bytes.
     #[must_use]
-    pub const fn entry_size_bytes(value_len: u64) -> u64 {
-        Self::STORABLE_MAX_SIZE as u64 + value_len
+    pub fn entry_size_bytes(&self, value_len: u64) -> u64 {
+        Storable::to_bytes(self).len() as u64 + value_len
     }
 
     #[must_use]
@@ -131,7 +199,85 @@ impl From<DataKey> for Key {
     }
 }
 
-impl_storable_bounded!(DataKey, Self::STORABLE_MAX_SIZE, false);
+///
+/// DataKey encoding
+///
+/// `[Key::ENCODING_VERSION][entity_id: u64 BE][Key tag + payload]`, so the
+/// byte order of encoded keys matches the derived `Ord` (entity, then key).
+///
+
+impl Storable for DataKey {
+    const BOUND: Bound = Bound::Bounded {
+        max_size: Self::STORABLE_MAX_SIZE,
+        is_fixed_size: false,
+    };
+
+    fn to_bytes(&self) -> Cow<'_, [u8]> {
+        Cow::Owned(self.clone().into_bytes())
+    }
+
+    fn into_bytes(self) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(Self::STORABLE_MAX_SIZE as usize);
+        buf.push(Key::ENCODING_VERSION);
+        buf.extend_from_slice(&self.entity_id.to_be_bytes());
+        self.key.encode_into(&mut buf);
+
+        buf
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        match bytes.split_first() {
+            Some((&Key::ENCODING_VERSION, rest)) if rest.len() > 8 => {
+                let (entity_id, key) = rest.split_at(8);
+
+                Self {
+                    entity_id: u64::from_be_bytes(entity_id.try_into().unwrap()),
+                    key: Key::decode(key).expect("invalid DataKey encoding"),
+                }
+            }
+            _ => canic::serialize::deserialize(&bytes).expect("invalid legacy DataKey encoding"),
+        }
+    }
+}
+
+// legacy
+// stable stores holding CBOR keys, as written before the binary encoding
+#[cfg(test)]
+#[derive(Clone, Eq, Ord, PartialEq, PartialOrd)]
+struct LegacyDataKey(DataKey);
+
+#[cfg(test)]
+impl Storable for LegacyDataKey {
+    const BOUND: Bound = Bound::Unbounded;
+
+    fn to_bytes(&self) -> Cow<'_, [u8]> {
+        Cow::Owned(canic::serialize::serialize(&self.0).unwrap())
+    }
+
+    fn into_bytes(self) -> Vec<u8> {
+        canic::serialize::serialize(&self.0).unwrap()
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        Self(canic::serialize::deserialize(&bytes).unwrap())
+    }
+}
+
+#[cfg(test)]
+impl DataStore {
+    /// A stable store holding `rows` under CBOR keys, and the memory it's in.
+    pub(crate) fn with_legacy_keys(rows: &[DataRow]) -> (Self, VirtualMemory<DefaultMemoryImpl>) {
+        use canic::cdk::structures::memory::{MemoryId, MemoryManager};
+
+        let memory = MemoryManager::init(DefaultMemoryImpl::default()).get(MemoryId::new(0));
+        let mut legacy = BTreeMap::init(memory.clone());
+        for (key, value) in rows {
+            legacy.insert(LegacyDataKey(key.clone()), value.clone());
+        }
+
+        (Self::init(memory.clone()), memory)
+    }
+}
 
 ///
 /// TESTS
@@ -153,4 +299,59 @@ mod tests {
             DataKey::STORABLE_MAX_SIZE
         );
     }
+
+    #[test]
+    fn data_key_bytes_follow_key_order() {
+        let keys = [
+            DataKey::from_entity_id(1, Key::Int(-5)),
+            DataKey::from_entity_id(1, Key::Uint(3)),
+            DataKey::from_entity_id(1, Key::Unit),
+            DataKey::from_entity_id(2, Key::lower_bound()),
+            DataKey::from_entity_id(256, Key::Int(0)),
+            DataKey::max_storable(),
+        ];
+
+        for pair in keys.windows(2) {
+            assert!(pair[0] < pair[1]);
+            assert!(Storable::to_bytes(&pair[0]) < Storable::to_bytes(&pair[1]));
+        }
+        for key in keys {
+            assert_eq!(DataKey::from_bytes(Storable::to_bytes(&key)), key);
+        }
+    }
+
+    #[test]
+    fn legacy_keys_are_found_and_rekeyed() {
+        let old = DataKey::from_entity_id(7, Key::Uint(42));
+        let (mut store, memory) = DataStore::with_legacy_keys(&[(old.clone(), vec![1])]);
+        let new = DataKey::from_entity_id(7, Key::Uint(43));
+        store.insert(new, vec![2]);
+
+        assert_eq!(store.get(&old), Some(vec![1]));
+        let range = (
+            RangeBound::Included(DataKey::from_entity_id(7, Key::lower_bound())),
+            RangeBound::Included(DataKey::from_entity_id(7, Key::upper_bound())),
+        );
+        assert_eq!(store.range(range.clone()).count(), 2);
+
+        // writing the same logical key replaces the row rather than adding one
+        store.insert(old.clone(), vec![3]);
+        assert_eq!(store.len(), 2);
+
+        assert_eq!(store.rekey(), 2);
+        assert_eq!(store.get(&old), Some(vec![3]));
+        assert_eq!(store.range(range).count(), 2);
+
+        // no key is left in the old encoding
+        let raw = BTreeMap::<Vec<u8>, Vec<u8>, _>::init(memory);
+        assert!(raw.iter().all(|e| e.key()[0] == Key::ENCODING_VERSION));
+    }
+
+    #[test]
+    fn legacy_data_keys_still_decode() {
+        let key = DataKey::from_entity_id(7, Key::Uint(42));
+        let legacy = canic::serialize::serialize(&key).unwrap();
+
+        assert_eq!(DataKey::from_bytes(Cow::Owned(legacy)), key);
+    }
 }
@@ -0,0 +1,299 @@
+use crate::{
+    core::Key,
+    db::store::{DataKey, StoreError},
+};
+use candid::CandidType;
+use canic::{
+    cdk::structures::{
+        BTreeMap, DefaultMemoryImpl,
+        memory::VirtualMemory,
+        storable::{Bound, Storable},
+    },
+    impl_storable_unbounded,
+    utils::time,
+};
+use derive_more::{Deref, DerefMut};
+use serde::{Deserialize, Serialize};
+use std::{borrow::Cow, collections::BTreeMap as StdBTreeMap};
+
+///
+/// HistoryStore
+///
+/// Prior versions of rows for entities that opt into `history`. Each save or
+/// delete pushes the bytes it replaced, numbered per row from 1, and the oldest
+/// versions are dropped once a row has more than the entity's limit.
+///
+
+#[derive(Deref, DerefMut)]
+pub struct HistoryStore(BTreeMap<HistoryKey, HistoryRecord, VirtualMemory<DefaultMemoryImpl>>);
+
+impl HistoryStore {
+    #[must_use]
+    pub fn init(memory: VirtualMemory<DefaultMemoryImpl>) -> Self {
+        Self(BTreeMap::init(memory))
+    }
+
+    /// Keep `old` as the newest version of `key`, returning its version number.
+    /// `old` is `None` when the change created the row.
+    pub fn append(&mut self, key: &DataKey, old: Option<&[u8]>, limit: u32) -> u64 {
+        let existing: Vec<u64> = self
+            .range(HistoryKey::first(key)..=HistoryKey::last(key))
+            .map(|entry| entry.key().version)
+            .collect();
+
+        let version = existing.last().map_or(1, |v| v + 1);
+        self.insert(
+            HistoryKey {
+                key: key.clone(),
+                version,
+            },
+            HistoryRecord {
+                version,
+                replaced_at: time::now_secs(),
+                bytes: old.map(<[u8]>::to_vec),
+            },
+        );
+
+        // retention, counting the version we just added
+        let excess = (existing.len() + 1).saturating_sub(limit as usize);
+        for version in existing.into_iter().take(excess) {
+            self.remove(&HistoryKey {
+                key: key.clone(),
+                version,
+            });
+        }
+
+        version
+    }
+
+    /// Retained versions of `key`, oldest first.
+    #[must_use]
+    pub fn versions(&self, key: &DataKey) -> Vec<HistoryRecord> {
+        self.range(HistoryKey::first(key)..=HistoryKey::last(key))
+            .map(|entry| entry.value())
+            .collect()
+    }
+
+    /// State at `as_of` (seconds) of every row of `entity_id` that has changed since then.
+    ///
+    /// Rows missing from the result have not changed and still hold their current
+    /// value; a `None` state means the row did not exist at `as_of`. Fails when
+    /// retention has already dropped the version a row had at `as_of`.
+    pub fn states_as_of(
+        &self,
+        entity_id: u64,
+        as_of: u64,
+    ) -> Result<StdBTreeMap<Key, Option<Vec<u8>>>, StoreError> {
+        let lower = HistoryKey::first(&DataKey::from_entity_id(entity_id, Key::lower_bound()));
+        let upper = HistoryKey::last(&DataKey::from_entity_id(entity_id, Key::upper_bound()));
+
+        // the first version replaced after `as_of` is the one that was live at `as_of`
+        let mut states = StdBTreeMap::new();
+        let mut previous: Option<DataKey> = None;
+        for entry in self.range(lower..=upper) {
+            let HistoryKey { key, version } = entry.key().clone();
+            let oldest_kept = previous.as_ref() != Some(&key);
+            previous = Some(key.clone());
+
+            let record = entry.value();
+            if record.replaced_at <= as_of || states.contains_key(&key.key()) {
+                continue;
+            }
+
+            // versions before the oldest one kept may have been live at `as_of` too
+            if oldest_kept && version > 1 {
+                return Err(StoreError::HistoryPruned(key.to_string(), as_of));
+            }
+            states.insert(key.key(), record.bytes);
+        }
+
+        Ok(states)
+    }
+}
+
+///
+/// HistoryKey
+///
+/// Encoded as the `DataKey` followed by the version as a big-endian `u64`.
+///
+
+#[derive(
+    CandidType, Clone, Debug, Eq, PartialEq, Hash, Ord, PartialOrd, Serialize, Deserialize,
+)]
+pub struct HistoryKey {
+    pub key: DataKey,
+    pub version: u64,
+}
+
+impl HistoryKey {
+    pub const STORABLE_MAX_SIZE: u32 = DataKey::STORABLE_MAX_SIZE + 8;
+
+    fn first(key: &DataKey) -> Self {
+        Self {
+            key: key.clone(),
+            version: u64::MIN,
+        }
+    }
+
+    fn last(key: &DataKey) -> Self {
+        Self {
+            key: key.clone(),
+            version: u64::MAX,
+        }
+    }
+}
+
+impl Storable for HistoryKey {
+    const BOUND: Bound = Bound::Bounded {
+        max_size: Self::STORABLE_MAX_SIZE,
+        is_fixed_size: false,
+    };
+
+    fn to_bytes(&self) -> Cow<'_, [u8]> {
+        Cow::Owned(self.clone().into_bytes())
+    }
+
+    fn into_bytes(self) -> Vec<u8> {
+        let mut buf = self.key.into_bytes();
+        buf.extend_from_slice(&self.version.to_be_bytes());
+
+        buf
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        let (key, version) = bytes.split_at(bytes.len() - 8);
+
+        Self {
+            key: DataKey::from_bytes(Cow::Borrowed(key)),
+            version: u64::from_be_bytes(version.try_into().unwrap()),
+        }
+    }
+}
+
+///
+/// HistoryRecord
+///
+/// `bytes` is the serialized row as it was before the change made at
+/// `replaced_at` (seconds), or empty if the change created the row.
+///
+
+#[derive(CandidType, Clone, Debug, Deserialize, Serialize)]
+pub struct HistoryRecord {
+    pub version: u64,
+    pub replaced_at: u64,
+    pub bytes: Option<Vec<u8>>,
+}
+
+impl_storable_unbounded!(HistoryRecord);
+
+///
+/// TESTS
+///
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::traits::Storable;
+    use canic::cdk::structures::memory::{MemoryId, MemoryManager};
+
+    fn store() -> HistoryStore {
+        let manager = MemoryManager::init(DefaultMemoryImpl::default());
+
+        HistoryStore::init(manager.get(MemoryId::new(0)))
+    }
+
+    #[test]
+    fn history_key_max_size_is_bounded() {
+        let key = HistoryKey::last(&DataKey::max_storable());
+        let size = Storable::to_bytes(&key).len();
+
+        assert!(
+            size <= HistoryKey::STORABLE_MAX_SIZE as usize,
+            "serialized HistoryKey too large: got {size} bytes (limit {})",
+            HistoryKey::STORABLE_MAX_SIZE
+        );
+    }
+
+    #[test]
+    fn append_keeps_latest_versions() {
+        let mut history = store();
+        let key = DataKey::from_entity_id(1, Key::Uint(1));
+
+        history.append(&key, None, 3);
+        for i in 0..4 {
+            history.append(&key, Some(&[i]), 3);
+        }
+
+        let versions: Vec<_> = history.versions(&key).iter().map(|r| r.version).collect();
+        assert_eq!(versions, vec![3, 4, 5]);
+    }
+
+    #[test]
+    fn versions_are_per_row() {
+        let mut history = store();
+        let a = DataKey::from_entity_id(1, Key::Uint(1));
+        let b = DataKey::from_entity_id(1, Key::Uint(2));
+
+        history.append(&a, None, 10);
+        history.append(&a, Some(&[1]), 10);
+        history.append(&b, None, 10);
+
+        assert_eq!(history.versions(&a).len(), 2);
+        assert_eq!(history.versions(&b)[0].version, 1);
+    }
+
+    #[test]
+    fn states_as_of_skips_older_changes() {
+        let mut history = store();
+        let key = DataKey::from_entity_id(1, Key::Uint(1));
+        let other = DataKey::from_entity_id(2, Key::Uint(1));
+        history.append(&key, Some(&[1]), 10);
+        history.append(&other, Some(&[2]), 10);
+
+        let now = time::now_secs();
+
+        // changed after the read point, so the old bytes were live then
+        let states = history.states_as_of(1, now - 1).unwrap();
+        assert_eq!(states.get(&Key::Uint(1)), Some(&Some(vec![1])));
+        assert_eq!(states.len(), 1);
+
+        // nothing has changed since now
+        assert!(history.states_as_of(1, now).unwrap().is_empty());
+    }
+
+    #[test]
+    fn states_as_of_fails_once_pruned() {
+        let mut history = store();
+        let key = DataKey::from_entity_id(1, Key::Uint(1));
+        let now = time::now_secs();
+
+        history.append(&key, None, 2);
+        history.append(&key, Some(&[1]), 2);
+        assert!(history.states_as_of(1, now - 1).is_ok());
+
+        // version 1, the row's state before `now`, is dropped
+        history.append(&key, Some(&[2]), 2);
+        assert!(matches!(
+            history.states_as_of(1, now - 1),
+            Err(StoreError::HistoryPruned(..))
+        ));
+
+        // rows that haven't changed since still resolve to their current value
+        assert!(history.states_as_of(1, now).unwrap().is_empty());
+    }
+
+    #[test]
+    fn history_key_bytes_round_trip() {
+        let keys = [
+            HistoryKey {
+                key: DataKey::from_entity_id(1, Key::Uint(3)),
+                version: 2,
+            },
+            HistoryKey::last(&DataKey::max_storable()),
+        ];
+
+        for key in keys {
+            assert_eq!(HistoryKey::from_bytes(Storable::to_bytes(&key)), key);
+        }
+    }
+}
@@ -3,7 +3,8 @@ use crate::{
     core::{Key, Value, traits::EntityKind},
     db::{
         executor::ExecutorError,
-        store::{DataKey, StoreRegistry},
+        primitives::BudgetMeter,
+        store::{Backend, DataKey, StoreRegistry},
     },
     obs::metrics,
     schema::node::Index,
@@ -19,8 +20,12 @@ use serde::{Deserialize, Serialize};
 use std::{
     collections::HashSet,
     fmt::{self, Display},
+    ops::Bound,
 };
 
+// index entries collected per range pass when clearing an index
+const CLEAR_BATCH: usize = 64;
+
 ///
 /// IndexStoreRegistry
 ///
@@ -34,6 +39,13 @@ impl IndexStoreRegistry {
     pub fn new() -> Self {
         Self(StoreRegistry::new())
     }
+
+    /// A registry of heap stores, created as indexes ask for them.
+    #[cfg(any(test, feature = "in-memory"))]
+    #[must_use]
+    pub fn in_memory() -> Self {
+        Self(StoreRegistry::in_memory(IndexStore::in_memory))
+    }
 }
 
 ///
@@ -41,12 +53,18 @@ impl IndexStoreRegistry {
 ///
 
 #[derive(Deref, DerefMut)]
-pub struct IndexStore(BTreeMap<IndexKey, IndexEntry, VirtualMemory<DefaultMemoryImpl>>);
+pub struct IndexStore(Backend<IndexKey, IndexEntry>);
 
 impl IndexStore {
     #[must_use]
     pub fn init(memory: VirtualMemory<DefaultMemoryImpl>) -> Self {
-        Self(BTreeMap::init(memory))
+        Self(Backend::Stable(BTreeMap::init(memory)))
+    }
+
+    #[cfg(any(test, feature = "in-memory"))]
+    #[must_use]
+    pub fn in_memory() -> Self {
+        Self(Backend::Heap(crate::db::store::HeapBackend::default()))
     }
 
     /// Inserts the given entity into the index defined by `I`.
@@ -118,6 +136,73 @@ impl IndexStore {
         }
     }
 
+    /// Remove `keys` from every entry of one index. For rows stored at an older
+    /// schema version, whose indexed values can't be rebuilt, so this walks
+    /// the whole index; callers batch the keys they need to drop.
+    pub fn remove_keys<E: EntityKind>(&mut self, index: &Index, keys: &HashSet<Key>) {
+        let index_id = IndexId::new::<E>(index);
+        let start = IndexKey {
+            index_id,
+            hashed_values: Vec::new(),
+        };
+
+        // collect first, the backend is borrowed while iterating
+        let stale: Vec<(IndexKey, IndexEntry)> = self
+            .range((Bound::Included(start), Bound::Unbounded))
+            .take_while(|(key, _)| key.index_id == index_id)
+            .filter(|(_, entry)| !entry.keys.is_disjoint(keys))
+            .collect();
+
+        let mut removed = 0u64;
+        for (index_key, mut entry) in stale {
+            let before = entry.len();
+            entry.keys.retain(|key| !keys.contains(key));
+            removed += (before - entry.len()) as u64;
+
+            if entry.is_empty() {
+                self.remove(&index_key);
+            } else {
+                self.insert(index_key, entry);
+            }
+        }
+
+        if removed > 0 {
+            metrics::with_state_mut(|m| {
+                m.ops.index_removes += removed;
+                let entry = m.entities.entry(E::PATH.to_string()).or_default();
+                entry.index_removes = entry.index_removes.saturating_add(removed);
+            });
+        }
+    }
+
+    /// Remove every entry of one index, ticking `meter` per entry.
+    /// Returns `false` if the meter ran out before the index was empty.
+    pub fn clear_index(&mut self, index_id: IndexId, meter: &mut BudgetMeter) -> bool {
+        let start = IndexKey {
+            index_id,
+            hashed_values: Vec::new(),
+        };
+
+        loop {
+            let keys: Vec<IndexKey> = self
+                .range_keys((Bound::Included(start.clone()), Bound::Unbounded))
+                .take_while(|key| key.index_id == index_id)
+                .take(CLEAR_BATCH)
+                .collect();
+            if keys.is_empty() {
+                return true;
+            }
+
+            for key in keys {
+                self.remove(&key);
+                meter.tick();
+                if meter.is_spent() {
+                    return false;
+                }
+            }
+        }
+    }
+
     #[must_use]
     pub fn resolve_data_values<E: EntityKind>(
         &self,
@@ -133,9 +218,10 @@ impl IndexStore {
         out
     }
 
+    #[must_use]
     pub fn memory_bytes(&self) -> u64 {
         self.iter()
-            .map(|entry| u64::from(IndexKey::STORABLE_MAX_SIZE) + entry.value().len() as u64)
+            .map(|(_, entry)| u64::from(IndexKey::STORABLE_MAX_SIZE) + entry.len() as u64)
             .sum()
     }
 
@@ -166,8 +252,7 @@ impl IndexStore {
             )
         };
 
-        self.range(start_key..end_key)
-            .map(|entry| (entry.key().clone(), entry.value()))
+        self.range((Bound::Included(start_key), Bound::Excluded(end_key)))
     }
 
     fn index_fingerprints(values: &[Value]) -> Option<Vec<[u8; 16]>> {
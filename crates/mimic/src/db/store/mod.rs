@@ -1,8 +1,20 @@
+mod backend;
+mod change;
+mod codec;
 mod data;
+mod history;
 mod index;
+mod row;
+mod schema;
 
+pub use backend::*;
+pub use change::*;
+pub use codec::*;
 pub use data::*;
+pub use history::*;
 pub use index::*;
+pub use row::*;
+pub use schema::*;
 
 use crate::{Error, db::DbError};
 use std::{cell::RefCell, collections::HashMap, thread::LocalKey};
@@ -16,6 +28,33 @@ use thiserror::Error as ThisError;
 pub enum StoreError {
     #[error("store '{0}' not found")]
     StoreNotFound(String),
+
+    #[error("no change log store is declared for this canister")]
+    ChangeLogNotFound,
+
+    #[error("no history store is declared for this canister")]
+    HistoryNotFound,
+
+    #[error("no job store is declared for this canister")]
+    JobStoreNotFound,
+
+    #[error("row of '{0}' is at version {1}, newer than the entity's version {2}")]
+    RowVersionTooNew(String, u32, u32),
+
+    #[error("no migration for '{0}' from version {1}")]
+    MigrationNotFound(String, u32),
+
+    #[error("row of '{0}' has unknown codec tag {1:#04x}")]
+    UnknownRowCodec(String, u8),
+
+    #[error("row of '{0}' can't be decoded: {1}")]
+    InvalidRow(String, String),
+
+    #[error("history of {0} before {1} has been pruned")]
+    HistoryPruned(String, u64),
+
+    #[error("schema is incompatible with the stored data: {}", .0.join("; "))]
+    SchemaIncompatible(Vec<String>),
 }
 
 impl From<StoreError> for Error {
@@ -28,55 +67,123 @@ impl From<StoreError> for Error {
 /// StoreRegistry
 ///
 
-#[derive(Default)]
-pub struct StoreRegistry<T: 'static>(HashMap<&'static str, &'static LocalKey<RefCell<T>>>);
+/// Stores registered by generated code, plus, for in-memory registries, heap
+/// stores created the first time a path is asked for.
+pub struct StoreRegistry<T: 'static> {
+    stores: HashMap<&'static str, &'static LocalKey<RefCell<T>>>,
+    #[cfg(any(test, feature = "in-memory"))]
+    heap: Option<HeapStores<T>>,
+}
+
+// heap stores are leaked so handles to them can be `'static` like the
+// thread-local ones; in-memory registries only exist in tests
+#[cfg(any(test, feature = "in-memory"))]
+struct HeapStores<T: 'static> {
+    init: fn() -> T,
+    stores: RefCell<HashMap<String, &'static RefCell<T>>>,
+}
+
+#[cfg(any(test, feature = "in-memory"))]
+impl<T: 'static> HeapStores<T> {
+    fn handles(&self) -> Vec<(String, StoreHandle<T>)> {
+        self.stores
+            .borrow()
+            .iter()
+            .map(|(path, cell)| (path.clone(), StoreHandle::Heap(*cell)))
+            .collect()
+    }
+
+    fn get_or_init(&self, path: &str) -> StoreHandle<T> {
+        let cell = *self
+            .stores
+            .borrow_mut()
+            .entry(path.to_string())
+            .or_insert_with(|| Box::leak(Box::new(RefCell::new((self.init)()))));
+
+        StoreHandle::Heap(cell)
+    }
+}
 
 impl<T: 'static> StoreRegistry<T> {
     // new
     #[must_use]
     pub fn new() -> Self {
-        Self(HashMap::new())
+        Self {
+            stores: HashMap::new(),
+            #[cfg(any(test, feature = "in-memory"))]
+            heap: None,
+        }
+    }
+
+    /// A registry that creates a store with `init` for any path it's asked for.
+    #[cfg(any(test, feature = "in-memory"))]
+    #[must_use]
+    pub fn in_memory(init: fn() -> T) -> Self {
+        Self {
+            stores: HashMap::new(),
+            heap: Some(HeapStores {
+                init,
+                stores: RefCell::default(),
+            }),
+        }
     }
 
     // iter
-    pub fn iter(&self) -> impl Iterator<Item = (&'static str, &'static LocalKey<RefCell<T>>)> {
-        self.0.iter().map(|(k, v)| (*k, *v))
+    #[allow(clippy::let_and_return)]
+    pub fn iter(&self) -> impl Iterator<Item = (String, StoreHandle<T>)> {
+        let stores = self
+            .stores
+            .iter()
+            .map(|(path, key)| ((*path).to_string(), StoreHandle::Local(*key)));
+        #[cfg(any(test, feature = "in-memory"))]
+        let stores = stores.chain(self.heap.iter().flat_map(HeapStores::handles));
+
+        stores
     }
 
     // for_each
-    pub fn for_each<R>(&self, mut f: impl FnMut(&'static str, &T) -> R) {
-        for (path, accessor) in &self.0 {
-            accessor.with(|cell| {
-                let store = cell.borrow();
-                f(path, &store);
-            });
+    pub fn for_each<R>(&self, mut f: impl FnMut(&str, &T) -> R) {
+        for (path, store) in self.iter() {
+            store.with_borrow(|store| f(&path, store));
         }
     }
 
     // register
     pub fn register(&mut self, name: &'static str, accessor: &'static LocalKey<RefCell<T>>) {
-        self.0.insert(name, accessor);
+        self.stores.insert(name, accessor);
     }
 
     // try_get_store
-    pub fn try_get_store(&self, path: &str) -> Result<&'static LocalKey<RefCell<T>>, Error> {
-        self.0
-            .get(path)
-            .copied()
-            .ok_or_else(|| StoreError::StoreNotFound(path.to_string()).into())
+    pub fn try_get_store(&self, path: &str) -> Result<StoreHandle<T>, Error> {
+        if let Some(key) = self.stores.get(path) {
+            return Ok(StoreHandle::Local(key));
+        }
+
+        #[cfg(any(test, feature = "in-memory"))]
+        if let Some(heap) = &self.heap {
+            return Ok(heap.get_or_init(path));
+        }
+
+        Err(StoreError::StoreNotFound(path.to_string()).into())
     }
 
     // with_store
     pub fn with_store<R>(&self, path: &str, f: impl FnOnce(&T) -> R) -> Result<R, Error> {
         let store = self.try_get_store(path)?;
 
-        Ok(store.with_borrow(|s| f(s)))
+        Ok(store.with_borrow(f))
     }
 
     // with_store_mut
     pub fn with_store_mut<R>(&self, path: &str, f: impl FnOnce(&mut T) -> R) -> Result<R, Error> {
         let store = self.try_get_store(path)?;
 
-        Ok(store.with_borrow_mut(|s| f(s)))
+        Ok(store.with_borrow_mut(f))
+    }
+}
+
+impl<T: 'static> Default for StoreRegistry<T> {
+    fn default() -> Self {
+        Self::new()
     }
 }
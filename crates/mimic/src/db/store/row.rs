@@ -0,0 +1,200 @@
+use crate::{
+    Error,
+    core::{deserialize, serialize, traits::EntityKind},
+    db::store::{RowCodec, StoreError},
+};
+use std::borrow::Cow;
+
+///
+/// RowMigration
+///
+/// Rewrites the serialized body of a row from one schema version to the next.
+/// Old versions have no Rust type, so migrations usually deserialize into a
+/// local copy of the old struct and serialize the new one.
+///
+
+pub type RowMigration = fn(&[u8]) -> Result<Vec<u8>, Error>;
+
+// a CBOR break byte, which never starts a serialized value, so rows written
+// before versioning (plain bodies) can't be mistaken for versioned ones
+const ROW_MARKER: u8 = 0xFF;
+const ROW_HEADER_LEN: usize = 5;
+
+// a reserved CBOR byte, marking rows whose header also holds a non-default
+// codec tag: `[ROW_CODEC_MARKER, tag, version: u32 BE]`
+const ROW_CODEC_MARKER: u8 = 0xFE;
+const ROW_CODEC_HEADER_LEN: usize = 6;
+
+/// Serialize `entity` with a header holding `E::SCHEMA_VERSION` and the codec used.
+pub fn encode_row<E: EntityKind>(entity: &E) -> Result<Vec<u8>, Error> {
+    encode_body::<E>(E::SCHEMA_VERSION, serialize(entity)?)
+}
+
+/// Deserialize a stored row, migrating it in memory if it is at an older version.
+pub fn decode_row<E: EntityKind>(bytes: &[u8]) -> Result<E, Error> {
+    let (version, body) = decode_body::<E>(bytes)?;
+    if version == E::SCHEMA_VERSION {
+        return deserialize(&body);
+    }
+
+    deserialize(&migrate_body::<E>(version, &body)?)
+}
+
+/// Re-encode a stored row at the current version and format, or `None` if it
+/// already is. Rows left uncompressed are only compressed on their next write.
+pub fn migrate_row<E: EntityKind>(bytes: &[u8]) -> Result<Option<Vec<u8>>, Error> {
+    let (version, codec, _) = split_row::<E>(bytes)?;
+    if version == E::SCHEMA_VERSION
+        && codec.format == E::CODEC.format
+        && (E::CODEC.compress || !codec.compress)
+    {
+        return Ok(None);
+    }
+
+    let (version, body) = decode_body::<E>(bytes)?;
+    let body = if version == E::SCHEMA_VERSION {
+        body.into_owned()
+    } else {
+        migrate_body::<E>(version, &body)?
+    };
+
+    encode_body::<E>(E::SCHEMA_VERSION, body).map(Some)
+}
+
+/// Split a stored row into its schema version and (codec-encoded) payload.
+/// Rows written before versioning are treated as version 1.
+#[must_use]
+pub fn row_version(bytes: &[u8]) -> (u32, &[u8]) {
+    match bytes {
+        [ROW_MARKER, a, b, c, d, body @ ..] | [ROW_CODEC_MARKER, _, a, b, c, d, body @ ..] => {
+            (u32::from_be_bytes([*a, *b, *c, *d]), body)
+        }
+        _ => (1, bytes),
+    }
+}
+
+/// Stored size of a row and its size before compression.
+#[must_use]
+pub fn row_sizes(bytes: &[u8]) -> (usize, usize) {
+    match bytes {
+        [ROW_CODEC_MARKER, tag, _, _, _, _, payload @ ..] => {
+            let uncompressed = RowCodec::from_tag(*tag)
+                .map_or(payload.len(), |codec| codec.uncompressed_len(payload));
+
+            (bytes.len(), ROW_CODEC_HEADER_LEN + uncompressed)
+        }
+        _ => (bytes.len(), bytes.len()),
+    }
+}
+
+// encode_body
+// encodes a CBOR body with `E::CODEC` behind the matching header
+fn encode_body<E: EntityKind>(version: u32, body: Vec<u8>) -> Result<Vec<u8>, Error> {
+    let (codec, payload) = E::CODEC
+        .encode(body, E::FIELDS)
+        .map_err(|e| StoreError::InvalidRow(E::PATH.to_string(), e))?;
+
+    Ok(with_header(version, codec, &payload))
+}
+
+// decode_body
+// the row's version and its CBOR body
+fn decode_body<E: EntityKind>(bytes: &[u8]) -> Result<(u32, Cow<'_, [u8]>), Error> {
+    let (version, codec, payload) = split_row::<E>(bytes)?;
+    let body = codec
+        .decode(payload, E::FIELDS, E::RETIRED_FIELDS)
+        .map_err(|e| StoreError::InvalidRow(E::PATH.to_string(), e))?;
+
+    Ok((version, body))
+}
+
+// split_row
+fn split_row<E: EntityKind>(bytes: &[u8]) -> Result<(u32, RowCodec, &[u8]), Error> {
+    let codec = match bytes {
+        [ROW_CODEC_MARKER, tag, ..] => RowCodec::from_tag(*tag)
+            .ok_or_else(|| StoreError::UnknownRowCodec(E::PATH.to_string(), *tag))?,
+        _ => RowCodec::DEFAULT,
+    };
+    let (version, payload) = row_version(bytes);
+
+    Ok((version, codec, payload))
+}
+
+// with_header
+// default-codec rows keep the original header, so their bytes don't change
+fn with_header(version: u32, codec: RowCodec, payload: &[u8]) -> Vec<u8> {
+    let mut bytes;
+    if codec == RowCodec::DEFAULT {
+        bytes = Vec::with_capacity(ROW_HEADER_LEN + payload.len());
+        bytes.push(ROW_MARKER);
+    } else {
+        bytes = Vec::with_capacity(ROW_CODEC_HEADER_LEN + payload.len());
+        bytes.extend_from_slice(&[ROW_CODEC_MARKER, codec.tag()]);
+    }
+    bytes.extend_from_slice(&version.to_be_bytes());
+    bytes.extend_from_slice(payload);
+
+    bytes
+}
+
+// migrate_body
+// runs each migration from `version` up to the current one
+fn migrate_body<E: EntityKind>(version: u32, body: &[u8]) -> Result<Vec<u8>, Error> {
+    if version > E::SCHEMA_VERSION {
+        return Err(
+            StoreError::RowVersionTooNew(E::PATH.to_string(), version, E::SCHEMA_VERSION).into(),
+        );
+    }
+
+    let mut body = body.to_vec();
+    for from in version..E::SCHEMA_VERSION {
+        let migration = from
+            .checked_sub(1)
+            .and_then(|i| E::MIGRATIONS.get(i as usize))
+            .ok_or_else(|| StoreError::MigrationNotFound(E::PATH.to_string(), from))?;
+        body = migration(&body)?;
+    }
+
+    Ok(body)
+}
+
+///
+/// TESTS
+///
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::types::RowFormat;
+
+    #[test]
+    fn unversioned_rows_are_version_one() {
+        let body = serialize(&("a", 1u32)).unwrap();
+        let (version, rest) = row_version(&body);
+
+        assert_eq!(version, 1);
+        assert_eq!(rest, body.as_slice());
+    }
+
+    #[test]
+    fn header_round_trip() {
+        let body = serialize(&("a", 1u32)).unwrap();
+        let bytes = with_header(7, RowCodec::DEFAULT, &body);
+        let (version, rest) = row_version(&bytes);
+
+        assert_eq!(version, 7);
+        assert_eq!(rest, body.as_slice());
+        assert_eq!(bytes.len(), ROW_HEADER_LEN + body.len());
+    }
+
+    #[test]
+    fn codec_header_round_trip() {
+        let codec = RowCodec::new(RowFormat::Compact, true);
+        let bytes = with_header(3, codec, &[1, 2, 3]);
+        let (version, rest) = row_version(&bytes);
+
+        assert_eq!(bytes[..2], [ROW_CODEC_MARKER, codec.tag()]);
+        assert_eq!(version, 3);
+        assert_eq!(rest, &[1, 2, 3]);
+    }
+}
@@ -0,0 +1,303 @@
+use crate::{SCHEMA_MEMORY_ID, core::Key, db::store::StoreError};
+use candid::CandidType;
+use canic::{
+    cdk::structures::{Cell, DefaultMemoryImpl, memory::VirtualMemory},
+    impl_storable_unbounded,
+};
+use derive_more::{Deref, DerefMut};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+///
+/// SchemaStore
+///
+/// The schema the canister's stores were last written with, kept in mimic's
+/// reserved `SCHEMA_MEMORY_ID` slot so upgrades can be checked against it.
+///
+
+#[derive(Deref, DerefMut)]
+pub struct SchemaStore(Cell<SchemaRecord, VirtualMemory<DefaultMemoryImpl>>);
+
+impl SchemaStore {
+    #[must_use]
+    pub fn init(memory: VirtualMemory<DefaultMemoryImpl>) -> Self {
+        Self(Cell::init(memory, SchemaRecord::default()))
+    }
+
+    /// Init in `SCHEMA_MEMORY_ID`, registered under mimic's own crate name
+    /// as canic allows one range per crate.
+    #[must_use]
+    pub fn reserved() -> Self {
+        canic::ic_memory_range!(SCHEMA_MEMORY_ID, SCHEMA_MEMORY_ID);
+
+        Self::init(canic::ic_memory!(SchemaStore, SCHEMA_MEMORY_ID))
+    }
+}
+
+///
+/// SchemaRecord
+///
+/// Fingerprints of one canister's stores and entities, computed at build time.
+/// An empty `hash` means nothing has been recorded yet. `key_encoding` is the
+/// `Key::ENCODING_VERSION` data keys were written with, 0 for CBOR keys.
+///
+
+#[derive(CandidType, Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct SchemaRecord {
+    pub hash: String,
+    pub stores: BTreeMap<String, StoreFingerprint>,
+    pub entities: BTreeMap<String, EntityFingerprint>,
+    #[serde(default)]
+    pub key_encoding: u8,
+}
+
+impl SchemaRecord {
+    /// Build a record from the tables emitted by `mimic_build`.
+    #[must_use]
+    pub fn new(
+        hash: &str,
+        stores: &[(&str, &str, u8, u64)],
+        entities: &[(&str, &str, u32, u64, u64)],
+    ) -> Self {
+        let stores = stores
+            .iter()
+            .map(|(path, ty, memory_id, fingerprint)| {
+                let store = StoreFingerprint {
+                    ty: (*ty).to_string(),
+                    memory_id: *memory_id,
+                    fingerprint: *fingerprint,
+                };
+
+                ((*path).to_string(), store)
+            })
+            .collect();
+
+        let entities = entities
+            .iter()
+            .map(|(path, store, version, layout, indexes)| {
+                let entity = EntityFingerprint {
+                    store: (*store).to_string(),
+                    version: *version,
+                    layout: *layout,
+                    indexes: *indexes,
+                };
+
+                ((*path).to_string(), entity)
+            })
+            .collect();
+
+        Self {
+            hash: hash.to_string(),
+            stores,
+            entities,
+            key_encoding: Key::ENCODING_VERSION,
+        }
+    }
+
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.hash.is_empty()
+    }
+
+    /// What moving the stored data from this record to `current` needs, or an
+    /// error listing every change the stored data can't survive.
+    pub fn upgrade_to(&self, current: &Self) -> Result<SchemaUpgrade, StoreError> {
+        if self.key_encoding > current.key_encoding {
+            return Err(StoreError::SchemaIncompatible(vec![format!(
+                "data keys went back from encoding {} to {}",
+                self.key_encoding, current.key_encoding
+            )]));
+        }
+
+        // canisters from before the schema record have no record but may hold rows
+        let mut upgrade = SchemaUpgrade {
+            rekey: self.key_encoding < current.key_encoding,
+            ..SchemaUpgrade::default()
+        };
+        if self.is_empty() || self.hash == current.hash {
+            return Ok(upgrade);
+        }
+
+        let mut problems = Vec::new();
+        for (path, old) in &self.stores {
+            match current.stores.get(path) {
+                None => problems.push(format!("store {path} was removed")),
+                Some(new) if new.ty != old.ty || new.memory_id != old.memory_id => {
+                    problems.push(format!(
+                        "store {path} moved from {} at memory {} to {} at memory {}",
+                        old.ty, old.memory_id, new.ty, new.memory_id
+                    ));
+                }
+                Some(_) => {}
+            }
+        }
+
+        for (path, old) in &self.entities {
+            let Some(new) = current.entities.get(path) else {
+                problems.push(format!("entity {path} was removed or renamed"));
+                continue;
+            };
+
+            // unchanged stores hold unchanged entities
+            let store_changed = self.stores.get(&old.store).map(|s| s.fingerprint)
+                != current.stores.get(&new.store).map(|s| s.fingerprint);
+            if !store_changed {
+                continue;
+            }
+
+            if new.store != old.store {
+                problems.push(format!(
+                    "entity {path} moved from store {} to {}",
+                    old.store, new.store
+                ));
+            } else if new.version < old.version {
+                problems.push(format!(
+                    "entity {path} went back from version {} to {}",
+                    old.version, new.version
+                ));
+            } else if new.version > old.version {
+                upgrade.migrate.push(path.clone());
+            } else if new.layout != old.layout {
+                problems.push(format!(
+                    "entity {path} changed its encoding without a version bump"
+                ));
+            }
+
+            if new.indexes != old.indexes {
+                upgrade.reindex.push(path.clone());
+            }
+        }
+
+        if problems.is_empty() {
+            Ok(upgrade)
+        } else {
+            Err(StoreError::SchemaIncompatible(problems))
+        }
+    }
+}
+
+impl_storable_unbounded!(SchemaRecord);
+
+///
+/// StoreFingerprint
+///
+/// `fingerprint` covers the store itself and everything kept in it, so an
+/// unchanged value means none of its entities or indexes changed.
+///
+
+#[derive(CandidType, Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct StoreFingerprint {
+    pub ty: String,
+    pub memory_id: u8,
+    pub fingerprint: u64,
+}
+
+///
+/// EntityFingerprint
+///
+/// `layout` covers how rows are encoded (store, primary key, fields and the
+/// records, enums and other type nodes they reach),
+/// `indexes` covers the entity's index definitions.
+///
+
+#[derive(CandidType, Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct EntityFingerprint {
+    pub store: String,
+    pub version: u32,
+    pub layout: u64,
+    pub indexes: u64,
+}
+
+///
+/// SchemaUpgrade
+///
+/// Entities whose stored rows need migrating or whose indexes need rebuilding,
+/// and whether data keys need rewriting in the current encoding.
+///
+
+#[derive(CandidType, Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct SchemaUpgrade {
+    pub migrate: Vec<String>,
+    pub reindex: Vec<String>,
+    pub rekey: bool,
+}
+
+impl SchemaUpgrade {
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.migrate.is_empty() && self.reindex.is_empty() && !self.rekey
+    }
+}
+
+///
+/// TESTS
+///
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(hash: &str, store_fp: u64, version: u32, layout: u64, indexes: u64) -> SchemaRecord {
+        SchemaRecord::new(
+            hash,
+            &[("app::Data", "Data", 1, store_fp)],
+            &[("app::User", "app::Data", version, layout, indexes)],
+        )
+    }
+
+    #[test]
+    fn first_record_only_rekeys() {
+        let upgrade = SchemaRecord::default()
+            .upgrade_to(&record("a", 1, 1, 1, 1))
+            .unwrap();
+
+        assert!(upgrade.rekey);
+        assert!(upgrade.migrate.is_empty() && upgrade.reindex.is_empty());
+        assert!(record("a", 1, 1, 1, 1).upgrade_to(&record("a", 1, 1, 1, 1)).unwrap().is_empty());
+    }
+
+    #[test]
+    fn older_key_encoding_rekeys() {
+        let mut old = record("a", 1, 1, 1, 1);
+        old.key_encoding = 0;
+
+        assert!(old.upgrade_to(&record("a", 1, 1, 1, 1)).unwrap().rekey);
+        assert!(record("a", 1, 1, 1, 1).upgrade_to(&old).is_err());
+    }
+
+    #[test]
+    fn version_bump_migrates() {
+        let upgrade = record("a", 1, 1, 1, 1)
+            .upgrade_to(&record("b", 2, 2, 2, 1))
+            .unwrap();
+
+        assert_eq!(upgrade.migrate, vec!["app::User".to_string()]);
+        assert!(upgrade.reindex.is_empty());
+    }
+
+    #[test]
+    fn index_change_reindexes() {
+        let upgrade = record("a", 1, 1, 1, 1)
+            .upgrade_to(&record("b", 2, 1, 1, 2))
+            .unwrap();
+
+        assert_eq!(upgrade.reindex, vec!["app::User".to_string()]);
+    }
+
+    #[test]
+    fn layout_change_without_version_fails() {
+        let res = record("a", 1, 1, 1, 1).upgrade_to(&record("b", 2, 1, 2, 1));
+
+        assert!(matches!(res, Err(StoreError::SchemaIncompatible(p)) if p.len() == 1));
+    }
+
+    #[test]
+    fn moved_store_fails() {
+        let old = record("a", 1, 1, 1, 1);
+        let mut new = old.clone();
+        new.hash = "b".to_string();
+        new.stores.get_mut("app::Data").unwrap().memory_id = 2;
+
+        assert!(old.upgrade_to(&new).is_err());
+    }
+}
@@ -13,9 +13,9 @@ pub mod prelude {
         core::{
             Key, Value,
             traits::{
-                EntityKind, FieldValue as _, Inner as _, Path as _, Sanitize as _, Sanitizer as _,
-                Serialize as _, Validate as _, ValidateCustom, Validator as _, View as _,
-                Visitable as _,
+                EntityHooks, EntityKind, FieldValue as _, Inner as _, Path as _, Sanitize as _,
+                Sanitizer as _, Serialize as _, Validate as _, ValidateCustom, Validator as _,
+                View as _, Visitable as _,
             },
             view::View,
         },
@@ -0,0 +1,41 @@
+use crate::{Error, interface::InterfaceError};
+use candid::Principal;
+use canic::cdk::api::{is_controller, msg_caller};
+use thiserror::Error as ThisError;
+
+///
+/// AccessError
+///
+
+#[derive(Debug, ThisError)]
+pub enum AccessError {
+    #[error("caller '{0}' is not a controller")]
+    NotController(Principal),
+
+    #[error("access denied: {0}")]
+    Denied(String),
+}
+
+impl From<AccessError> for Error {
+    fn from(err: AccessError) -> Self {
+        InterfaceError::from(err).into()
+    }
+}
+
+/// Reject callers that don't control this canister.
+pub fn require_controller() -> Result<(), Error> {
+    let caller = msg_caller();
+
+    if is_controller(&caller) {
+        Ok(())
+    } else {
+        Err(AccessError::NotController(caller))?
+    }
+}
+
+/// Run a schema-declared guard, turning its rejection into an [`AccessError`].
+pub fn require_guard(guard: fn() -> Result<(), String>) -> Result<(), Error> {
+    guard().map_err(AccessError::Denied)?;
+
+    Ok(())
+}
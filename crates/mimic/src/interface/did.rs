@@ -0,0 +1,62 @@
+use crate::{
+    Error,
+    build::did::Builtins,
+    core::{
+        Key, Value,
+        traits::{UpdateView, View},
+    },
+    db::{
+        jobs::{Job, JobKind},
+        query::{DeleteQuery, LoadQuery, SaveQuery},
+        store::ChangePage,
+    },
+    interface::query::{ValueRow, ViewPage},
+    obs::{metrics::EventReport, schema::SchemaReport, snapshot::StorageReport},
+    schema::types::Primitive,
+    types,
+};
+use std::collections::BTreeMap;
+
+// add_primitives
+// the View and Update type of each `Primitive` variant
+macro_rules! add_primitives {
+    ($builtins:expr, $($prim:ident),* $(,)?) => {
+        $(
+            $builtins.add_primitive::<
+                <types::$prim as View>::ViewType,
+                <types::$prim as UpdateView>::UpdateViewType,
+            >(Primitive::$prim);
+        )*
+    };
+}
+
+/// The candid types `mimic_build` needs to describe the generated endpoints,
+/// see [`crate::build::generate_did`].
+#[must_use]
+pub fn builtins() -> Builtins {
+    let mut builtins = Builtins::new();
+
+    builtins
+        .add::<ChangePage>("ChangePage")
+        .add::<DeleteQuery>("DeleteQuery")
+        .add::<Error>("Error")
+        .add::<EventReport>("EventReport")
+        .add::<Job>("Job")
+        .add::<JobKind>("JobKind")
+        .add::<Key>("Key")
+        .add::<LoadQuery>("LoadQuery")
+        .add::<SaveQuery>("SaveQuery")
+        .add::<SchemaReport>("SchemaReport")
+        .add::<StorageReport>("StorageReport")
+        .add::<ValueRow>("ValueRow")
+        .add::<BTreeMap<String, Value>>("Values")
+        .add::<ViewPage>("ViewPage");
+
+    add_primitives!(
+        builtins, Account, Blob, Bool, Date, Decimal, Duration, E8s, E18s, Float32, Float64, Int,
+        Int8, Int16, Int32, Int64, Int128, Nat, Nat8, Nat16, Nat32, Nat64, Nat128, Principal,
+        Subaccount, Text, Timestamp, Ulid, Unit,
+    );
+
+    builtins
+}
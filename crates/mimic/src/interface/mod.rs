@@ -1,4 +1,7 @@
+pub mod access;
+pub mod did;
 pub mod query;
+pub mod remote;
 
 use thiserror::Error as ThisError;
 
@@ -8,6 +11,12 @@ use thiserror::Error as ThisError;
 
 #[derive(Debug, ThisError)]
 pub enum InterfaceError {
+    #[error(transparent)]
+    AccessError(#[from] access::AccessError),
+
     #[error(transparent)]
     QueryError(#[from] query::QueryError),
+
+    #[error(transparent)]
+    RemoteError(#[from] remote::RemoteError),
 }
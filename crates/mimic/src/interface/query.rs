@@ -1,11 +1,18 @@
 use crate::{
     Error,
-    core::Key,
-    db::query::{DeleteQuery, LoadQuery, SaveQuery},
-    interface::InterfaceError,
+    core::{Key, Value, serialize, traits::EntityKind, value::entity_values},
+    db::{
+        query::{DeleteQuery, LoadQuery, SaveQuery},
+        response::{NextPage, Page, Response},
+    },
+    interface::{
+        InterfaceError,
+        remote::{self, IcTransport},
+    },
 };
-use candid::Principal;
-use canic::{Error as CanicError, cdk::call::Call};
+use candid::{CandidType, Principal};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use thiserror::Error as ThisError;
 
 ///
@@ -24,34 +31,109 @@ impl From<QueryError> for Error {
     }
 }
 
+///
+/// ViewPage
+///
+/// A page of rows as returned by the generated `mimic_query_load_views`
+/// endpoint. Each view is serialized, so one payload type serves every entity.
+///
+
+#[derive(CandidType, Clone, Debug, Default, Deserialize, Serialize)]
+pub struct ViewPage {
+    pub rows: Vec<ViewRow>,
+    pub total: Option<u64>,
+    pub next: Option<NextPage>,
+}
+
+///
+/// ViewRow
+///
+
+#[derive(CandidType, Clone, Debug, Deserialize, Serialize)]
+pub struct ViewRow {
+    pub key: Key,
+    pub view: Vec<u8>,
+}
+
+///
+/// ValueRow
+///
+/// A row as field name → `Value`, as returned by the generated `*_values`
+/// endpoints for tools that don't know the entity types.
+///
+
+#[derive(CandidType, Clone, Debug, Deserialize, Serialize)]
+pub struct ValueRow {
+    pub key: Key,
+    pub values: BTreeMap<String, Value>,
+}
+
+impl ValueRow {
+    #[must_use]
+    pub fn new<E: EntityKind>(entity: &E) -> Self {
+        Self {
+            key: entity.key(),
+            values: entity_values(entity),
+        }
+    }
+}
+
 // query_load
-pub async fn query_load(pid: Principal, query: LoadQuery) -> Result<Vec<Key>, Error> {
-    query_call(pid, "mimic_query_load", query).await
+pub async fn query_load(pid: Principal, path: &str, query: LoadQuery) -> Result<Vec<Key>, Error> {
+    remote::call(&IcTransport, pid, "mimic_query_load", (path, query)).await
 }
 
 // query_save
-pub async fn query_save(pid: Principal, query: SaveQuery) -> Result<Key, Error> {
-    query_call(pid, "mimic_query_save", query).await
+pub async fn query_save(pid: Principal, path: &str, query: SaveQuery) -> Result<Key, Error> {
+    remote::call(&IcTransport, pid, "mimic_query_save", (path, query)).await
 }
 
 // query_delete
-pub async fn query_delete(pid: Principal, query: DeleteQuery) -> Result<Vec<Key>, Error> {
-    query_call(pid, "mimic_query_delete", query).await
+pub async fn query_delete(
+    pid: Principal,
+    path: &str,
+    query: DeleteQuery,
+) -> Result<Vec<Key>, Error> {
+    remote::call(&IcTransport, pid, "mimic_query_delete", (path, query)).await
 }
 
-// query_call
-// private helper method
-async fn query_call<T: candid::CandidType + for<'de> candid::Deserialize<'de>>(
-    pid: Principal,
-    method: &str,
-    arg: impl candid::CandidType,
-) -> Result<T, Error> {
-    let result = Call::unbounded_wait(pid, method)
-        .with_arg(arg)
-        .await
-        .map_err(CanicError::from)?;
+/// Serialize the rows of a response, as returned by the generated `*_rows` endpoints.
+pub fn encode_rows<E: EntityKind>(response: Response<E>) -> Result<Vec<Vec<u8>>, Error> {
+    response
+        .entities_iter()
+        .map(|entity| serialize(&entity))
+        .collect()
+}
+
+/// The rows of a response as values, as returned by `mimic_query_load_values`.
+#[must_use]
+pub fn value_rows<E: EntityKind>(response: Response<E>) -> Vec<ValueRow> {
+    response
+        .entities_iter()
+        .map(|e| ValueRow::new(&e))
+        .collect()
+}
 
-    let response = result.candid::<T>().map_err(CanicError::from)?;
+/// Serialize the views of a page, as returned by `mimic_query_load_views`.
+pub fn encode_view_page<E>(page: Page<E>) -> Result<ViewPage, Error>
+where
+    E: EntityKind,
+    E::ViewType: Serialize,
+{
+    let rows = page
+        .rows
+        .into_iter()
+        .map(|(key, entity)| {
+            Ok(ViewRow {
+                key,
+                view: serialize(&entity.to_view())?,
+            })
+        })
+        .collect::<Result<_, Error>>()?;
 
-    Ok(response)
+    Ok(ViewPage {
+        rows,
+        total: page.total,
+        next: page.next,
+    })
 }
@@ -0,0 +1,522 @@
+use crate::{
+    Error,
+    core::{
+        Key, deserialize, serialize,
+        traits::{CanisterKind, EntityKind, UpdateView},
+    },
+    db::{
+        query::{DeleteQuery, LoadQuery, SaveMode, SaveQuery},
+        response::Page,
+    },
+    interface::{InterfaceError, query::ViewPage},
+};
+use candid::{CandidType, Principal, utils::ArgumentEncoder};
+use canic::{Error as CanicError, cdk::call::Call};
+use serde::de::DeserializeOwned;
+use std::{future::Future, marker::PhantomData};
+use thiserror::Error as ThisError;
+
+///
+/// RemoteError
+///
+
+#[derive(Debug, ThisError)]
+pub enum RemoteError {
+    #[error("candid error calling '{0}': {1}")]
+    CandidError(String, String),
+}
+
+impl From<RemoteError> for Error {
+    fn from(err: RemoteError) -> Self {
+        InterfaceError::from(err).into()
+    }
+}
+
+///
+/// Transport
+///
+/// Sends candid-encoded arguments to a canister method and returns the raw
+/// reply, so `RemoteDb` can run against a mock in tests.
+///
+
+pub trait Transport {
+    fn call(
+        &self,
+        canister: Principal,
+        method: &str,
+        args: Vec<u8>,
+    ) -> impl Future<Output = Result<Vec<u8>, Error>>;
+}
+
+///
+/// IcTransport
+///
+/// Inter-canister calls through the management API.
+///
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct IcTransport;
+
+impl Transport for IcTransport {
+    async fn call(
+        &self,
+        canister: Principal,
+        method: &str,
+        args: Vec<u8>,
+    ) -> Result<Vec<u8>, Error> {
+        let response = Call::unbounded_wait(canister, method)
+            .with_raw_args(&args)
+            .await
+            .map_err(CanicError::from)?;
+
+        Ok(response.into_bytes())
+    }
+}
+
+///
+/// RemoteDb
+///
+/// A typed client for the `mimic_query_*` endpoints of another canister
+/// built with `mimic_build`. Entities travel serialized and are decoded on
+/// this side; errors returned by the remote canister come back as they were.
+///
+/// - `C` is the [`CanisterKind`] the remote canister was built for.
+///
+
+pub struct RemoteDb<C: CanisterKind, T: Transport = IcTransport> {
+    canister: Principal,
+    transport: T,
+    _marker: PhantomData<C>,
+}
+
+impl<C: CanisterKind> RemoteDb<C> {
+    #[must_use]
+    pub const fn new(canister: Principal) -> Self {
+        Self::with_transport(canister, IcTransport)
+    }
+}
+
+// canister code runs on one thread, so these futures needn't be Send
+#[allow(clippy::future_not_send)]
+impl<C: CanisterKind, T: Transport> RemoteDb<C, T> {
+    #[must_use]
+    pub const fn with_transport(canister: Principal, transport: T) -> Self {
+        Self {
+            canister,
+            transport,
+            _marker: PhantomData,
+        }
+    }
+
+    #[must_use]
+    pub const fn canister(&self) -> Principal {
+        self.canister
+    }
+
+    //
+    // Load
+    //
+
+    pub async fn load<E>(&self, query: LoadQuery) -> Result<Vec<E>, Error>
+    where
+        E: EntityKind<Canister = C>,
+    {
+        let rows: Vec<Vec<u8>> = self.call("mimic_query_load_rows", (E::PATH, query)).await?;
+
+        rows.iter().map(|bytes| deserialize(bytes)).collect()
+    }
+
+    pub async fn load_views<E>(&self, query: LoadQuery) -> Result<Vec<E::ViewType>, Error>
+    where
+        E: EntityKind<Canister = C>,
+    {
+        let entities = self.load::<E>(query).await?;
+
+        Ok(entities.iter().map(E::to_view).collect())
+    }
+
+    /// One page of views, with the total match count and where the next page
+    /// starts; pass `next` to [`LoadQuery::page`] to fetch it.
+    pub async fn load_page<E>(&self, query: LoadQuery) -> Result<Page<E::ViewType>, Error>
+    where
+        E: EntityKind<Canister = C>,
+        E::ViewType: DeserializeOwned,
+    {
+        let page: ViewPage = self
+            .call("mimic_query_load_views", (E::PATH, query))
+            .await?;
+        let rows = page
+            .rows
+            .iter()
+            .map(|row| Ok((row.key, deserialize(&row.view)?)))
+            .collect::<Result<_, Error>>()?;
+
+        Ok(Page {
+            rows,
+            total: page.total,
+            next: page.next,
+        })
+    }
+
+    /// Keys of the matching rows, without transferring the rows themselves.
+    pub async fn load_keys<E>(&self, query: LoadQuery) -> Result<Vec<Key>, Error>
+    where
+        E: EntityKind<Canister = C>,
+    {
+        self.call("mimic_query_load", (E::PATH, query)).await
+    }
+
+    //
+    // Save
+    //
+
+    /// Save `entity` and return it as stored, after the remote canister's
+    /// sanitizers and hooks have run.
+    pub async fn save<E>(&self, mode: SaveMode, entity: E) -> Result<E, Error>
+    where
+        E: EntityKind<Canister = C>,
+    {
+        let query = SaveQuery::new(mode).from_entity(entity)?;
+        let bytes: Vec<u8> = self.call("mimic_query_save_row", (E::PATH, query)).await?;
+
+        deserialize(&bytes)
+    }
+
+    pub async fn insert<E>(&self, entity: E) -> Result<E, Error>
+    where
+        E: EntityKind<Canister = C>,
+    {
+        self.save(SaveMode::Insert, entity).await
+    }
+
+    pub async fn replace<E>(&self, entity: E) -> Result<E, Error>
+    where
+        E: EntityKind<Canister = C>,
+    {
+        self.save(SaveMode::Replace, entity).await
+    }
+
+    pub async fn update<E>(&self, entity: E) -> Result<E, Error>
+    where
+        E: EntityKind<Canister = C>,
+    {
+        self.save(SaveMode::Update, entity).await
+    }
+
+    /// Merge an update view into an existing row on the remote canister.
+    pub async fn patch<E>(&self, key: impl Into<Key>, patch: E::UpdateViewType) -> Result<E, Error>
+    where
+        E: EntityKind<Canister = C> + UpdateView,
+        E::UpdateViewType: serde::Serialize,
+    {
+        let patch = serialize(&patch)?;
+        let bytes: Vec<u8> = self
+            .call("mimic_query_patch", (E::PATH, key.into(), patch))
+            .await?;
+
+        deserialize(&bytes)
+    }
+
+    //
+    // Delete
+    //
+
+    /// Delete the matching rows and return their keys.
+    pub async fn delete<E>(&self, query: DeleteQuery) -> Result<Vec<Key>, Error>
+    where
+        E: EntityKind<Canister = C>,
+    {
+        self.call("mimic_query_delete", (E::PATH, query)).await
+    }
+
+    // call
+    async fn call<R>(&self, method: &str, args: impl ArgumentEncoder) -> Result<R, Error>
+    where
+        R: CandidType + DeserializeOwned,
+    {
+        call(&self.transport, self.canister, method, args).await
+    }
+}
+
+// call
+// encodes the arguments, and decodes a `Result<R, Error>` reply
+#[allow(clippy::future_not_send)]
+pub(crate) async fn call<R>(
+    transport: &impl Transport,
+    canister: Principal,
+    method: &str,
+    args: impl ArgumentEncoder,
+) -> Result<R, Error>
+where
+    R: CandidType + DeserializeOwned,
+{
+    let candid_error =
+        |e: candid::Error| RemoteError::CandidError(method.to_string(), e.to_string());
+
+    let args = candid::encode_args(args).map_err(candid_error)?;
+    let reply = transport.call(canister, method, args).await?;
+
+    candid::decode_one::<Result<R, Error>>(&reply).map_err(candid_error)?
+}
+
+///
+/// TESTS
+///
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        core::{
+            Value,
+            traits::{
+                EntityHooks, FieldValues, Path, SanitizeAuto, SanitizeCustom, StoreKind,
+                ValidateAuto, ValidateCustom, View, Visitable,
+            },
+            value::ValueFamily,
+        },
+        db::{
+            response::NextPage,
+            store::{RowCodec, RowMigration},
+        },
+        interface::query::ViewRow,
+        schema::node::{Field, Index},
+    };
+    use serde::{Deserialize, Serialize};
+    use std::{
+        cell::RefCell,
+        pin::pin,
+        task::{Context, Poll, Waker},
+    };
+
+    #[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+    struct Note {
+        id: u64,
+        text: String,
+    }
+
+    struct NoteCanister;
+    struct NoteStore;
+
+    impl Path for NoteCanister {
+        const PATH: &'static str = "test::canister";
+    }
+
+    impl CanisterKind for NoteCanister {}
+
+    impl Path for NoteStore {
+        const PATH: &'static str = "test::store";
+    }
+
+    impl StoreKind for NoteStore {
+        type Canister = NoteCanister;
+    }
+
+    impl Path for Note {
+        const PATH: &'static str = "test::note";
+    }
+
+    impl View for Note {
+        type ViewType = Self;
+
+        fn to_view(&self) -> Self::ViewType {
+            self.clone()
+        }
+
+        fn from_view(view: Self::ViewType) -> Self {
+            view
+        }
+    }
+
+    impl UpdateView for Note {
+        type UpdateViewType = Option<String>;
+
+        fn merge(&mut self, patch: Self::UpdateViewType) {
+            if let Some(text) = patch {
+                self.text = text;
+            }
+        }
+    }
+
+    impl SanitizeAuto for Note {}
+    impl SanitizeCustom for Note {}
+    impl ValidateAuto for Note {}
+    impl ValidateCustom for Note {}
+    impl EntityHooks for Note {}
+    impl Visitable for Note {}
+
+    impl FieldValues for Note {
+        fn get_value(&self, field: &str) -> Option<Value> {
+            match field {
+                "id" => Some(Value::Uint(self.id)),
+                "text" => Some(Value::Text(self.text.clone())),
+                _ => None,
+            }
+        }
+
+        fn path_family(field: &str) -> Option<ValueFamily> {
+            match field {
+                "id" => Some(ValueFamily::Numeric),
+                "text" => Some(ValueFamily::Textual),
+                _ => None,
+            }
+        }
+    }
+
+    impl EntityKind for Note {
+        type PrimaryKey = u64;
+        type Store = NoteStore;
+        type Canister = NoteCanister;
+
+        const ENTITY_ID: u64 = 98;
+        const PRIMARY_KEY: &'static str = "id";
+        const FIELDS: &'static [&'static str] = &["id", "text"];
+        const INDEXES: &'static [&'static Index] = &[];
+        const RELATIONS: &'static [&'static Field] = &[];
+        const SOFT_DELETE: bool = false;
+        const TTL_FIELD: Option<&'static str> = None;
+        const HISTORY_LIMIT: Option<u32> = None;
+        const SCHEMA_VERSION: u32 = 1;
+        const MIGRATIONS: &'static [RowMigration] = &[];
+        const CODEC: RowCodec = RowCodec::DEFAULT;
+        const RETIRED_FIELDS: &'static [&'static str] = &[];
+
+        fn key(&self) -> Key {
+            self.id.into()
+        }
+
+        fn primary_key(&self) -> Self::PrimaryKey {
+            self.id
+        }
+    }
+
+    ///
+    /// MockTransport
+    /// records each call and answers with a canned reply
+    ///
+
+    struct MockTransport {
+        calls: RefCell<Vec<(String, Vec<u8>)>>,
+        reply: Vec<u8>,
+    }
+
+    impl MockTransport {
+        fn replying<R: CandidType>(reply: Result<R, Error>) -> Self {
+            Self {
+                calls: RefCell::default(),
+                reply: candid::encode_one(reply).unwrap(),
+            }
+        }
+
+        fn last_call(&self) -> (String, Vec<u8>) {
+            self.calls.borrow().last().cloned().unwrap()
+        }
+    }
+
+    #[allow(clippy::future_not_send)]
+    impl Transport for &MockTransport {
+        async fn call(
+            &self,
+            _canister: Principal,
+            method: &str,
+            args: Vec<u8>,
+        ) -> Result<Vec<u8>, Error> {
+            self.calls.borrow_mut().push((method.to_string(), args));
+
+            Ok(self.reply.clone())
+        }
+    }
+
+    fn remote(transport: &MockTransport) -> RemoteDb<NoteCanister, &MockTransport> {
+        RemoteDb::with_transport(Principal::anonymous(), transport)
+    }
+
+    // the mock never suspends, so one poll finishes the call
+    fn block_on<F: Future>(future: F) -> F::Output {
+        match pin!(future).poll(&mut Context::from_waker(Waker::noop())) {
+            Poll::Ready(output) => output,
+            Poll::Pending => panic!("mock transport call did not complete"),
+        }
+    }
+
+    fn note(id: u64, text: &str) -> Note {
+        Note {
+            id,
+            text: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn load_sends_path_and_decodes_rows() {
+        let rows = vec![
+            serialize(&note(1, "a")).unwrap(),
+            serialize(&note(2, "b")).unwrap(),
+        ];
+        let transport = MockTransport::replying(Ok(rows));
+
+        let notes = block_on(remote(&transport).load::<Note>(LoadQuery::new())).unwrap();
+        assert_eq!(notes, vec![note(1, "a"), note(2, "b")]);
+
+        let (method, args) = transport.last_call();
+        let (path, _): (String, LoadQuery) = candid::decode_args(&args).unwrap();
+        assert_eq!(method, "mimic_query_load_rows");
+        assert_eq!(path, Note::PATH);
+    }
+
+    #[test]
+    fn load_page_decodes_views() {
+        let page = ViewPage {
+            rows: vec![ViewRow {
+                key: Key::from(3u64),
+                view: serialize(&note(3, "c")).unwrap(),
+            }],
+            total: Some(9),
+            next: Some(NextPage::Offset(1)),
+        };
+        let transport = MockTransport::replying(Ok(page));
+
+        let page = block_on(remote(&transport).load_page::<Note>(LoadQuery::new())).unwrap();
+        assert_eq!(page.rows, vec![(Key::from(3u64), note(3, "c"))]);
+        assert_eq!(page.total, Some(9));
+        assert_eq!(page.next, Some(NextPage::Offset(1)));
+        assert_eq!(transport.last_call().0, "mimic_query_load_views");
+    }
+
+    #[test]
+    fn patch_sends_key_and_update_view() {
+        let transport = MockTransport::replying(Ok(serialize(&note(7, "new")).unwrap()));
+
+        let saved = block_on(remote(&transport).patch::<Note>(7u64, Some("new".to_string())));
+        assert_eq!(saved.unwrap(), note(7, "new"));
+
+        let (method, args) = transport.last_call();
+        let (path, key, update): (String, Key, Vec<u8>) = candid::decode_args(&args).unwrap();
+        assert_eq!(method, "mimic_query_patch");
+        assert_eq!(path, Note::PATH);
+        assert_eq!(key, Key::from(7u64));
+        assert_eq!(
+            deserialize::<Option<String>>(&update).unwrap().as_deref(),
+            Some("new")
+        );
+    }
+
+    #[test]
+    fn remote_errors_come_back_unchanged() {
+        let transport =
+            MockTransport::replying::<Vec<Key>>(Err(Error::DbError("key exists".to_string())));
+
+        let err = block_on(remote(&transport).delete::<Note>(DeleteQuery::new())).unwrap_err();
+        assert!(matches!(err, Error::DbError(msg) if msg == "key exists"));
+    }
+
+    #[test]
+    fn bad_replies_are_candid_errors() {
+        let transport = MockTransport {
+            calls: RefCell::default(),
+            reply: vec![0, 1, 2],
+        };
+
+        let err = block_on(remote(&transport).load_keys::<Note>(LoadQuery::new())).unwrap_err();
+        assert!(matches!(err, Error::InterfaceError(msg) if msg.contains("mimic_query_load")));
+    }
+}
@@ -53,6 +53,8 @@ pub const VERSION: &str = env!("CARGO_PKG_VERSION");
 
 pub const MAX_INDEX_FIELDS: usize = 4;
 
+pub use mimic_schema::SCHEMA_MEMORY_ID;
+
 ///
 /// MIMIC PRELUDE
 /// using _ brings traits into scope and avoids name conflicts
@@ -72,8 +74,8 @@ pub mod prelude {
         db::{
             executor::SaveExecutor,
             primitives::{
-                self, FilterDsl, FilterExpr, FilterExt as _, LimitExpr, LimitExt as _, SortExpr,
-                SortExt as _,
+                self, BudgetExt as _, FilterDsl, FilterExpr, FilterExt as _, LimitExpr,
+                LimitExt as _, ResumeToken, SortExpr, SortExt as _,
             },
             query,
             response::Response,
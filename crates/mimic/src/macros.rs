@@ -1,17 +1,57 @@
 // mimic_start
 // macro to be included at the start of each canister lib.rs file
+// canisters define their own init and post_upgrade hooks and call mimic_init
+// and mimic_post_upgrade from them, or use `hooks = true` to have them generated
 #[macro_export]
 macro_rules! mimic_start {
     () => {
         // actor.rs
         include!(concat!(env!("OUT_DIR"), "/actor.rs"));
     };
+
+    (hooks = false) => {
+        $crate::mimic_start!();
+    };
+
+    (hooks = true) => {
+        $crate::mimic_start!();
+
+        #[$crate::export::canic::cdk::init]
+        fn init() {
+            mimic_init();
+        }
+
+        #[$crate::export::canic::cdk::post_upgrade]
+        fn post_upgrade() {
+            mimic_post_upgrade();
+        }
+    };
 }
 
 // mimic_build
 // for the various build.rs files
 #[macro_export]
 macro_rules! mimic_build {
+    // also fail the build on breaking schema changes against a committed snapshot
+    ($actor:expr, snapshot = $snapshot:expr) => {
+        println!("cargo:rerun-if-changed={}", $snapshot);
+        println!(
+            "cargo:rerun-if-env-changed={}",
+            ::mimic::build::compat::ACCEPT_ENV
+        );
+        match ::mimic::build::check_schema($snapshot) {
+            Ok(::mimic::build::compat::SnapshotCheck::Outdated) => println!(
+                "cargo:warning=schema snapshot '{}' is out of date, set {}=1 to update it",
+                $snapshot,
+                ::mimic::build::compat::ACCEPT_ENV
+            ),
+            Ok(_) => {}
+            Err(e) => panic!("{e}"),
+        }
+
+        $crate::mimic_build!($actor);
+    };
+
     ($actor:expr) => {
         use std::{env::var, fs::File, io::Write, path::PathBuf};
 
@@ -42,6 +82,28 @@ macro_rules! mimic_build {
         let actor_file = PathBuf::from(out_dir.clone()).join("actor.rs");
         let mut file = File::create(actor_file)?;
         file.write_all(output.as_bytes())?;
+
+        //
+        // CANDID
+        // the generated endpoints and entity views, next to actor.rs
+        //
+
+        let did = ::mimic::build::generate_did($actor, ::mimic::interface::did::builtins());
+
+        let did_file = PathBuf::from(out_dir.clone()).join("actor.did");
+        let mut file = File::create(did_file)?;
+        file.write_all(did.as_bytes())?;
+
+        //
+        // TYPESCRIPT
+        // schema types and a typed client, importing the bindings of actor.did
+        //
+
+        let ts = ::mimic::build::generate_ts($actor);
+
+        let ts_file = PathBuf::from(out_dir.clone()).join("actor.ts");
+        let mut file = File::create(ts_file)?;
+        file.write_all(ts.as_bytes())?;
     };
 }
 
@@ -1,6 +1,6 @@
 use crate::core::traits::EntityKind;
 use candid::CandidType;
-use canic::{cdk::api::performance_counter, utils::time};
+use canic::utils::time;
 use serde::{Deserialize, Serialize};
 use std::cmp::Ordering;
 use std::{cell::RefCell, collections::BTreeMap, marker::PhantomData};
@@ -53,6 +53,10 @@ pub struct EventOps {
     pub index_inserts: u64,
     pub index_removes: u64,
     pub unique_violations: u64,
+
+    // TTL sweeper
+    pub ttl_sweeps: u64,
+    pub rows_expired: u64,
 }
 
 ///
@@ -69,6 +73,8 @@ pub struct EntityCounters {
     pub index_inserts: u64,
     pub index_removes: u64,
     pub unique_violations: u64,
+    pub ttl_sweeps: u64,
+    pub rows_expired: u64,
 }
 
 ///
@@ -142,13 +148,27 @@ pub fn exec_start(kind: ExecKind) -> u64 {
         ExecKind::Delete => m.ops.delete_calls = m.ops.delete_calls.saturating_add(1),
     });
 
-    // Instruction counter (counter_type = 1) is per-message and monotonic.
-    performance_counter(1)
+    instruction_counter()
+}
+
+// Instruction counter (counter_type = 1) is per-message and monotonic.
+// It traps outside a canister, so executors running natively (tests on an
+// in-memory db) count nothing.
+#[allow(clippy::missing_const_for_fn)]
+fn instruction_counter() -> u64 {
+    #[cfg(target_arch = "wasm32")]
+    {
+        canic::cdk::api::performance_counter(1)
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        0
+    }
 }
 
 /// Finish an executor timing span and aggregate instruction deltas and row counters.
 pub fn exec_finish(kind: ExecKind, start_inst: u64, rows_touched: u64) {
-    let now = performance_counter(1);
+    let now = instruction_counter();
     let delta = now.saturating_sub(start_inst);
 
     with_state_mut(|m| match kind {
@@ -292,6 +312,8 @@ pub struct EntitySummary {
     pub index_inserts: u64,
     pub index_removes: u64,
     pub unique_violations: u64,
+    pub ttl_sweeps: u64,
+    pub rows_expired: u64,
 }
 
 /// Increment unique-violation counters globally and for a specific entity type.
@@ -304,6 +326,18 @@ where
     entry.unique_violations = entry.unique_violations.saturating_add(1);
 }
 
+/// Count a TTL sweep and the rows it expired, globally and for a specific entity type.
+pub fn record_ttl_sweep_for<E>(m: &mut EventState, rows: u64)
+where
+    E: crate::core::traits::EntityKind,
+{
+    m.ops.ttl_sweeps = m.ops.ttl_sweeps.saturating_add(1);
+    m.ops.rows_expired = m.ops.rows_expired.saturating_add(rows);
+    let entry = m.entities.entry(E::PATH.to_string()).or_default();
+    entry.ttl_sweeps = entry.ttl_sweeps.saturating_add(1);
+    entry.rows_expired = entry.rows_expired.saturating_add(rows);
+}
+
 ///
 /// EventSelect
 /// Select which parts of the metrics report to include.
@@ -366,6 +400,8 @@ pub fn report() -> EventReport {
             index_inserts: ops.index_inserts,
             index_removes: ops.index_removes,
             unique_violations: ops.unique_violations,
+            ttl_sweeps: ops.ttl_sweeps,
+            rows_expired: ops.rows_expired,
         });
     }
 
@@ -1,7 +1,8 @@
-//! Observability: runtime event telemetry (metrics) and storage snapshots.
+//! Observability: runtime event telemetry (metrics), storage snapshots and the schema report.
 //! Import via `mimic::obs::*` or re-exports below.
 
 pub mod metrics;
+pub mod schema;
 pub mod snapshot;
 
 // Convenient re-exports
@@ -9,6 +10,7 @@ pub use metrics::{
     EntityCounters, EntitySummary, EventOps, EventPerf, EventReport, EventSelect, EventState, Span,
     report as event_report, reset_all as event_reset_all,
 };
+pub use schema::{EntitySchema, FieldSchema, IndexSchema, RuleSchema, SchemaReport, StoreSchema};
 pub use snapshot::{
     DataStoreSnapshot, EntitySnapshot, IndexStoreSnapshot, StorageReport, storage_report,
 };
@@ -0,0 +1,104 @@
+use crate::schema::types::{Cardinality, Primitive, StoreType};
+use candid::CandidType;
+use serde::{Deserialize, Serialize};
+
+///
+/// SchemaReport
+/// The canister's slice of the schema, as returned by `mimic_schema`
+///
+
+#[derive(CandidType, Clone, Debug, Default, Deserialize, Serialize)]
+pub struct SchemaReport {
+    /// Canister path (e.g., test::Canister)
+    pub canister: String,
+    /// Hash of the stored schema record, changes whenever stores or entities do
+    pub hash: String,
+    pub stores: Vec<StoreSchema>,
+    pub entities: Vec<EntitySchema>,
+}
+
+impl SchemaReport {
+    #[must_use]
+    pub fn entity(&self, path: &str) -> Option<&EntitySchema> {
+        self.entities.iter().find(|e| e.path == path)
+    }
+}
+
+///
+/// StoreSchema
+///
+
+#[derive(CandidType, Clone, Debug, Deserialize, Serialize)]
+pub struct StoreSchema {
+    pub path: String,
+    pub ident: String,
+    pub ty: StoreType,
+    pub memory_id: u8,
+}
+
+///
+/// EntitySchema
+///
+
+#[derive(CandidType, Clone, Debug, Deserialize, Serialize)]
+pub struct EntitySchema {
+    pub path: String,
+    pub entity_id: u64,
+    pub store: String,
+    pub primary_key: String,
+    pub version: u32,
+    pub soft_delete: bool,
+    pub fields: Vec<FieldSchema>,
+    pub indexes: Vec<IndexSchema>,
+    /// Validators and sanitizers declared on the entity itself
+    pub validators: Vec<RuleSchema>,
+    pub sanitizers: Vec<RuleSchema>,
+}
+
+impl EntitySchema {
+    #[must_use]
+    pub fn field(&self, ident: &str) -> Option<&FieldSchema> {
+        self.fields.iter().find(|f| f.ident == ident)
+    }
+}
+
+///
+/// FieldSchema
+/// Exactly one of `primitive` and `type_path` is set
+///
+
+#[derive(CandidType, Clone, Debug, Deserialize, Serialize)]
+pub struct FieldSchema {
+    pub ident: String,
+    pub cardinality: Cardinality,
+    pub primitive: Option<Primitive>,
+    /// Path of the schema type the field holds when it isn't a primitive
+    pub type_path: Option<String>,
+    /// Path of the entity the field refers to
+    pub relation: Option<String>,
+    pub is_system: bool,
+    pub validators: Vec<RuleSchema>,
+    pub sanitizers: Vec<RuleSchema>,
+}
+
+///
+/// IndexSchema
+///
+
+#[derive(CandidType, Clone, Debug, Deserialize, Serialize)]
+pub struct IndexSchema {
+    pub store: String,
+    pub fields: Vec<String>,
+    pub unique: bool,
+}
+
+///
+/// RuleSchema
+/// A validator or sanitizer and the arguments it was declared with
+///
+
+#[derive(CandidType, Clone, Debug, Deserialize, Serialize)]
+pub struct RuleSchema {
+    pub path: String,
+    pub args: Vec<String>,
+}
@@ -1,6 +1,9 @@
 use crate::{
     core::traits::CanisterKind,
-    db::{Db, store::DataKey},
+    db::{
+        Db,
+        store::{DataKey, row_sizes},
+    },
 };
 use candid::CandidType;
 use serde::{Deserialize, Serialize};
@@ -28,6 +31,8 @@ pub struct DataStoreSnapshot {
     pub path: String,
     pub entries: u64,
     pub memory_bytes: u64,
+    /// Bytes saved by row compression
+    pub saved_bytes: u64,
 }
 
 ///
@@ -57,6 +62,8 @@ pub struct EntitySnapshot {
     pub entries: u64,
     /// Approximate bytes used (key + value)
     pub memory_bytes: u64,
+    /// Bytes saved by row compression (uncompressed minus stored row size)
+    pub saved_bytes: u64,
     /// Minimum DataKey for this entity (by full DataKey ordering)
     pub min_key: Option<DataKey>,
     /// Maximum DataKey for this entity (by full DataKey ordering)
@@ -72,16 +79,21 @@ pub struct EntitySnapshot {
 struct EntityStats {
     entries: u64,
     memory_bytes: u64,
+    saved_bytes: u64,
     min_key: Option<DataKey>,
     max_key: Option<DataKey>,
 }
 
 impl EntityStats {
-    fn update(&mut self, dk: &DataKey, value_len: u64) {
+    fn update(&mut self, dk: &DataKey, value: &[u8]) {
+        let (stored, uncompressed) = row_sizes(value);
         self.entries = self.entries.saturating_add(1);
         self.memory_bytes = self
             .memory_bytes
-            .saturating_add(DataKey::entry_size_bytes(value_len));
+            .saturating_add(dk.entry_size_bytes(stored as u64));
+        self.saved_bytes = self
+            .saved_bytes
+            .saturating_add(uncompressed.saturating_sub(stored) as u64);
 
         match &mut self.min_key {
             Some(min) if dk < min => *min = dk.clone(),
@@ -111,24 +123,23 @@ pub fn storage_report<C: CanisterKind>(
 
     db.with_data(|reg| {
         reg.for_each(|path, store| {
-            data.push(DataStoreSnapshot {
-                path: path.to_string(),
-                entries: store.len(),
-                memory_bytes: store.memory_bytes(),
-            });
-
             // Track per-entity counts, memory, and min/max DataKey
             let mut by_entity: BTreeMap<u64, EntityStats> = BTreeMap::new();
 
-            for entry in store.iter() {
-                let dk = entry.key();
-                let value_len = entry.value().len() as u64;
+            for (dk, value) in store.iter() {
                 by_entity
                     .entry(dk.entity_id())
                     .or_default()
-                    .update(dk, value_len);
+                    .update(&dk, &value);
             }
 
+            data.push(DataStoreSnapshot {
+                path: path.to_string(),
+                entries: store.len(),
+                memory_bytes: store.memory_bytes(),
+                saved_bytes: by_entity.values().map(|stats| stats.saved_bytes).sum(),
+            });
+
             for (entity_id, stats) in by_entity {
                 let path_name = id_map.get(&entity_id).copied().unwrap_or("");
                 entity_storage.push(EntitySnapshot {
@@ -136,6 +147,7 @@ pub fn storage_report<C: CanisterKind>(
                     path: path_name.to_string(),
                     entries: stats.entries,
                     memory_bytes: stats.memory_bytes,
+                    saved_bytes: stats.saved_bytes,
                     min_key: stats.min_key,
                     max_key: stats.max_key,
                 });
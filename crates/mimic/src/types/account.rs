@@ -5,6 +5,7 @@ use crate::{
             FieldValue, Filterable, Inner, SanitizeAuto, SanitizeCustom, Storable, UpdateView,
             ValidateAuto, ValidateCustom, View, Visitable,
         },
+        value::ValueFamily,
     },
     db::primitives::{TextFilterKind, TextListFilterKind},
     types::{Principal, Subaccount},
@@ -101,6 +102,10 @@ impl FieldValue for Account {
     fn to_value(&self) -> Value {
         Value::Account(*self)
     }
+
+    fn family() -> ValueFamily {
+        ValueFamily::Identifier
+    }
 }
 
 impl Filterable for Account {
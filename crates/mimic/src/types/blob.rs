@@ -5,6 +5,7 @@ use crate::{
             FieldValue, Filterable, Inner, SanitizeAuto, SanitizeCustom, UpdateView, ValidateAuto,
             ValidateCustom, View, Visitable,
         },
+        value::ValueFamily,
     },
     db::primitives::NoFilterKind,
 };
@@ -58,6 +59,10 @@ impl FieldValue for Blob {
     fn to_value(&self) -> Value {
         Value::Blob(self.to_vec())
     }
+
+    fn family() -> ValueFamily {
+        ValueFamily::Blob
+    }
 }
 
 impl Filterable for Blob {
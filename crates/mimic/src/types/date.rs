@@ -5,6 +5,7 @@ use crate::{
             FieldValue, Filterable, Inner, NumCast, NumFromPrimitive, NumToPrimitive, SanitizeAuto,
             SanitizeCustom, UpdateView, ValidateAuto, ValidateCustom, View, Visitable,
         },
+        value::ValueFamily,
     },
     db::primitives::{Int64ListFilterKind, Int64RangeFilterKind},
 };
@@ -126,6 +127,10 @@ impl FieldValue for Date {
     fn to_value(&self) -> Value {
         Value::Date(*self)
     }
+
+    fn family() -> ValueFamily {
+        ValueFamily::Numeric
+    }
 }
 
 impl Filterable for Date {
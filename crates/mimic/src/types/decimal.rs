@@ -4,7 +4,7 @@ use crate::{
             FieldValue, Filterable, Inner, NumCast, NumFromPrimitive, NumToPrimitive, SanitizeAuto,
             SanitizeCustom, UpdateView, ValidateAuto, ValidateCustom, View, Visitable,
         },
-        value::Value,
+        value::{Value, ValueFamily},
     },
     db::primitives::{DecimalListFilterKind, DecimalRangeFilterKind},
 };
@@ -137,6 +137,10 @@ impl FieldValue for Decimal {
     fn to_value(&self) -> Value {
         Value::Decimal(*self)
     }
+
+    fn family() -> ValueFamily {
+        ValueFamily::Numeric
+    }
 }
 
 impl Filterable for Decimal {
@@ -5,6 +5,7 @@ use crate::{
             FieldValue, Filterable, Inner, NumCast, NumFromPrimitive, NumToPrimitive, SanitizeAuto,
             SanitizeCustom, UpdateView, ValidateAuto, ValidateCustom, View, Visitable,
         },
+        value::ValueFamily,
     },
     db::primitives::{Nat64ListFilterKind, Nat64RangeFilterKind},
 };
@@ -125,6 +126,10 @@ impl FieldValue for Duration {
     fn to_value(&self) -> Value {
         Value::Duration(*self)
     }
+
+    fn family() -> ValueFamily {
+        ValueFamily::Numeric
+    }
 }
 
 impl Filterable for Duration {
@@ -4,7 +4,7 @@ use crate::{
             FieldValue, Filterable, Inner, NumCast, NumFromPrimitive, NumToPrimitive, SanitizeAuto,
             SanitizeCustom, UpdateView, ValidateAuto, ValidateCustom, View, Visitable,
         },
-        value::Value,
+        value::{Value, ValueFamily},
     },
     db::primitives::{NatListFilterKind, NatRangeFilterKind},
     types::Decimal,
@@ -122,6 +122,10 @@ impl FieldValue for E18s {
     fn to_value(&self) -> Value {
         Value::E18s(*self)
     }
+
+    fn family() -> ValueFamily {
+        ValueFamily::Numeric
+    }
 }
 
 impl Filterable for E18s {
@@ -4,7 +4,7 @@ use crate::{
             FieldValue, Filterable, Inner, NumCast, NumFromPrimitive, NumToPrimitive, SanitizeAuto,
             SanitizeCustom, UpdateView, ValidateAuto, ValidateCustom, View, Visitable,
         },
-        value::Value,
+        value::{Value, ValueFamily},
     },
     db::primitives::{Nat64ListFilterKind, Nat64RangeFilterKind},
     types::Decimal,
@@ -130,6 +130,10 @@ impl FieldValue for E8s {
     fn to_value(&self) -> Value {
         Value::E8s(*self)
     }
+
+    fn family() -> ValueFamily {
+        ValueFamily::Numeric
+    }
 }
 
 impl Filterable for E8s {
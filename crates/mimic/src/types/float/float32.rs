@@ -5,6 +5,7 @@ use crate::{
             FieldValue, Filterable, Inner, NumFromPrimitive, NumToPrimitive, SanitizeAuto,
             SanitizeCustom, UpdateView, ValidateAuto, ValidateCustom, View, Visitable,
         },
+        value::ValueFamily,
     },
     db::primitives::{DecimalListFilterKind, DecimalRangeFilterKind},
 };
@@ -67,6 +68,10 @@ impl FieldValue for Float32 {
     fn to_value(&self) -> Value {
         Value::Float32(*self)
     }
+
+    fn family() -> ValueFamily {
+        ValueFamily::Numeric
+    }
 }
 
 impl Filterable for Float32 {
@@ -5,6 +5,7 @@ use crate::{
             FieldValue, Filterable, Inner, NumFromPrimitive, NumToPrimitive, SanitizeAuto,
             SanitizeCustom, UpdateView, ValidateAuto, ValidateCustom, View, Visitable,
         },
+        value::ValueFamily,
     },
     db::primitives::{DecimalListFilterKind, DecimalRangeFilterKind},
 };
@@ -60,6 +61,10 @@ impl FieldValue for Float64 {
     fn to_value(&self) -> Value {
         Value::Float64(*self)
     }
+
+    fn family() -> ValueFamily {
+        ValueFamily::Numeric
+    }
 }
 
 impl Filterable for Float64 {
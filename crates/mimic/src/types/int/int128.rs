@@ -5,6 +5,7 @@ use crate::{
             FieldValue, Filterable, Inner, NumCast, NumFromPrimitive, NumToPrimitive, SanitizeAuto,
             SanitizeCustom, UpdateView, ValidateAuto, ValidateCustom, View, Visitable,
         },
+        value::ValueFamily,
     },
     db::primitives::{IntListFilterKind, IntRangeFilterKind},
 };
@@ -51,6 +52,10 @@ impl FieldValue for Int128 {
     fn to_value(&self) -> Value {
         Value::Int128(*self)
     }
+
+    fn family() -> ValueFamily {
+        ValueFamily::Numeric
+    }
 }
 
 impl Filterable for Int128 {
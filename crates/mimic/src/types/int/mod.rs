@@ -9,6 +9,7 @@ use crate::{
             FieldValue, Filterable, Inner, SanitizeAuto, SanitizeCustom, UpdateView, ValidateAuto,
             ValidateCustom, View, Visitable,
         },
+        value::ValueFamily,
     },
     db::primitives::{IntListFilterKind, IntRangeFilterKind},
 };
@@ -59,6 +60,10 @@ impl FieldValue for Int {
     fn to_value(&self) -> Value {
         Value::IntBig(self.clone())
     }
+
+    fn family() -> ValueFamily {
+        ValueFamily::Numeric
+    }
 }
 
 impl Filterable for Int {
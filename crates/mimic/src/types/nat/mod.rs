@@ -9,6 +9,7 @@ use crate::{
             FieldValue, Filterable, Inner, SanitizeAuto, SanitizeCustom, UpdateView, ValidateAuto,
             ValidateCustom, View, Visitable,
         },
+        value::ValueFamily,
     },
     db::primitives::{NatListFilterKind, NatRangeFilterKind},
 };
@@ -58,6 +59,10 @@ impl FieldValue for Nat {
     fn to_value(&self) -> Value {
         Value::UintBig(self.clone())
     }
+
+    fn family() -> ValueFamily {
+        ValueFamily::Numeric
+    }
 }
 
 impl Filterable for Nat {
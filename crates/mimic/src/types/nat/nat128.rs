@@ -5,6 +5,7 @@ use crate::{
             FieldValue, Filterable, Inner, NumCast, NumToPrimitive, SanitizeAuto, SanitizeCustom,
             UpdateView, ValidateAuto, ValidateCustom, View, Visitable,
         },
+        value::ValueFamily,
     },
     db::primitives::{NatListFilterKind, NatRangeFilterKind},
 };
@@ -51,6 +52,10 @@ impl FieldValue for Nat128 {
     fn to_value(&self) -> Value {
         Value::Uint128(*self)
     }
+
+    fn family() -> ValueFamily {
+        ValueFamily::Numeric
+    }
 }
 
 impl Filterable for Nat128 {
@@ -5,7 +5,7 @@ use crate::{
             FieldValue, Filterable, Inner, SanitizeAuto, SanitizeCustom, Storable, UpdateView,
             ValidateAuto, ValidateCustom, View, Visitable,
         },
-        value::Value,
+        value::{Value, ValueFamily},
     },
     db::primitives::filter::{TextEqualityFilterKind, TextListFilterKind},
 };
@@ -93,12 +93,20 @@ impl FieldValue for Principal {
     fn to_value(&self) -> Value {
         Value::Principal(*self)
     }
+
+    fn family() -> ValueFamily {
+        ValueFamily::Identifier
+    }
 }
 
 impl FieldValue for WrappedPrincipal {
     fn to_value(&self) -> Value {
         Value::Principal(self.into())
     }
+
+    fn family() -> ValueFamily {
+        ValueFamily::Identifier
+    }
 }
 
 impl Filterable for Principal {
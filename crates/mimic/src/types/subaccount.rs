@@ -4,7 +4,7 @@ use crate::{
             FieldValue, Filterable, Inner, SanitizeAuto, SanitizeCustom, UpdateView, ValidateAuto,
             ValidateCustom, View, Visitable,
         },
-        value::Value,
+        value::{Value, ValueFamily},
     },
     db::primitives::NoFilterKind,
     types::{Principal, Ulid},
@@ -119,6 +119,10 @@ impl FieldValue for Subaccount {
     fn to_value(&self) -> Value {
         Value::Subaccount(*self)
     }
+
+    fn family() -> ValueFamily {
+        ValueFamily::Blob
+    }
 }
 
 impl Filterable for Subaccount {
@@ -5,6 +5,7 @@ use crate::{
             FieldValue, Filterable, Inner, NumCast, NumFromPrimitive, NumToPrimitive, SanitizeAuto,
             SanitizeCustom, UpdateView, ValidateAuto, ValidateCustom, View, Visitable,
         },
+        value::ValueFamily,
     },
     db::primitives::{Nat64ListFilterKind, Nat64RangeFilterKind},
 };
@@ -63,6 +64,10 @@ impl FieldValue for Timestamp {
     fn to_value(&self) -> Value {
         Value::Timestamp(*self)
     }
+
+    fn family() -> ValueFamily {
+        ValueFamily::Numeric
+    }
 }
 
 impl Filterable for Timestamp {
@@ -9,7 +9,7 @@ use crate::{
             FieldValue, Filterable, Inner, SanitizeAuto, SanitizeCustom, Storable, UpdateView,
             ValidateAuto, ValidateCustom, View, Visitable,
         },
-        value::Value,
+        value::{Value, ValueFamily},
     },
     db::primitives::{TextFilterKind, TextListFilterKind},
 };
@@ -127,6 +127,10 @@ impl FieldValue for Ulid {
     fn to_value(&self) -> Value {
         Value::Ulid(*self)
     }
+
+    fn family() -> ValueFamily {
+        ValueFamily::Identifier
+    }
 }
 
 impl Filterable for Ulid {
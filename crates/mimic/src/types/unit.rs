@@ -5,6 +5,7 @@ use crate::{
             FieldValue, Filterable, Inner, SanitizeAuto, SanitizeCustom, UpdateView, ValidateAuto,
             ValidateCustom, View, Visitable,
         },
+        value::ValueFamily,
     },
     db::primitives::NoFilterKind,
 };
@@ -37,12 +38,20 @@ impl FieldValue for () {
     fn to_value(&self) -> Value {
         Value::Unit
     }
+
+    fn family() -> ValueFamily {
+        ValueFamily::Unit
+    }
 }
 
 impl FieldValue for Unit {
     fn to_value(&self) -> Value {
         Value::Unit
     }
+
+    fn family() -> ValueFamily {
+        ValueFamily::Unit
+    }
 }
 
 impl Filterable for Unit {
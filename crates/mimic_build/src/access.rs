@@ -0,0 +1,101 @@
+use crate::ActorBuilder;
+use mimic_schema::node::{AccessRule, Entity};
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Path, parse_str};
+
+///
+/// Operation
+/// which of the canister's access rules an endpoint is under
+///
+
+#[derive(Clone, Copy, Debug)]
+pub enum Operation {
+    Read,
+    Write,
+    Admin,
+}
+
+// check
+// statements that reject the caller before the endpoint runs, the entity's
+// rule replacing the canister's when it sets one; unset admin rules only let
+// controllers in, as those endpoints start jobs and return raw rows
+#[must_use]
+pub fn check(builder: &ActorBuilder, op: Operation, entity: Option<&Entity>) -> TokenStream {
+    let canister = &builder.canister.access;
+    let entity = entity.and_then(|e| e.access.as_ref());
+
+    let rule = match op {
+        Operation::Read => entity
+            .and_then(|a| a.read)
+            .or(canister.read)
+            .unwrap_or(AccessRule::Public),
+        Operation::Write => entity
+            .and_then(|a| a.write)
+            .or(canister.write)
+            .unwrap_or(AccessRule::Public),
+        Operation::Admin => canister.admin.unwrap_or(AccessRule::Controllers),
+    };
+
+    match rule {
+        AccessRule::Public => quote!(),
+        AccessRule::Controllers => quote! {
+            ::mimic::interface::access::require_controller()?;
+        },
+        AccessRule::Guard(guard) => {
+            let guard: Path =
+                parse_str(guard).unwrap_or_else(|_| panic!("invalid guard path: {guard}"));
+
+            quote! {
+                ::mimic::interface::access::require_guard(#guard)?;
+            }
+        }
+    }
+}
+
+///
+/// TESTS
+///
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mimic_schema::node::{Access, Canister, Def, Schema};
+    use std::sync::Arc;
+
+    fn builder(access: Access) -> ActorBuilder {
+        let canister = Canister {
+            def: Def {
+                module_path: "app",
+                ident: "Canister",
+                comments: None,
+            },
+            memory_min: 0,
+            memory_max: 10,
+            access,
+            skip_endpoints: &[],
+        };
+
+        ActorBuilder::new(Arc::new(Schema::new()), canister)
+    }
+
+    #[test]
+    fn unset_admin_rule_requires_controllers() {
+        let builder = builder(Access::default());
+
+        let admin = check(&builder, Operation::Admin, None).to_string();
+        assert!(admin.contains("require_controller"), "{admin}");
+        assert!(check(&builder, Operation::Read, None).is_empty());
+        assert!(check(&builder, Operation::Write, None).is_empty());
+    }
+
+    #[test]
+    fn admin_rule_can_be_made_public() {
+        let builder = builder(Access {
+            admin: Some(AccessRule::Public),
+            ..Access::default()
+        });
+
+        assert!(check(&builder, Operation::Admin, None).is_empty());
+    }
+}
@@ -0,0 +1,35 @@
+use crate::{
+    ActorBuilder,
+    access::{self, Operation},
+};
+use mimic_schema::types::StoreType;
+use proc_macro2::TokenStream;
+use quote::quote;
+
+// generate
+// only canisters that declare a change log store get the endpoint
+#[must_use]
+pub fn generate(builder: &ActorBuilder) -> TokenStream {
+    let has_change_log = builder
+        .get_stores()
+        .iter()
+        .any(|(_, store)| matches!(store.ty, StoreType::ChangeLog));
+
+    if !has_change_log || builder.skips("mimic_changes") {
+        return quote!();
+    }
+    let check = access::check(builder, Operation::Admin, None);
+
+    quote! {
+        /// Page through the change log, returning changes after sequence number `since`.
+        /// Pass the returned `last_seq` as `since` to read the next page.
+        #[::mimic::export::canic::cdk::query]
+        pub fn mimic_changes(
+            since: u64,
+            limit: u32,
+        ) -> Result<::mimic::db::store::ChangePage, ::mimic::Error> {
+            #check
+            DB.changes(since, limit as usize)
+        }
+    }
+}
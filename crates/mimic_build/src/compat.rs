@@ -0,0 +1,793 @@
+use mimic_schema::node::{Entity, Enum, Item, ItemTarget, Schema, SchemaNode, Store, Value};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt, fs, io,
+    path::Path,
+};
+use thiserror::Error as ThisError;
+
+/// Set this env var to accept breaking changes and rewrite the snapshot.
+pub const ACCEPT_ENV: &str = "MIMIC_SCHEMA_ACCEPT";
+
+///
+/// CompatError
+///
+
+#[derive(Debug, ThisError)]
+pub enum CompatError {
+    #[error("schema snapshot '{0}': {1}")]
+    Io(String, io::Error),
+
+    #[error("schema snapshot '{0}' is not valid: {1}")]
+    Json(String, serde_json::Error),
+
+    #[error(
+        "schema has breaking changes against '{path}':\n{}\nset {ACCEPT_ENV}=1 to accept them and update the snapshot",
+        .changes.iter().map(|c| format!("  - {c}")).collect::<Vec<_>>().join("\n")
+    )]
+    Breaking {
+        path: String,
+        changes: Vec<BreakingChange>,
+    },
+}
+
+///
+/// SnapshotCheck
+///
+/// What [`check_snapshot`] found, the snapshot is only written when accepted.
+///
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SnapshotCheck {
+    Unchanged,
+    Written,
+    Outdated,
+}
+
+/// Compare the schema against the snapshot at `path`.
+///
+/// Fails on breaking changes unless `accept` is set. The snapshot is only
+/// (re)written when `accept` is set, other differences, or a missing
+/// snapshot, are reported as `Outdated` and the file is left alone.
+pub fn check_snapshot(
+    schema: &Schema,
+    path: &Path,
+    accept: bool,
+) -> Result<SnapshotCheck, CompatError> {
+    compare_snapshot(&SchemaSnapshot::from_schema(schema), path, accept)
+}
+
+// compare_snapshot
+fn compare_snapshot(
+    current: &SchemaSnapshot,
+    path: &Path,
+    accept: bool,
+) -> Result<SnapshotCheck, CompatError> {
+    let path_str = path.display().to_string();
+
+    let previous = match fs::read_to_string(path) {
+        Ok(json) => Some(
+            serde_json::from_str::<SchemaSnapshot>(&json)
+                .map_err(|e| CompatError::Json(path_str.clone(), e))?,
+        ),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => None,
+        Err(e) => return Err(CompatError::Io(path_str, e)),
+    };
+
+    if let Some(previous) = &previous {
+        if previous == current {
+            return Ok(SnapshotCheck::Unchanged);
+        }
+
+        let changes = previous.breaking_changes(current);
+        if !changes.is_empty() && !accept {
+            return Err(CompatError::Breaking {
+                path: path_str,
+                changes,
+            });
+        }
+    }
+
+    if !accept {
+        return Ok(SnapshotCheck::Outdated);
+    }
+
+    let mut json = serde_json::to_string_pretty(current)
+        .map_err(|e| CompatError::Json(path_str.clone(), e))?;
+    json.push('\n');
+    fs::write(path, json).map_err(|e| CompatError::Io(path_str, e))?;
+
+    Ok(SnapshotCheck::Written)
+}
+
+/// FNV-1a hash of a value's JSON form, stable between builds and toolchains.
+#[must_use]
+pub fn fingerprint<T: Serialize>(value: &T) -> u64 {
+    let bytes = serde_json::to_vec(value).expect("schema snapshots serialize to json");
+
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, b| {
+        (hash ^ u64::from(*b)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+///
+/// SchemaSnapshot
+///
+/// The parts of a schema that decide how stored data is laid out,
+/// kept as JSON next to the canister so builds can be compared.
+///
+
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct SchemaSnapshot {
+    pub entities: BTreeMap<String, EntitySnapshot>,
+    pub enums: BTreeMap<String, Vec<String>>,
+    pub stores: BTreeMap<String, StoreSnapshot>,
+}
+
+impl SchemaSnapshot {
+    #[must_use]
+    pub fn from_schema(schema: &Schema) -> Self {
+        let entities = schema
+            .get_nodes::<Entity>()
+            .map(|(path, entity)| {
+                (
+                    path.to_string(),
+                    EntitySnapshot::from_entity(schema, entity),
+                )
+            })
+            .collect();
+
+        let enums = schema
+            .get_nodes::<Enum>()
+            .map(|(path, node)| {
+                let variants = node.variants.iter().map(|v| v.ident.to_string()).collect();
+
+                (path.to_string(), variants)
+            })
+            .collect();
+
+        let stores = schema
+            .get_nodes::<Store>()
+            .map(|(path, store)| {
+                let snapshot = StoreSnapshot {
+                    canister: store.canister.to_string(),
+                    ty: store.ty.to_string(),
+                    memory_id: store.memory_id,
+                };
+
+                (path.to_string(), snapshot)
+            })
+            .collect();
+
+        Self {
+            entities,
+            enums,
+            stores,
+        }
+    }
+
+    /// Changes from `self` to `current` that existing data or callers can't survive.
+    #[must_use]
+    pub fn breaking_changes(&self, current: &Self) -> Vec<BreakingChange> {
+        let mut changes = Vec::new();
+
+        for (path, old) in &self.entities {
+            match current.entities.get(path) {
+                Some(new) => old.diff(path, new, &mut changes),
+                None => changes.push(BreakingChange::EntityRemoved(path.clone())),
+            }
+        }
+
+        for (path, old) in &self.enums {
+            let Some(new) = current.enums.get(path) else {
+                continue;
+            };
+            for variant in old.iter().filter(|v| !new.contains(v)) {
+                changes.push(BreakingChange::VariantRemoved(
+                    path.clone(),
+                    variant.clone(),
+                ));
+            }
+        }
+
+        self.diff_stores(current, &mut changes);
+
+        changes
+    }
+
+    // diff_stores
+    fn diff_stores(&self, current: &Self, changes: &mut Vec<BreakingChange>) {
+        for (path, old) in &self.stores {
+            match current.stores.get(path) {
+                Some(new) if new.ty != old.ty => changes.push(BreakingChange::StoreRetyped(
+                    path.clone(),
+                    old.ty.clone(),
+                    new.ty.clone(),
+                )),
+                Some(new) if new.memory_id != old.memory_id => {
+                    changes.push(BreakingChange::StoreMoved(
+                        path.clone(),
+                        old.memory_id,
+                        new.memory_id,
+                    ));
+                }
+                Some(_) => {}
+                None => changes.push(BreakingChange::StoreRemoved(path.clone())),
+            }
+
+            // another store now sits on this store's memory
+            for (other_path, other) in &current.stores {
+                if other_path != path
+                    && other.canister == old.canister
+                    && other.memory_id == old.memory_id
+                {
+                    changes.push(BreakingChange::MemoryIdReused(
+                        old.memory_id,
+                        path.clone(),
+                        other_path.clone(),
+                    ));
+                }
+            }
+        }
+    }
+}
+
+///
+/// EntitySnapshot
+///
+/// Field types are written as `Cardinality target`, e.g. `Opt Text`.
+/// `types` holds every type node the fields reach, directly or through
+/// other type nodes, as their changes alter the rows too.
+///
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct EntitySnapshot {
+    pub store: String,
+    pub primary_key: String,
+    pub version: u32,
+    pub fields: BTreeMap<String, String>,
+    #[serde(default)]
+    pub types: BTreeMap<String, TypeSnapshot>,
+    pub indexes: BTreeSet<IndexSnapshot>,
+}
+
+impl EntitySnapshot {
+    fn from_entity(schema: &Schema, entity: &Entity) -> Self {
+        let mut types = BTreeMap::new();
+        let fields = entity
+            .fields
+            .fields
+            .iter()
+            .map(|f| {
+                TypeSnapshot::collect(schema, &f.value.item, &mut types);
+
+                (f.ident.to_string(), value_target(&f.value))
+            })
+            .collect();
+
+        let indexes = entity
+            .indexes
+            .iter()
+            .map(|index| IndexSnapshot {
+                store: index.store.to_string(),
+                fields: index.fields.iter().map(ToString::to_string).collect(),
+                unique: index.unique,
+            })
+            .collect();
+
+        Self {
+            store: entity.store.to_string(),
+            primary_key: entity.primary_key.to_string(),
+            version: entity.version,
+            fields,
+            types,
+            indexes,
+        }
+    }
+
+    // diff
+    // field changes are fine once the version is bumped, migrations handle the old rows
+    fn diff(&self, path: &str, new: &Self, changes: &mut Vec<BreakingChange>) {
+        if new.store != self.store {
+            changes.push(BreakingChange::EntityStoreChanged(
+                path.to_string(),
+                self.store.clone(),
+                new.store.clone(),
+            ));
+        }
+        if new.primary_key != self.primary_key {
+            changes.push(BreakingChange::PrimaryKeyChanged(
+                path.to_string(),
+                self.primary_key.clone(),
+                new.primary_key.clone(),
+            ));
+        }
+        if new.indexes != self.indexes {
+            changes.push(BreakingChange::IndexesChanged(path.to_string()));
+        }
+
+        if new.version > self.version {
+            return;
+        }
+        for (ty_path, ty) in &self.types {
+            if let Some(new_ty) = new.types.get(ty_path) {
+                ty.diff(path, ty_path, new_ty, changes);
+            }
+        }
+        for (field, ty) in &self.fields {
+            match new.fields.get(field) {
+                Some(new_ty) if new_ty != ty => changes.push(BreakingChange::FieldRetyped(
+                    path.to_string(),
+                    field.clone(),
+                    ty.clone(),
+                    new_ty.clone(),
+                )),
+                Some(_) => {}
+                None => changes.push(BreakingChange::FieldRemoved(
+                    path.to_string(),
+                    field.clone(),
+                )),
+            }
+        }
+    }
+}
+
+///
+/// TypeSnapshot
+///
+/// `members` maps each field, variant, tuple position or collection part
+/// (`item`, `key`, `value`) to its type, written like entity fields.
+/// Unit variants are written as `Unit`.
+///
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct TypeSnapshot {
+    pub kind: String,
+    pub members: BTreeMap<String, String>,
+}
+
+impl TypeSnapshot {
+    // collect
+    // snapshot the type node `item` points at and everything it reaches,
+    // each node once so recursive types terminate
+    fn collect(schema: &Schema, item: &Item, types: &mut BTreeMap<String, Self>) {
+        let ItemTarget::Is(path) = item.target else {
+            return;
+        };
+        if types.contains_key(path) {
+            return;
+        }
+
+        let mut reached = Vec::new();
+        let (kind, members) = match schema.get_node(path) {
+            Some(SchemaNode::Record(node)) => {
+                let members = node
+                    .fields
+                    .fields
+                    .iter()
+                    .map(|f| {
+                        reached.push(&f.value.item);
+                        (f.ident.to_string(), value_target(&f.value))
+                    })
+                    .collect();
+
+                ("Record", members)
+            }
+            Some(SchemaNode::Enum(node)) => {
+                let members = node
+                    .variants
+                    .iter()
+                    .map(|v| {
+                        let ty = v.value.as_ref().map_or_else(
+                            || "Unit".to_string(),
+                            |value| {
+                                reached.push(&value.item);
+                                value_target(value)
+                            },
+                        );
+
+                        (v.ident.to_string(), ty)
+                    })
+                    .collect();
+
+                ("Enum", members)
+            }
+            Some(SchemaNode::Tuple(node)) => {
+                let members = node
+                    .values
+                    .iter()
+                    .enumerate()
+                    .map(|(i, value)| {
+                        reached.push(&value.item);
+                        (i.to_string(), value_target(value))
+                    })
+                    .collect();
+
+                ("Tuple", members)
+            }
+            Some(SchemaNode::Newtype(node)) => {
+                reached.push(&node.item);
+                ("Newtype", BTreeMap::from([item_member(&node.item)]))
+            }
+            Some(SchemaNode::List(node)) => {
+                reached.push(&node.item);
+                ("List", BTreeMap::from([item_member(&node.item)]))
+            }
+            Some(SchemaNode::Set(node)) => {
+                reached.push(&node.item);
+                ("Set", BTreeMap::from([item_member(&node.item)]))
+            }
+            Some(SchemaNode::Map(node)) => {
+                reached.extend([&node.key, &node.value.item]);
+                let members = BTreeMap::from([
+                    ("key".to_string(), item_target(&node.key)),
+                    ("value".to_string(), value_target(&node.value)),
+                ]);
+
+                ("Map", members)
+            }
+            _ => return,
+        };
+
+        types.insert(
+            path.to_string(),
+            Self {
+                kind: kind.to_string(),
+                members,
+            },
+        );
+        for item in reached {
+            Self::collect(schema, item, types);
+        }
+    }
+
+    // diff
+    // removed enum variants are reported for the enum itself, see `SchemaSnapshot::enums`
+    fn diff(&self, entity: &str, path: &str, new: &Self, changes: &mut Vec<BreakingChange>) {
+        if new.kind != self.kind {
+            changes.push(BreakingChange::TypeRetyped(
+                entity.to_string(),
+                path.to_string(),
+                self.kind.clone(),
+                new.kind.clone(),
+            ));
+            return;
+        }
+
+        for (member, ty) in &self.members {
+            match new.members.get(member) {
+                Some(new_ty) if new_ty != ty => changes.push(BreakingChange::TypeMemberRetyped(
+                    entity.to_string(),
+                    path.to_string(),
+                    member.clone(),
+                    ty.clone(),
+                    new_ty.clone(),
+                )),
+                Some(_) => {}
+                None if self.kind == "Enum" => {}
+                None => changes.push(BreakingChange::TypeMemberRemoved(
+                    entity.to_string(),
+                    path.to_string(),
+                    member.clone(),
+                )),
+            }
+        }
+    }
+}
+
+// value_target
+// `Cardinality target`, how fields and other values are written in snapshots
+fn value_target(value: &Value) -> String {
+    format!("{} {}", value.cardinality, item_target(&value.item))
+}
+
+fn item_target(item: &Item) -> String {
+    match &item.target {
+        ItemTarget::Is(path) => (*path).to_string(),
+        ItemTarget::Primitive(prim) => prim.to_string(),
+    }
+}
+
+fn item_member(item: &Item) -> (String, String) {
+    ("item".to_string(), item_target(item))
+}
+
+///
+/// IndexSnapshot
+///
+
+#[derive(Clone, Debug, Deserialize, Eq, Ord, PartialEq, PartialOrd, Serialize)]
+pub struct IndexSnapshot {
+    pub store: String,
+    pub fields: Vec<String>,
+    pub unique: bool,
+}
+
+///
+/// StoreSnapshot
+///
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct StoreSnapshot {
+    pub canister: String,
+    pub ty: String,
+    pub memory_id: u8,
+}
+
+///
+/// BreakingChange
+///
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum BreakingChange {
+    EntityRemoved(String),
+    EntityStoreChanged(String, String, String),
+    FieldRemoved(String, String),
+    FieldRetyped(String, String, String, String),
+    IndexesChanged(String),
+    MemoryIdReused(u8, String, String),
+    PrimaryKeyChanged(String, String, String),
+    StoreMoved(String, u8, u8),
+    StoreRemoved(String),
+    StoreRetyped(String, String, String),
+    TypeMemberRemoved(String, String, String),
+    TypeMemberRetyped(String, String, String, String, String),
+    TypeRetyped(String, String, String, String),
+    VariantRemoved(String, String),
+}
+
+impl fmt::Display for BreakingChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::EntityRemoved(e) => write!(
+                f,
+                "entity {e} was removed or renamed, rows stored under its old path are orphaned"
+            ),
+            Self::EntityStoreChanged(e, from, to) => {
+                write!(f, "entity {e} moved from store {from} to {to}")
+            }
+            Self::FieldRemoved(e, field) => write!(
+                f,
+                "entity {e} field '{field}' was removed, bump the entity version and add a migration"
+            ),
+            Self::FieldRetyped(e, field, from, to) => write!(
+                f,
+                "entity {e} field '{field}' changed from {from} to {to}, bump the entity version and add a migration"
+            ),
+            Self::IndexesChanged(e) => write!(
+                f,
+                "entity {e} indexes changed, existing rows need a Reindex job"
+            ),
+            Self::MemoryIdReused(id, from, to) => {
+                write!(f, "memory_id {id} moved from store {from} to {to}")
+            }
+            Self::PrimaryKeyChanged(e, from, to) => {
+                write!(f, "entity {e} primary key changed from '{from}' to '{to}'")
+            }
+            Self::StoreMoved(s, from, to) => {
+                write!(f, "store {s} memory_id changed from {from} to {to}")
+            }
+            Self::StoreRemoved(s) => write!(f, "store {s} was removed"),
+            Self::StoreRetyped(s, from, to) => {
+                write!(f, "store {s} changed type from {from} to {to}")
+            }
+            Self::TypeMemberRemoved(e, ty, member) => write!(
+                f,
+                "entity {e} uses {ty}, whose '{member}' was removed, bump the entity version and add a migration"
+            ),
+            Self::TypeMemberRetyped(e, ty, member, from, to) => write!(
+                f,
+                "entity {e} uses {ty}, whose '{member}' changed from {from} to {to}, bump the entity version and add a migration"
+            ),
+            Self::TypeRetyped(e, ty, from, to) => write!(
+                f,
+                "entity {e} uses {ty}, which changed from a {from} to a {to}, bump the entity version and add a migration"
+            ),
+            Self::VariantRemoved(e, variant) => {
+                write!(f, "enum {e} variant '{variant}' was removed")
+            }
+        }
+    }
+}
+
+///
+/// TESTS
+///
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entity() -> EntitySnapshot {
+        EntitySnapshot {
+            store: "app::DataStore".to_string(),
+            primary_key: "id".to_string(),
+            version: 1,
+            fields: BTreeMap::from([
+                ("id".to_string(), "One Ulid".to_string()),
+                ("name".to_string(), "One Text".to_string()),
+                ("stats".to_string(), "One app::Stats".to_string()),
+            ]),
+            types: BTreeMap::from([(
+                "app::Stats".to_string(),
+                TypeSnapshot {
+                    kind: "Record".to_string(),
+                    members: BTreeMap::from([("level".to_string(), "One Nat8".to_string())]),
+                },
+            )]),
+            indexes: BTreeSet::new(),
+        }
+    }
+
+    fn store(memory_id: u8) -> StoreSnapshot {
+        StoreSnapshot {
+            canister: "app::Canister".to_string(),
+            ty: "Data".to_string(),
+            memory_id,
+        }
+    }
+
+    fn snapshot() -> SchemaSnapshot {
+        SchemaSnapshot {
+            entities: BTreeMap::from([("app::User".to_string(), entity())]),
+            enums: BTreeMap::from([(
+                "app::Role".to_string(),
+                vec!["Admin".to_string(), "Member".to_string()],
+            )]),
+            stores: BTreeMap::from([("app::DataStore".to_string(), store(1))]),
+        }
+    }
+
+    #[test]
+    fn additions_are_not_breaking() {
+        let old = snapshot();
+        let mut new = snapshot();
+        new.entities
+            .get_mut("app::User")
+            .unwrap()
+            .fields
+            .insert("email".to_string(), "Opt Text".to_string());
+        new.enums
+            .get_mut("app::Role")
+            .unwrap()
+            .push("Guest".to_string());
+        new.stores.insert("app::OtherStore".to_string(), store(2));
+
+        assert!(old.breaking_changes(&new).is_empty());
+    }
+
+    #[test]
+    fn field_changes_need_a_version_bump() {
+        let old = snapshot();
+        let mut new = snapshot();
+        let user = new.entities.get_mut("app::User").unwrap();
+        user.fields.remove("name");
+        user.fields
+            .insert("id".to_string(), "One Nat64".to_string());
+
+        let changes = old.breaking_changes(&new);
+        assert_eq!(changes.len(), 2);
+
+        new.entities.get_mut("app::User").unwrap().version = 2;
+        assert!(old.breaking_changes(&new).is_empty());
+    }
+
+    #[test]
+    fn nested_type_changes_need_a_version_bump() {
+        let old = snapshot();
+        let mut new = snapshot();
+        let stats = new
+            .entities
+            .get_mut("app::User")
+            .unwrap()
+            .types
+            .get_mut("app::Stats")
+            .unwrap();
+        stats
+            .members
+            .insert("level".to_string(), "One Nat16".to_string());
+        stats
+            .members
+            .insert("xp".to_string(), "Opt Nat64".to_string());
+
+        assert_eq!(
+            old.breaking_changes(&new),
+            vec![BreakingChange::TypeMemberRetyped(
+                "app::User".to_string(),
+                "app::Stats".to_string(),
+                "level".to_string(),
+                "One Nat8".to_string(),
+                "One Nat16".to_string()
+            )]
+        );
+
+        new.entities.get_mut("app::User").unwrap().version = 2;
+        assert!(old.breaking_changes(&new).is_empty());
+    }
+
+    #[test]
+    fn detects_layout_changes() {
+        let old = snapshot();
+        let mut new = snapshot();
+        new.entities.get_mut("app::User").unwrap().primary_key = "name".to_string();
+        new.entities
+            .get_mut("app::User")
+            .unwrap()
+            .indexes
+            .insert(IndexSnapshot {
+                store: "app::IndexStore".to_string(),
+                fields: vec!["name".to_string()],
+                unique: true,
+            });
+        new.enums.get_mut("app::Role").unwrap().pop();
+
+        let changes = old.breaking_changes(&new);
+        assert!(changes.contains(&BreakingChange::PrimaryKeyChanged(
+            "app::User".to_string(),
+            "id".to_string(),
+            "name".to_string()
+        )));
+        assert!(changes.contains(&BreakingChange::IndexesChanged("app::User".to_string())));
+        assert!(changes.contains(&BreakingChange::VariantRemoved(
+            "app::Role".to_string(),
+            "Member".to_string()
+        )));
+    }
+
+    #[test]
+    fn detects_removed_entities_and_memory_reuse() {
+        let old = snapshot();
+        let mut new = snapshot();
+        new.entities.clear();
+        new.stores.remove("app::DataStore");
+        new.stores.insert("app::NewStore".to_string(), store(1));
+
+        let changes = old.breaking_changes(&new);
+        assert_eq!(
+            changes,
+            vec![
+                BreakingChange::EntityRemoved("app::User".to_string()),
+                BreakingChange::StoreRemoved("app::DataStore".to_string()),
+                BreakingChange::MemoryIdReused(
+                    1,
+                    "app::DataStore".to_string(),
+                    "app::NewStore".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn snapshot_is_only_written_when_accepted() {
+        let path = std::env::temp_dir().join(format!("mimic_snapshot_{}.json", std::process::id()));
+        let _ = fs::remove_file(&path);
+        let old = snapshot();
+
+        assert_eq!(
+            compare_snapshot(&old, &path, false).unwrap(),
+            SnapshotCheck::Outdated
+        );
+        assert!(!path.exists());
+        assert_eq!(
+            compare_snapshot(&old, &path, true).unwrap(),
+            SnapshotCheck::Written
+        );
+        assert_eq!(
+            compare_snapshot(&old, &path, false).unwrap(),
+            SnapshotCheck::Unchanged
+        );
+
+        // additions leave the committed file alone
+        let mut new = snapshot();
+        new.stores.insert("app::OtherStore".to_string(), store(2));
+        let before = fs::read_to_string(&path).unwrap();
+        assert_eq!(
+            compare_snapshot(&new, &path, false).unwrap(),
+            SnapshotCheck::Outdated
+        );
+        assert_eq!(fs::read_to_string(&path).unwrap(), before);
+
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -1,15 +1,20 @@
-use crate::ActorBuilder;
+use crate::{
+    ActorBuilder,
+    compat::{SchemaSnapshot, fingerprint},
+    ttl,
+};
 use mimic_schema::types::StoreType;
-use proc_macro2::TokenStream;
+use proc_macro2::{Ident, TokenStream};
 use quote::{format_ident, quote};
 use syn::parse_str;
 
 ///
 /// Entry point for codegen.
 /// Expands into:
-/// - thread-local Data/Index store definitions
+/// - thread-local Data/Index/ChangeLog/History/Job store definitions
 /// - registry wiring
 /// - a global `db()` accessor
+/// - the schema record and its upgrade check
 ///
 #[must_use]
 pub fn generate(builder: &ActorBuilder) -> TokenStream {
@@ -17,53 +22,87 @@ pub fn generate(builder: &ActorBuilder) -> TokenStream {
 
     // Generate store definitions + registries
     tokens.extend(stores(builder));
+    tokens.extend(schema_guard(builder));
+    tokens.extend(accessor(builder));
 
     tokens
 }
 
 ///
 /// Generate thread-local DataStores and IndexStores for this canister,
-/// along with their registries and the `db()` accessor.
+/// along with their registries and the `DB` handle.
 ///
 fn stores(builder: &ActorBuilder) -> TokenStream {
     let mut data_defs = quote!();
     let mut index_defs = quote!();
     let mut data_inits = quote!();
     let mut index_inits = quote!();
+    let mut single_defs = quote!();
+    let mut db_inits = quote!();
 
     for (store_path, store) in builder.get_stores() {
         let cell_ident = format_ident!("{}", &store.ident);
         let memory_id = store.memory_id;
         let store_path_lit = store_path;
 
-        if matches!(store.ty, StoreType::Index) {
-            // Index store
-            index_defs.extend(quote! {
-                ::canic::eager_static! {
-                    static #cell_ident: ::std::cell::RefCell<::mimic::db::store::IndexStore> =
-                        ::std::cell::RefCell::new(::mimic::db::store::IndexStore::init(
-                            ::canic::ic_memory!(::mimic::db::store::IndexStore, #memory_id)
-                        ));
-                }
-            });
-
-            index_inits.extend(quote! {
-                reg.register(#store_path_lit, &#cell_ident);
-            });
-        } else {
-            // Data store
-            data_defs.extend(quote! {
-                ::canic::eager_static! {
-                    static #cell_ident: ::std::cell::RefCell<::mimic::db::store::DataStore> =
-                        ::std::cell::RefCell::new(::mimic::db::store::DataStore::init(
-                            ::canic::ic_memory!(::mimic::db::store::DataStore, #memory_id)
-                        ));
-                }
-            });
-
-            data_inits.extend(quote! {
-                reg.register(#store_path_lit, &#cell_ident);
-            });
+        match store.ty {
+            StoreType::Index => {
+                index_defs.extend(quote! {
+                    ::canic::eager_static! {
+                        static #cell_ident: ::std::cell::RefCell<::mimic::db::store::IndexStore> =
+                            ::std::cell::RefCell::new(::mimic::db::store::IndexStore::init(
+                                ::canic::ic_memory!(::mimic::db::store::IndexStore, #memory_id)
+                            ));
+                    }
+                });
+
+                index_inits.extend(quote! {
+                    reg.register(#store_path_lit, &#cell_ident);
+                });
+            }
+
+            StoreType::Data => {
+                data_defs.extend(quote! {
+                    ::canic::eager_static! {
+                        static #cell_ident: ::std::cell::RefCell<::mimic::db::store::DataStore> =
+                            ::std::cell::RefCell::new(::mimic::db::store::DataStore::init(
+                                ::canic::ic_memory!(::mimic::db::store::DataStore, #memory_id)
+                            ));
+                    }
+                });
+
+                data_inits.extend(quote! {
+                    reg.register(#store_path_lit, &#cell_ident);
+                });
+            }
+
+            // not part of a registry, the Db handle points at it directly
+            StoreType::ChangeLog => {
+                single_defs.extend(single_store(
+                    &quote!(::mimic::db::store::ChangeLogStore),
+                    &cell_ident,
+                    memory_id,
+                ));
+                db_inits.extend(quote!(.with_change_log(&#cell_ident)));
+            }
+
+            StoreType::History => {
+                single_defs.extend(single_store(
+                    &quote!(::mimic::db::store::HistoryStore),
+                    &cell_ident,
+                    memory_id,
+                ));
+                db_inits.extend(quote!(.with_history(&#cell_ident)));
+            }
+
+            StoreType::Job => {
+                single_defs.extend(single_store(
+                    &quote!(::mimic::db::jobs::JobStore),
+                    &cell_ident,
+                    memory_id,
+                ));
+                db_inits.extend(quote!(.with_jobs(&#cell_ident)));
+            }
         }
     }
 
@@ -76,6 +115,7 @@ fn stores(builder: &ActorBuilder) -> TokenStream {
     quote! {
         #data_defs
         #index_defs
+        #single_defs
 
         thread_local! {
             // registries
@@ -96,18 +136,202 @@ fn stores(builder: &ActorBuilder) -> TokenStream {
             };
         }
 
-        static DB: ::mimic::db::Db<#canister_path> =
-            ::mimic::db::Db::<#canister_path>::new(&DATA_REGISTRY, &INDEX_REGISTRY);
+        // a const, as a `Db` built with the `in-memory` feature isn't `Sync`
+        const DB: ::mimic::db::Db<#canister_path> =
+            ::mimic::db::Db::<#canister_path>::new(&DATA_REGISTRY, &INDEX_REGISTRY)#db_inits;
 
         // reserve the ic memory range
         ::canic::eager_init!({
             ::canic::ic_memory_range!(#memory_min, #memory_max);
         });
+    }
+}
+
+// accessor
+// the `db()` handle, which refuses to serve rows until the schema check has run
+// and the ttl sweeper has started
+fn accessor(builder: &ActorBuilder) -> TokenStream {
+    let canister_path: syn::Path = parse_str(&builder.canister.def.path())
+        .unwrap_or_else(|_| panic!("invalid canister path: {}", builder.canister.def.path()));
+
+    // expired rows would never be purged without the sweeper
+    let ttl_check = if ttl::has_ttl(builder) {
+        quote! {
+            ::std::assert!(
+                MIMIC_TTL_STARTED.get(),
+                "mimic: the ttl sweeper was never started, call mimic_init and mimic_post_upgrade \
+                 from the canister's init and post_upgrade hooks, or use mimic_start!(hooks = true)"
+            );
+        }
+    } else {
+        quote!()
+    };
 
-        /// Global accessor (fat handle) for this canister’s DB
+    quote! {
+        thread_local! {
+            // set by `mimic_schema_upgrade` once this instance has checked the stored schema
+            static MIMIC_SCHEMA_CHECKED: ::std::cell::Cell<bool> = const { ::std::cell::Cell::new(false) };
+        }
+
+        /// Global accessor (fat handle) for this canister’s DB.
+        ///
+        /// Traps until `mimic_schema_upgrade` has run in this canister instance, and the ttl
+        /// sweeper has started if it has one, so a canister whose init and post_upgrade hooks
+        /// don't call `mimic_init` and `mimic_post_upgrade` can't serve rows its stored schema
+        /// was never checked against, or keep expired rows forever.
         #[must_use]
-        pub const fn db() -> ::mimic::db::DbSession<#canister_path> {
+        pub fn db() -> ::mimic::db::DbSession<#canister_path> {
+            ::std::assert!(
+                MIMIC_SCHEMA_CHECKED.get(),
+                "mimic: the stored schema was never checked, call mimic_init and mimic_post_upgrade \
+                     from the canister's init and post_upgrade hooks, or use mimic_start!(hooks = true)"
+            );
+            #ttl_check
+
             ::mimic::db::DbSession::new(DB)
         }
     }
 }
+
+// single_store
+// a store the canister has at most one of, held directly by the Db handle
+fn single_store(store: &TokenStream, cell_ident: &Ident, memory_id: u8) -> TokenStream {
+    quote! {
+        ::canic::eager_static! {
+            static #cell_ident: ::std::cell::RefCell<#store> =
+                ::std::cell::RefCell::new(<#store>::init(::canic::ic_memory!(#store, #memory_id)));
+        }
+    }
+}
+
+// schema_guard
+// fingerprints this canister's stores and entities, and generates the check
+// that compares them with the ones recorded in the reserved memory slot
+fn schema_guard(builder: &ActorBuilder) -> TokenStream {
+    let snapshot = SchemaSnapshot::from_schema(&builder.schema);
+
+    let mut stores = Vec::new();
+    let mut entities = Vec::new();
+
+    for (path, _) in builder.get_stores() {
+        let store = &snapshot.stores[&path];
+        let in_store: Vec<_> = snapshot
+            .entities
+            .iter()
+            .filter(|(_, e)| e.store == path)
+            .collect();
+        let indexes: Vec<_> = snapshot
+            .entities
+            .iter()
+            .flat_map(|(e, entity)| entity.indexes.iter().map(move |i| (e, i)))
+            .filter(|(_, i)| i.store == path)
+            .collect();
+        let store_fp = fingerprint(&(store, &in_store, &indexes));
+
+        for (entity_path, entity) in in_store {
+            // the type nodes the fields reach change the encoding as much as the fields do
+            let layout = fingerprint(&(
+                &entity.store,
+                &entity.primary_key,
+                &entity.fields,
+                &entity.types,
+            ));
+            entities.push((
+                entity_path.clone(),
+                entity.store.clone(),
+                entity.version,
+                layout,
+                fingerprint(&entity.indexes),
+            ));
+        }
+        stores.push((path, store.ty.clone(), store.memory_id, store_fp));
+    }
+
+    let hash = format!("{:016x}", fingerprint(&(&stores, &entities)));
+    let store_rows = stores
+        .iter()
+        .map(|(path, ty, memory_id, fp)| quote!((#path, #ty, #memory_id, #fp)));
+    let entity_rows = entities
+        .iter()
+        .map(|(path, store, version, layout, indexes)| {
+            quote!((#path, #store, #version, #layout, #indexes))
+        });
+
+    let start_jobs = upgrade_jobs(builder);
+
+    quote! {
+        ::canic::eager_static! {
+            static MIMIC_SCHEMA_STORE: ::std::cell::RefCell<::mimic::db::store::SchemaStore> =
+                ::std::cell::RefCell::new(::mimic::db::store::SchemaStore::reserved());
+        }
+
+        /// Fingerprints of this build's stores and entities.
+        #[must_use]
+        #[allow(clippy::unreadable_literal)]
+        pub fn mimic_schema_record() -> ::mimic::db::store::SchemaRecord {
+            ::mimic::db::store::SchemaRecord::new(
+                #hash,
+                &[#(#store_rows),*],
+                &[#(#entity_rows),*],
+            )
+        }
+
+        /// Check the stored data against this build's schema, then record it.
+        ///
+        /// Called by `mimic_init` and `mimic_post_upgrade`: it traps, rolling the upgrade back, when
+        /// stores moved or an entity's encoding changed without a version bump, rewrites data keys
+        /// stored in an older encoding, and starts jobs for entities that need migrating or reindexing.
+        pub fn mimic_schema_upgrade() -> ::mimic::db::store::SchemaUpgrade {
+            let current = mimic_schema_record();
+            let upgrade = MIMIC_SCHEMA_STORE
+                .with_borrow(|store| store.get().upgrade_to(&current))
+                .unwrap_or_else(|e| ::std::panic!("{e}"));
+
+            // before anything reads a row, keys written in an older encoding get rewritten
+            if upgrade.rekey {
+                DATA_REGISTRY.with(::mimic::db::store::DataStoreRegistry::rekey);
+            }
+            #start_jobs
+            MIMIC_SCHEMA_STORE.with_borrow_mut(|store| store.set(current));
+            MIMIC_SCHEMA_CHECKED.set(true);
+
+            upgrade
+        }
+    }
+}
+
+// upgrade_jobs
+// starts the migrate and reindex jobs an upgrade asks for
+fn upgrade_jobs(builder: &ActorBuilder) -> TokenStream {
+    let has_jobs = builder
+        .get_stores()
+        .iter()
+        .any(|(_, store)| matches!(store.ty, StoreType::Job));
+    if has_jobs {
+        quote! {
+            for entity in &upgrade.migrate {
+                ::mimic::db::jobs::start(DB, MIMIC_JOB_RUNNERS, ::mimic::db::jobs::JobKind::Migrate {
+                    entity: entity.clone(),
+                })
+                .unwrap_or_else(|e| ::std::panic!("{e}"));
+            }
+            for entity in &upgrade.reindex {
+                ::mimic::db::jobs::start(DB, MIMIC_JOB_RUNNERS, ::mimic::db::jobs::JobKind::Reindex {
+                    entity: entity.clone(),
+                })
+                .unwrap_or_else(|e| ::std::panic!("{e}"));
+            }
+            mimic_jobs_restart();
+        }
+    } else {
+        // old rows still migrate when read, but nothing can rebuild the indexes
+        quote! {
+            if !upgrade.reindex.is_empty() {
+                ::std::panic!(
+                    "indexes changed for {}, declare a Job store so they can be rebuilt",
+                    upgrade.reindex.join(", ")
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,578 @@
+//! Candid (`.did`) description of a canister's generated endpoints and of the
+//! View, Create and Update types of its entities, written next to `actor.rs`.
+
+use crate::ActorBuilder;
+use candid::{
+    CandidType,
+    pretty::candid::compile,
+    types::{Field, FuncMode, Function, Label, Type, TypeEnv, TypeInner, internal::TypeContainer},
+};
+use mimic_schema::{
+    node::{Entity, Enum, Item, ItemTarget, List, Map, Newtype, Record, Set, Tuple, Value},
+    types::{Cardinality, Primitive, StoreType},
+};
+use std::collections::BTreeMap;
+
+///
+/// Builtins
+///
+/// Candid types only the `mimic` crate can produce: the arguments and results
+/// of the generated endpoints, and the View and Update type of each primitive.
+///
+
+pub struct Builtins {
+    container: TypeContainer,
+    types: BTreeMap<&'static str, Type>,
+    primitives: BTreeMap<String, (Type, Type)>,
+}
+
+impl Builtins {
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            container: TypeContainer::new(),
+            types: BTreeMap::new(),
+            primitives: BTreeMap::new(),
+        }
+    }
+
+    /// Register `T` as the type endpoints refer to as `name`.
+    pub fn add<T: CandidType>(&mut self, name: &'static str) -> &mut Self {
+        let ty = self.container.add::<T>();
+        self.types.insert(name, ty);
+
+        self
+    }
+
+    /// Register the View (`V`) and Update (`U`) type of a primitive.
+    pub fn add_primitive<V: CandidType, U: CandidType>(&mut self, prim: Primitive) -> &mut Self {
+        let view = self.container.add::<V>();
+        let update = self.container.add::<U>();
+        self.primitives.insert(prim.to_string(), (view, update));
+
+        self
+    }
+
+    fn get(&self, name: &str) -> Type {
+        self.types
+            .get(name)
+            .cloned()
+            .unwrap_or_else(|| panic!("candid type '{name}' was not registered"))
+    }
+
+    fn primitive(&self, prim: Primitive) -> &(Type, Type) {
+        self.primitives
+            .get(&prim.to_string())
+            .unwrap_or_else(|| panic!("candid type for primitive '{prim}' was not registered"))
+    }
+}
+
+impl Default for Builtins {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// generate
+#[must_use]
+pub fn generate(builder: &ActorBuilder, builtins: Builtins) -> String {
+    let mut did = Did::new(builder, builtins);
+
+    for (path, entity) in builder.get_entities() {
+        did.entity(&path, &entity);
+    }
+
+    let service = did.service();
+
+    compile(&did.env, &Some(service))
+}
+
+// name, whether it's a query, argument types and the Ok type of its result
+type Endpoint = (&'static str, bool, Vec<Type>, Type);
+
+///
+/// Did
+/// collects the named types while the service is described
+///
+
+struct Did<'a> {
+    builder: &'a ActorBuilder,
+    builtins: Builtins,
+    env: TypeEnv,
+
+    // candid name -> schema path it was given to
+    names: BTreeMap<String, String>,
+}
+
+impl<'a> Did<'a> {
+    fn new(builder: &'a ActorBuilder, builtins: Builtins) -> Self {
+        let env = builtins.container.env.clone();
+
+        Self {
+            builder,
+            builtins,
+            env,
+            names: BTreeMap::new(),
+        }
+    }
+
+    // service
+    // every endpoint the ActorBuilder generates for this canister
+    fn service(&self) -> Type {
+        let b = &self.builtins;
+
+        let mut endpoints = self.query_endpoints();
+        endpoints.extend([
+            ("mimic_schema", true, vec![], b.get("SchemaReport")),
+            ("mimic_snapshot", true, vec![], b.get("StorageReport")),
+            ("mimic_metrics", true, vec![], b.get("EventReport")),
+            ("mimic_metrics_reset", false, vec![], TypeInner::Null.into()),
+        ]);
+
+        let stores = self.builder.get_stores();
+        if stores
+            .iter()
+            .any(|(_, s)| matches!(s.ty, StoreType::ChangeLog))
+        {
+            let args = vec![TypeInner::Nat64.into(), TypeInner::Nat32.into()];
+            endpoints.push(("mimic_changes", true, args, b.get("ChangePage")));
+        }
+
+        if stores.iter().any(|(_, s)| matches!(s.ty, StoreType::Job)) {
+            let id = || vec![TypeInner::Nat64.into()];
+            endpoints.extend([
+                ("mimic_jobs", true, vec![], vec_of(b.get("Job"))),
+                (
+                    "mimic_job_start",
+                    false,
+                    vec![b.get("JobKind")],
+                    b.get("Job"),
+                ),
+                ("mimic_job_pause", false, id(), b.get("Job")),
+                ("mimic_job_resume", false, id(), b.get("Job")),
+                ("mimic_job_cancel", false, id(), b.get("Job")),
+            ]);
+        }
+
+        let mut methods: Vec<(String, Type)> = endpoints
+            .into_iter()
+            .filter(|(name, ..)| !self.builder.skips(name))
+            .map(|(name, query, args, ok)| {
+                let func = Function {
+                    modes: if query { vec![FuncMode::Query] } else { vec![] },
+                    args,
+                    rets: vec![result_of(ok, b.get("Error"))],
+                };
+
+                (name.to_string(), TypeInner::Func(func).into())
+            })
+            .collect();
+        methods.sort_by(|a, b| a.0.cmp(&b.0));
+
+        TypeInner::Service(methods).into()
+    }
+
+    // query_endpoints
+    // the mimic_query_* endpoints, as (name, is query, args, ok type)
+    fn query_endpoints(&self) -> Vec<Endpoint> {
+        let b = &self.builtins;
+        let text = || TypeInner::Text.into();
+        let keys = || vec_of(b.get("Key"));
+        let bytes = || vec_of(TypeInner::Nat8.into());
+
+        vec![
+            (
+                "mimic_query_load",
+                true,
+                vec![text(), b.get("LoadQuery")],
+                keys(),
+            ),
+            (
+                "mimic_query_save",
+                false,
+                vec![text(), b.get("SaveQuery")],
+                b.get("Key"),
+            ),
+            (
+                "mimic_query_delete",
+                false,
+                vec![text(), b.get("DeleteQuery")],
+                keys(),
+            ),
+            (
+                "mimic_query_load_views",
+                true,
+                vec![text(), b.get("LoadQuery")],
+                b.get("ViewPage"),
+            ),
+            (
+                "mimic_query_load_rows",
+                true,
+                vec![text(), b.get("LoadQuery")],
+                vec_of(bytes()),
+            ),
+            (
+                "mimic_query_save_row",
+                false,
+                vec![text(), b.get("SaveQuery")],
+                bytes(),
+            ),
+            (
+                "mimic_query_patch",
+                false,
+                vec![text(), b.get("Key"), bytes()],
+                bytes(),
+            ),
+            (
+                "mimic_query_load_values",
+                true,
+                vec![text(), b.get("LoadQuery")],
+                vec_of(b.get("ValueRow")),
+            ),
+            (
+                "mimic_query_insert_values",
+                false,
+                vec![text(), b.get("Values")],
+                b.get("ValueRow"),
+            ),
+            (
+                "mimic_query_update_values",
+                false,
+                vec![text(), b.get("Key"), b.get("Values")],
+                b.get("ValueRow"),
+            ),
+        ]
+    }
+
+    // entity
+    fn entity(&mut self, path: &str, entity: &Entity) {
+        let fields = entity.fields.fields;
+        let editable = || {
+            fields
+                .iter()
+                .filter(|f| f.ident != entity.primary_key && !f.is_system)
+        };
+
+        self.define(path, entity.def.ident, "View", |did| {
+            record_of(fields.iter().map(|f| (f.ident, did.value_view(&f.value))))
+        });
+        self.define(path, entity.def.ident, "Create", |did| {
+            record_of(editable().map(|f| (f.ident, did.value_view(&f.value))))
+        });
+        self.define(path, entity.def.ident, "Update", |did| {
+            record_of(editable().map(|f| (f.ident, opt_of(did.value_update(&f.value)))))
+        });
+    }
+
+    // define
+    // gives the type built by `build` a name in the env and returns a
+    // reference to it; the name is reserved first so recursive types work
+    fn define(
+        &mut self,
+        path: &str,
+        ident: &str,
+        suffix: &str,
+        build: impl FnOnce(&mut Self) -> Type,
+    ) -> Type {
+        // fall back to the full path when the short name is taken
+        let mut name = format!("{ident}{suffix}");
+        let taken = match self.names.get(&name) {
+            Some(owner) => owner != path,
+            None => self.env.0.contains_key(&name),
+        };
+        if taken {
+            name = format!("{}{suffix}", path.replace("::", "_"));
+        }
+        if self.names.contains_key(&name) {
+            return TypeInner::Var(name).into();
+        }
+
+        self.names.insert(name.clone(), path.to_string());
+        self.env.0.insert(name.clone(), TypeInner::Null.into());
+        let ty = build(self);
+        self.env.0.insert(name.clone(), ty);
+
+        TypeInner::Var(name).into()
+    }
+
+    fn value_view(&mut self, value: &Value) -> Type {
+        let item = self.item_view(&value.item);
+
+        match value.cardinality {
+            Cardinality::One => item,
+            Cardinality::Opt => opt_of(item),
+            Cardinality::Many => vec_of(item),
+        }
+    }
+
+    fn value_update(&mut self, value: &Value) -> Type {
+        let item = self.item_update(&value.item);
+
+        match value.cardinality {
+            Cardinality::One => item,
+            Cardinality::Opt => opt_of(item),
+            Cardinality::Many => vec_of(list_patch(item)),
+        }
+    }
+
+    fn item_view(&mut self, item: &Item) -> Type {
+        match item.target {
+            ItemTarget::Primitive(prim) => self.builtins.primitive(prim).0.clone(),
+            ItemTarget::Is(path) => self.node_view(path),
+        }
+    }
+
+    fn item_update(&mut self, item: &Item) -> Type {
+        match item.target {
+            ItemTarget::Primitive(prim) => self.builtins.primitive(prim).1.clone(),
+            ItemTarget::Is(path) => self.node_update(path),
+        }
+    }
+
+    // node_view
+    // the View type of the type node at `path`, mirroring mimic_declare's views
+    fn node_view(&mut self, path: &str) -> Type {
+        let schema = self.builder.schema.clone();
+
+        if let Ok(node) = schema.cast_node::<Record>(path) {
+            self.define(path, node.def.ident, "View", |did| {
+                let fields = node.fields.fields;
+                record_of(fields.iter().map(|f| (f.ident, did.value_view(&f.value))))
+            })
+        } else if let Ok(node) = schema.cast_node::<Enum>(path) {
+            self.define(path, node.def.ident, "View", |did| {
+                variant_of(node.variants.iter().map(|v| {
+                    let ident = if v.unspecified {
+                        "Unspecified"
+                    } else {
+                        v.ident
+                    };
+                    let ty = v.value.as_ref().map(|value| did.value_view(value));
+
+                    (ident, ty.unwrap_or_else(|| TypeInner::Null.into()))
+                }))
+            })
+        } else if let Ok(node) = schema.cast_node::<Newtype>(path) {
+            self.define(path, node.def.ident, "View", |did| {
+                did.item_view(&node.item)
+            })
+        } else if let Ok(node) = schema.cast_node::<List>(path) {
+            self.define(path, node.def.ident, "View", |did| {
+                vec_of(did.item_view(&node.item))
+            })
+        } else if let Ok(node) = schema.cast_node::<Set>(path) {
+            self.define(path, node.def.ident, "View", |did| {
+                vec_of(did.item_view(&node.item))
+            })
+        } else if let Ok(node) = schema.cast_node::<Map>(path) {
+            self.define(path, node.def.ident, "View", |did| {
+                let key = did.item_view(&node.key);
+                let value = did.value_view(&node.value);
+
+                vec_of(tuple_of(vec![key, value]))
+            })
+        } else if let Ok(node) = schema.cast_node::<Tuple>(path) {
+            self.define(path, node.def.ident, "View", |did| {
+                tuple_of(node.values.iter().map(|v| did.value_view(v)).collect())
+            })
+        } else {
+            panic!("no candid view for type node '{path}'")
+        }
+    }
+
+    // node_update
+    // the Update type of the type node at `path`
+    fn node_update(&mut self, path: &str) -> Type {
+        let schema = self.builder.schema.clone();
+
+        if let Ok(node) = schema.cast_node::<Record>(path) {
+            self.define(path, node.def.ident, "Update", |did| {
+                let fields = node.fields.fields;
+                record_of(
+                    fields
+                        .iter()
+                        .map(|f| (f.ident, opt_of(did.value_update(&f.value)))),
+                )
+            })
+        } else if schema.cast_node::<Enum>(path).is_ok() {
+            // enums are replaced whole
+            self.node_view(path)
+        } else if let Ok(node) = schema.cast_node::<Newtype>(path) {
+            self.define(path, node.def.ident, "Update", |did| {
+                did.item_update(&node.item)
+            })
+        } else if let Ok(node) = schema.cast_node::<List>(path) {
+            self.define(path, node.def.ident, "Update", |did| {
+                vec_of(list_patch(did.item_update(&node.item)))
+            })
+        } else if let Ok(node) = schema.cast_node::<Set>(path) {
+            self.define(path, node.def.ident, "Update", |did| {
+                vec_of(set_patch(did.item_update(&node.item)))
+            })
+        } else if let Ok(node) = schema.cast_node::<Map>(path) {
+            self.define(path, node.def.ident, "Update", |did| {
+                let key = did.item_update(&node.key);
+                let value = did.value_update(&node.value);
+
+                vec_of(map_patch(key, value))
+            })
+        } else if let Ok(node) = schema.cast_node::<Tuple>(path) {
+            self.define(path, node.def.ident, "Update", |did| {
+                tuple_of(
+                    node.values
+                        .iter()
+                        .map(|v| opt_of(did.value_update(v)))
+                        .collect(),
+                )
+            })
+        } else {
+            panic!("no candid update for type node '{path}'")
+        }
+    }
+}
+
+//
+// candid type helpers
+//
+
+fn opt_of(ty: Type) -> Type {
+    TypeInner::Opt(ty).into()
+}
+
+fn vec_of(ty: Type) -> Type {
+    TypeInner::Vec(ty).into()
+}
+
+fn field(name: &str, ty: Type) -> Field {
+    Field {
+        id: Label::Named(name.to_string()).into(),
+        ty,
+    }
+}
+
+fn record_of<'s>(fields: impl IntoIterator<Item = (&'s str, Type)>) -> Type {
+    TypeInner::Record(
+        fields
+            .into_iter()
+            .map(|(name, ty)| field(name, ty))
+            .collect(),
+    )
+    .into()
+}
+
+fn variant_of<'s>(fields: impl IntoIterator<Item = (&'s str, Type)>) -> Type {
+    TypeInner::Variant(
+        fields
+            .into_iter()
+            .map(|(name, ty)| field(name, ty))
+            .collect(),
+    )
+    .into()
+}
+
+fn tuple_of(types: Vec<Type>) -> Type {
+    let fields = (0u32..)
+        .zip(types)
+        .map(|(i, ty)| Field {
+            id: Label::Id(i).into(),
+            ty,
+        })
+        .collect();
+
+    TypeInner::Record(fields).into()
+}
+
+fn result_of(ok: Type, err: Type) -> Type {
+    variant_of([("Ok", ok), ("Err", err)])
+}
+
+// list_patch
+// mimic::core::view::ListPatch<U>
+fn list_patch(u: Type) -> Type {
+    let index = || TypeInner::Nat64.into();
+
+    variant_of([
+        (
+            "Update",
+            record_of([("index", index()), ("patch", u.clone())]),
+        ),
+        (
+            "Insert",
+            record_of([("index", index()), ("value", u.clone())]),
+        ),
+        ("Push", record_of([("value", u.clone())])),
+        ("Overwrite", record_of([("values", vec_of(u))])),
+        ("Remove", record_of([("index", index())])),
+        ("Clear", TypeInner::Null.into()),
+    ])
+}
+
+// set_patch
+// mimic::core::view::SetPatch<U>
+fn set_patch(u: Type) -> Type {
+    variant_of([
+        ("Insert", u.clone()),
+        ("Remove", u.clone()),
+        ("Overwrite", record_of([("values", vec_of(u))])),
+        ("Clear", TypeInner::Null.into()),
+    ])
+}
+
+// map_patch
+// mimic::core::view::MapPatch<K, V>
+fn map_patch(k: Type, v: Type) -> Type {
+    variant_of([
+        (
+            "Upsert",
+            record_of([("key", k.clone()), ("value", v.clone())]),
+        ),
+        ("Remove", record_of([("key", k.clone())])),
+        (
+            "Overwrite",
+            record_of([("entries", vec_of(tuple_of(vec![k, v])))]),
+        ),
+        ("Clear", TypeInner::Null.into()),
+    ])
+}
+
+///
+/// TESTS
+///
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render(name: &str, ty: Type) -> String {
+        let mut env = TypeEnv::new();
+        env.0.insert(name.to_string(), ty);
+
+        compile(&env, &None)
+    }
+
+    #[test]
+    fn patches_match_their_rust_types() {
+        let list = render("L", list_patch(TypeInner::Text.into()));
+        assert!(list.contains("Update : record { index : nat64; patch : text }"));
+        assert!(list.contains("Clear;"));
+
+        let map = render(
+            "M",
+            map_patch(TypeInner::Text.into(), TypeInner::Nat8.into()),
+        );
+        assert!(map.contains("Overwrite : record { entries : vec record { text; nat8 } }"));
+    }
+
+    #[test]
+    fn results_wrap_the_error() {
+        let result = render(
+            "R",
+            result_of(
+                TypeInner::Null.into(),
+                TypeInner::Var("Error".into()).into(),
+            ),
+        );
+
+        assert!(result.contains("variant { Ok; Err : Error }"));
+    }
+}
@@ -0,0 +1,148 @@
+use crate::{
+    ActorBuilder,
+    access::{self, Operation},
+};
+use mimic_schema::types::StoreType;
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::parse_str;
+
+// generate
+// only canisters that declare a job store get the runners, endpoints and scheduler
+#[must_use]
+pub fn generate(builder: &ActorBuilder) -> TokenStream {
+    let has_jobs = builder
+        .get_stores()
+        .iter()
+        .any(|(_, store)| matches!(store.ty, StoreType::Job));
+
+    if !has_jobs {
+        return quote!();
+    }
+
+    let canister_path: syn::Path = parse_str(&builder.canister.def.path())
+        .unwrap_or_else(|_| panic!("invalid canister path: {}", builder.canister.def.path()));
+
+    let mut runners = quote!();
+    for (entity_path, _) in builder.get_entities() {
+        let entity_ident: syn::Path = parse_str(&entity_path)
+            .unwrap_or_else(|_| panic!("invalid entity path: {entity_path}"));
+        runners.extend(quote! {
+            (
+                <#entity_ident as ::mimic::core::traits::Path>::PATH,
+                ::mimic::db::jobs::run_chunk::<#entity_ident>,
+            ),
+        });
+    }
+
+    let mut tokens = quote! {
+        /// Chunk runners for every entity in this canister, keyed by entity path.
+        pub static MIMIC_JOB_RUNNERS: &[(&str, ::mimic::db::jobs::JobRunner<#canister_path>)] = &[
+            #runners
+        ];
+
+        thread_local! {
+            static MIMIC_JOBS_SCHEDULED: ::std::cell::Cell<bool> = const { ::std::cell::Cell::new(false) };
+        }
+
+        // mimic_jobs_schedule
+        // queues one chunk on a zero-delay timer, each chunk queues the next while work remains
+        fn mimic_jobs_schedule() {
+            if MIMIC_JOBS_SCHEDULED.replace(true) {
+                return;
+            }
+
+            ::mimic::export::canic::cdk::timers::set_timer(::std::time::Duration::ZERO, async {
+                MIMIC_JOBS_SCHEDULED.set(false);
+
+                let budget = ::mimic::db::primitives::BudgetExpr::default()
+                    .max_instructions(::mimic::db::jobs::CHUNK_INSTRUCTIONS);
+                if matches!(::mimic::db::jobs::step(DB, MIMIC_JOB_RUNNERS, &budget), Ok(true)) {
+                    mimic_jobs_schedule();
+                }
+            });
+        }
+
+        /// Pick running jobs back up. Timers do not survive upgrades, so `mimic_schema_upgrade`
+        /// calls this from `mimic_init` and `mimic_post_upgrade`.
+        pub fn mimic_jobs_restart() {
+            mimic_jobs_schedule();
+        }
+    };
+
+    tokens.extend(endpoints(builder));
+
+    tokens
+}
+
+// endpoints
+// the job endpoints the canister hasn't skipped
+fn endpoints(builder: &ActorBuilder) -> TokenStream {
+    let check = access::check(builder, Operation::Admin, None);
+    let mut tokens = quote!();
+
+    if !builder.skips("mimic_jobs") {
+        tokens.extend(quote! {
+            /// Every maintenance job, oldest first.
+            #[::mimic::export::canic::cdk::query]
+            pub fn mimic_jobs() -> Result<Vec<::mimic::db::jobs::Job>, ::mimic::Error> {
+                #check
+                ::mimic::db::jobs::list(DB)
+            }
+        });
+    }
+
+    if !builder.skips("mimic_job_start") {
+        tokens.extend(quote! {
+            /// Start a maintenance job, which runs in chunks until it completes.
+            #[::mimic::export::canic::cdk::update]
+            pub fn mimic_job_start(
+                kind: ::mimic::db::jobs::JobKind,
+            ) -> Result<::mimic::db::jobs::Job, ::mimic::Error> {
+                #check
+                let job = ::mimic::db::jobs::start(DB, MIMIC_JOB_RUNNERS, kind)?;
+                mimic_jobs_schedule();
+
+                Ok(job)
+            }
+        });
+    }
+
+    if !builder.skips("mimic_job_pause") {
+        tokens.extend(quote! {
+            /// Pause a running job.
+            #[::mimic::export::canic::cdk::update]
+            pub fn mimic_job_pause(id: u64) -> Result<::mimic::db::jobs::Job, ::mimic::Error> {
+                #check
+                ::mimic::db::jobs::pause(DB, id)
+            }
+        });
+    }
+
+    if !builder.skips("mimic_job_resume") {
+        tokens.extend(quote! {
+            /// Resume a paused job from its cursor.
+            #[::mimic::export::canic::cdk::update]
+            pub fn mimic_job_resume(id: u64) -> Result<::mimic::db::jobs::Job, ::mimic::Error> {
+                #check
+                let job = ::mimic::db::jobs::resume(DB, id)?;
+                mimic_jobs_schedule();
+
+                Ok(job)
+            }
+        });
+    }
+
+    if !builder.skips("mimic_job_cancel") {
+        tokens.extend(quote! {
+            /// Cancel a job that has not finished.
+            #[::mimic::export::canic::cdk::update]
+            pub fn mimic_job_cancel(id: u64) -> Result<::mimic::db::jobs::Job, ::mimic::Error> {
+                #check
+                ::mimic::db::jobs::cancel(DB, id)
+            }
+        });
+    }
+
+    tokens
+}
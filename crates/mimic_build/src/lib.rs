@@ -1,6 +1,15 @@
+pub mod access;
+pub mod change_log;
+pub mod compat;
 pub mod db;
+pub mod did;
+pub mod jobs;
+pub mod lifecycle;
 pub mod metrics;
 pub mod query;
+pub mod schema;
+pub mod ts;
+pub mod ttl;
 
 use mimic_schema::{
     build::get_schema,
@@ -8,22 +17,85 @@ use mimic_schema::{
 };
 use proc_macro2::TokenStream;
 use quote::quote;
-use std::sync::Arc;
+use std::{path::Path, sync::Arc};
 
 // generate
 #[must_use]
 pub fn generate(canister_path: &str) -> String {
+    let tokens = actor_builder(canister_path).generate();
+
+    tokens.to_string()
+}
+
+// generate_did
+// the candid interface of the endpoints `generate` emits, see did::generate
+#[must_use]
+pub fn generate_did(canister_path: &str, builtins: did::Builtins) -> String {
+    did::generate(&actor_builder(canister_path), builtins)
+}
+
+// generate_ts
+// TypeScript types for the schema and a client for the canister, see ts::generate
+#[must_use]
+pub fn generate_ts(canister_path: &str) -> String {
+    ts::generate(&actor_builder(canister_path))
+}
+
+// actor_builder
+fn actor_builder(canister_path: &str) -> ActorBuilder {
     // load schema and get the specified canister
     let schema = get_schema().expect("schema must be valid before codegen");
 
     // filter by name
     let canister = schema.cast_node::<Canister>(canister_path).unwrap();
 
-    // create the ActorBuilder and generate the code
-    let code = ActorBuilder::new(Arc::new(schema.clone()), canister.clone());
-    let tokens = code.generate();
+    // the hash covers everything that decides how data is stored
+    let mut schema = schema.clone();
+    let hash = compat::fingerprint(&compat::SchemaSnapshot::from_schema(&schema));
+    schema.hash = Box::leak(format!("{hash:016x}").into_boxed_str());
 
-    tokens.to_string()
+    for name in canister.skip_endpoints {
+        assert!(
+            ENDPOINTS.contains(name),
+            "canister {canister_path} skips unknown endpoint '{name}'"
+        );
+    }
+
+    ActorBuilder::new(Arc::new(schema), canister.clone())
+}
+
+/// The endpoints `mimic_build` generates, any of which a canister can leave
+/// out with `skip_endpoint`.
+pub const ENDPOINTS: &[&str] = &[
+    "mimic_changes",
+    "mimic_job_cancel",
+    "mimic_job_pause",
+    "mimic_job_resume",
+    "mimic_job_start",
+    "mimic_jobs",
+    "mimic_metrics",
+    "mimic_metrics_reset",
+    "mimic_query_delete",
+    "mimic_query_insert_values",
+    "mimic_query_load",
+    "mimic_query_load_rows",
+    "mimic_query_load_values",
+    "mimic_query_load_views",
+    "mimic_query_patch",
+    "mimic_query_save",
+    "mimic_query_save_row",
+    "mimic_query_update_values",
+    "mimic_schema",
+    "mimic_snapshot",
+];
+
+// check_schema
+// compares the schema to the snapshot at `snapshot`, see compat::check_snapshot
+pub fn check_schema(snapshot: &str) -> Result<compat::SnapshotCheck, compat::CompatError> {
+    let schema = get_schema().expect("schema must be valid before codegen");
+    let accept = std::env::var_os(compat::ACCEPT_ENV).is_some();
+
+    compat::check_snapshot(&schema, Path::new(snapshot), accept)
 }
 
 ///
@@ -49,14 +121,26 @@ impl ActorBuilder {
 
         // shared between all canisters
         tokens.extend(db::generate(&self));
+        tokens.extend(change_log::generate(&self));
+        tokens.extend(jobs::generate(&self));
         tokens.extend(metrics::generate(&self));
         tokens.extend(query::generate(&self));
+        tokens.extend(schema::generate(&self));
+        tokens.extend(ttl::generate(&self));
+        tokens.extend(lifecycle::generate(&self));
 
         quote! {
             #tokens
         }
     }
 
+    // skips
+    // whether the canister left out endpoint `name`
+    #[must_use]
+    pub fn skips(&self, name: &str) -> bool {
+        self.canister.skip_endpoints.contains(&name)
+    }
+
     // get_stores
     #[must_use]
     pub fn get_stores(&self) -> Vec<(String, Store)> {
@@ -0,0 +1,35 @@
+use crate::{ActorBuilder, ttl};
+use proc_macro2::TokenStream;
+use quote::quote;
+
+// generate
+// what the canister runs on install and after every upgrade, called from the
+// canister's init and post_upgrade hooks, or the ones `mimic_start!(hooks = true)` generates
+#[must_use]
+pub fn generate(builder: &ActorBuilder) -> TokenStream {
+    // check the stored schema first, it may start jobs for old rows
+    let mut start = quote! {
+        mimic_schema_upgrade();
+    };
+
+    // timers do not survive upgrades, so both hooks start them
+    if ttl::has_ttl(builder) {
+        start.extend(quote! {
+            let _ = mimic_ttl_start(MIMIC_TTL_INTERVAL, MIMIC_TTL_BATCH);
+        });
+    }
+
+    quote! {
+        /// Run when the canister is installed. Canisters without `mimic_start!(hooks = true)`
+        /// call this from their own init hook.
+        pub fn mimic_init() {
+            #start
+        }
+
+        /// Run after every upgrade. Canisters without `mimic_start!(hooks = true)`
+        /// call this from their own post_upgrade hook, not from a timer it spawns.
+        pub fn mimic_post_upgrade() {
+            #start
+        }
+    }
+}
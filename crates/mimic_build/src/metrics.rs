@@ -1,4 +1,7 @@
-use crate::ActorBuilder;
+use crate::{
+    ActorBuilder,
+    access::{self, Operation},
+};
 use proc_macro2::TokenStream;
 use quote::quote;
 
@@ -13,32 +16,49 @@ pub fn generate(builder: &ActorBuilder) -> TokenStream {
         pairs.push(quote! { (#entity_ident::ENTITY_ID, #entity_ident::PATH) });
     }
 
-    quote! {
-        const MIMIC_ENTITY_ID_PATH: &[(u64, &str)] = &[
-            #(#pairs),*
-        ];
-
-        /// Storage snapshot (live view).
-        /// Includes data/index store stats and per-entity breakdown by store.
-        #[::mimic::export::canic::cdk::query]
-        pub fn mimic_snapshot() -> Result<::mimic::obs::snapshot::StorageReport, ::mimic::Error> {
-            Ok(::mimic::obs::snapshot::storage_report(&DB, MIMIC_ENTITY_ID_PATH))
-        }
-
-        /// Ephemeral event report since the internal `since_ms` (counters + per-entity summaries).
-        /// Call `mimic_metrics_reset` to reset counters and refresh `since_ms`.
-        #[::mimic::export::canic::cdk::query]
-        pub fn mimic_metrics() -> Result<::mimic::obs::metrics::EventReport, ::mimic::Error> {
-            Ok(::mimic::obs::metrics::report())
-        }
-
-        /// Reset ephemeral event state and refresh `since_ms`.
-        #[::mimic::export::canic::cdk::update]
-        pub fn mimic_metrics_reset() -> Result<(), ::mimic::Error> {
-            ::mimic::obs::metrics::reset_all();
-
-            Ok(())
-        }
+    let check = access::check(builder, Operation::Admin, None);
+    let mut tokens = quote!();
 
+    if !builder.skips("mimic_snapshot") {
+        tokens.extend(quote! {
+            const MIMIC_ENTITY_ID_PATH: &[(u64, &str)] = &[
+                #(#pairs),*
+            ];
+
+            /// Storage snapshot (live view).
+            /// Includes data/index store stats and per-entity breakdown by store.
+            #[::mimic::export::canic::cdk::query]
+            pub fn mimic_snapshot() -> Result<::mimic::obs::snapshot::StorageReport, ::mimic::Error> {
+                #check
+                Ok(::mimic::obs::snapshot::storage_report(&DB, MIMIC_ENTITY_ID_PATH))
+            }
+        });
+    }
+
+    if !builder.skips("mimic_metrics") {
+        tokens.extend(quote! {
+            /// Ephemeral event report since the internal `since_ms` (counters + per-entity summaries).
+            /// Call `mimic_metrics_reset` to reset counters and refresh `since_ms`.
+            #[::mimic::export::canic::cdk::query]
+            pub fn mimic_metrics() -> Result<::mimic::obs::metrics::EventReport, ::mimic::Error> {
+                #check
+                Ok(::mimic::obs::metrics::report())
+            }
+        });
     }
+
+    if !builder.skips("mimic_metrics_reset") {
+        tokens.extend(quote! {
+            /// Reset ephemeral event state and refresh `since_ms`.
+            #[::mimic::export::canic::cdk::update]
+            pub fn mimic_metrics_reset() -> Result<(), ::mimic::Error> {
+                #check
+                ::mimic::obs::metrics::reset_all();
+
+                Ok(())
+            }
+        });
+    }
+
+    tokens
 }
@@ -1,4 +1,7 @@
-use crate::ActorBuilder;
+use crate::{
+    ActorBuilder,
+    access::{self, Operation},
+};
 use proc_macro2::TokenStream;
 use quote::quote;
 use syn::{Path, parse_str};
@@ -6,15 +9,28 @@ use syn::{Path, parse_str};
 // generate
 #[must_use]
 pub fn generate(builder: &ActorBuilder) -> TokenStream {
-    let mut tokens = quote!();
+    let queries = [
+        ("mimic_query_load", QueryKind::Load),
+        ("mimic_query_save", QueryKind::Save),
+        ("mimic_query_delete", QueryKind::Delete),
+        // a page of views, with paging metadata
+        ("mimic_query_load_views", QueryKind::LoadViews),
+        // whole rows, for RemoteDb
+        ("mimic_query_load_rows", QueryKind::LoadRows),
+        ("mimic_query_save_row", QueryKind::SaveRow),
+        ("mimic_query_patch", QueryKind::Patch),
+        // untyped rows, for admin tools
+        ("mimic_query_load_values", QueryKind::LoadValues),
+        ("mimic_query_insert_values", QueryKind::InsertValues),
+        ("mimic_query_update_values", QueryKind::UpdateValues),
+    ];
 
-    tokens.extend(generate_query("mimic_query_load", builder, QueryKind::Load));
-    tokens.extend(generate_query("mimic_query_save", builder, QueryKind::Save));
-    tokens.extend(generate_query(
-        "mimic_query_delete",
-        builder,
-        QueryKind::Delete,
-    ));
+    let mut tokens = quote!();
+    for (name, kind) in queries {
+        if !builder.skips(name) {
+            tokens.extend(generate_query(name, builder, kind));
+        }
+    }
 
     tokens
 }
@@ -23,6 +39,27 @@ enum QueryKind {
     Load,
     Save,
     Delete,
+    LoadViews,
+    LoadRows,
+    SaveRow,
+    Patch,
+    LoadValues,
+    InsertValues,
+    UpdateValues,
+}
+
+impl QueryKind {
+    const fn operation(&self) -> Operation {
+        match self {
+            Self::Load | Self::LoadViews | Self::LoadRows | Self::LoadValues => Operation::Read,
+            Self::Save
+            | Self::Delete
+            | Self::SaveRow
+            | Self::Patch
+            | Self::InsertValues
+            | Self::UpdateValues => Operation::Write,
+        }
+    }
 }
 
 // generate_query
@@ -34,20 +71,17 @@ fn generate_query(name: &str, builder: &ActorBuilder, kind: QueryKind) -> TokenS
             Err(::mimic::interface::query::QueryError::EntityNotFound(path))?
         }
     } else {
-        let arms = entities.iter().map(|(entity_path, _)| {
+        let arms = entities.iter().map(|(entity_path, entity)| {
             let ty: Path =
                 parse_str(entity_path).unwrap_or_else(|_| panic!("Invalid path: {entity_path}"));
+            let check = access::check(builder, kind.operation(), Some(entity));
+            let dispatch = generate_dispatch(&kind, &ty);
 
-            match kind {
-                QueryKind::Load => quote! {
-                    #entity_path => db!().load::<#ty>().execute(query)?.keys(),
-                },
-                QueryKind::Delete => quote! {
-                    #entity_path => db!().delete::<#ty>().execute(query)?.keys(),
-                },
-                QueryKind::Save => quote! {
-                    #entity_path => db!().save::<#ty>().execute(query)?.key(),
-                },
+            quote! {
+                #entity_path => {
+                    #check
+                    #dispatch
+                }
             }
         });
 
@@ -62,8 +96,55 @@ fn generate_query(name: &str, builder: &ActorBuilder, kind: QueryKind) -> TokenS
     };
 
     // generate the fn
+    let fn_sig = generate_signature(name, &kind);
+
+    quote! {
+        #[allow(unused_variables)]
+        #fn_sig {
+            #match_arms
+        }
+    }
+}
+
+// generate_dispatch
+// runs the query against entity `ty`
+fn generate_dispatch(kind: &QueryKind, ty: &Path) -> TokenStream {
+    match kind {
+        QueryKind::Load => quote!(db!().load::<#ty>().execute(query)?.keys()),
+        QueryKind::Delete => quote!(db!().delete::<#ty>().execute(query)?.keys()),
+        QueryKind::Save => quote!(db!().save::<#ty>().execute(query)?.key()),
+        QueryKind::LoadViews => quote! {
+            ::mimic::interface::query::encode_view_page(db!().load::<#ty>().page(query)?)?
+        },
+        QueryKind::LoadRows => quote! {
+            ::mimic::interface::query::encode_rows(db!().load::<#ty>().execute(query)?)?
+        },
+        QueryKind::SaveRow => quote! {
+            ::mimic::core::serialize(&db!().save::<#ty>().execute(query)?)?
+        },
+        QueryKind::Patch => quote! {
+            ::mimic::core::serialize(
+                &db!().patch::<#ty>(key, ::mimic::core::deserialize(&update)?)?
+            )?
+        },
+        QueryKind::LoadValues => quote! {
+            ::mimic::interface::query::value_rows(db!().load::<#ty>().execute(query)?)
+        },
+        QueryKind::InsertValues => quote! {
+            ::mimic::interface::query::ValueRow::new(&db!().save::<#ty>().insert_values(&values)?)
+        },
+        QueryKind::UpdateValues => quote! {
+            ::mimic::interface::query::ValueRow::new(
+                &db!().save::<#ty>().update_values(key, &values)?
+            )
+        },
+    }
+}
+
+// generate_signature
+fn generate_signature(name: &str, kind: &QueryKind) -> TokenStream {
     let fn_name = quote::format_ident!("{name}");
-    let fn_sig = match kind {
+    match kind {
         QueryKind::Load => quote! {
             #[::mimic::export::canic::cdk::query]
             pub fn #fn_name(
@@ -87,12 +168,66 @@ fn generate_query(name: &str, builder: &ActorBuilder, kind: QueryKind) -> TokenS
                 query: ::mimic::db::query::DeleteQuery,
             ) -> Result<Vec<::mimic::core::Key>, ::mimic::Error>
         },
-    };
 
-    quote! {
-        #[allow(unused_variables)]
-        #fn_sig {
-            #match_arms
-        }
+        QueryKind::LoadViews => quote! {
+            /// One page of views. Without a filter, sort, soft delete or ttl the
+            /// total is a key count; otherwise every match is decoded to count it,
+            /// O(n), so set a budget on large entities and page by `resume`.
+            #[::mimic::export::canic::cdk::query]
+            pub fn #fn_name(
+                path: String,
+                query: ::mimic::db::query::LoadQuery,
+            ) -> Result<::mimic::interface::query::ViewPage, ::mimic::Error>
+        },
+
+        QueryKind::LoadRows => quote! {
+            #[::mimic::export::canic::cdk::query]
+            pub fn #fn_name(
+                path: String,
+                query: ::mimic::db::query::LoadQuery,
+            ) -> Result<Vec<Vec<u8>>, ::mimic::Error>
+        },
+
+        QueryKind::SaveRow => quote! {
+            #[::mimic::export::canic::cdk::update]
+            pub fn #fn_name(
+                path: String,
+                query: ::mimic::db::query::SaveQuery,
+            ) -> Result<Vec<u8>, ::mimic::Error>
+        },
+
+        QueryKind::Patch => quote! {
+            #[::mimic::export::canic::cdk::update]
+            pub fn #fn_name(
+                path: String,
+                key: ::mimic::core::Key,
+                update: Vec<u8>,
+            ) -> Result<Vec<u8>, ::mimic::Error>
+        },
+
+        QueryKind::LoadValues => quote! {
+            #[::mimic::export::canic::cdk::query]
+            pub fn #fn_name(
+                path: String,
+                query: ::mimic::db::query::LoadQuery,
+            ) -> Result<Vec<::mimic::interface::query::ValueRow>, ::mimic::Error>
+        },
+
+        QueryKind::InsertValues => quote! {
+            #[::mimic::export::canic::cdk::update]
+            pub fn #fn_name(
+                path: String,
+                values: ::std::collections::BTreeMap<String, ::mimic::core::Value>,
+            ) -> Result<::mimic::interface::query::ValueRow, ::mimic::Error>
+        },
+
+        QueryKind::UpdateValues => quote! {
+            #[::mimic::export::canic::cdk::update]
+            pub fn #fn_name(
+                path: String,
+                key: ::mimic::core::Key,
+                values: ::std::collections::BTreeMap<String, ::mimic::core::Value>,
+            ) -> Result<::mimic::interface::query::ValueRow, ::mimic::Error>
+        },
     }
 }
@@ -0,0 +1,159 @@
+use crate::{
+    ActorBuilder,
+    access::{self, Operation},
+};
+use mimic_schema::node::{Arg, Entity, Field, Index, ItemTarget, TypeSanitizer, TypeValidator};
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+
+// generate
+// the canister's stores and entities as a SchemaReport, for tooling
+#[must_use]
+pub fn generate(builder: &ActorBuilder) -> TokenStream {
+    if builder.skips("mimic_schema") {
+        return quote!();
+    }
+
+    let canister = builder.canister.def.path();
+    let stores = builder.get_stores().into_iter().map(|(path, store)| {
+        let ident = store.ident;
+        let ty = store.ty;
+        let memory_id = store.memory_id;
+
+        quote! {
+            ::mimic::obs::schema::StoreSchema {
+                path: #path.to_string(),
+                ident: #ident.to_string(),
+                ty: #ty,
+                memory_id: #memory_id,
+            }
+        }
+    });
+    let entities = builder
+        .get_entities()
+        .into_iter()
+        .map(|(path, entity)| entity_schema(&path, &entity));
+
+    let check = access::check(builder, Operation::Admin, None);
+
+    quote! {
+        /// The stores and entities of this canister, with their fields and indexes.
+        #[::mimic::export::canic::cdk::query]
+        pub fn mimic_schema() -> Result<::mimic::obs::schema::SchemaReport, ::mimic::Error> {
+            #check
+            Ok(::mimic::obs::schema::SchemaReport {
+                canister: #canister.to_string(),
+                hash: mimic_schema_record().hash,
+                stores: vec![#(#stores),*],
+                entities: vec![#(#entities),*],
+            })
+        }
+    }
+}
+
+// entity_schema
+fn entity_schema(path: &str, entity: &Entity) -> TokenStream {
+    let entity_path: syn::Path =
+        syn::parse_str(path).unwrap_or_else(|_| panic!("invalid entity path: {path}"));
+    let store = entity.store;
+    let primary_key = entity.primary_key;
+    let version = entity.version;
+    let soft_delete = entity.soft_delete;
+    let fields = entity.fields.fields.iter().map(field_schema);
+    let indexes = entity.indexes.iter().map(index_schema);
+    let validators = validators(entity.ty.validators);
+    let sanitizers = sanitizers(entity.ty.sanitizers);
+
+    quote! {
+        ::mimic::obs::schema::EntitySchema {
+            path: #path.to_string(),
+            entity_id: #entity_path::ENTITY_ID,
+            store: #store.to_string(),
+            primary_key: #primary_key.to_string(),
+            version: #version,
+            soft_delete: #soft_delete,
+            fields: vec![#(#fields),*],
+            indexes: vec![#(#indexes),*],
+            validators: #validators,
+            sanitizers: #sanitizers,
+        }
+    }
+}
+
+// field_schema
+fn field_schema(field: &Field) -> TokenStream {
+    let ident = field.ident;
+    let cardinality = field.value.cardinality;
+    let item = &field.value.item;
+    let (primitive, type_path) = match item.target {
+        ItemTarget::Primitive(prim) => {
+            let prim = format_ident!("{prim}");
+
+            (
+                quote!(Some(::mimic::schema::types::Primitive::#prim)),
+                quote!(None),
+            )
+        }
+        ItemTarget::Is(path) => (quote!(None), quote!(Some(#path.to_string()))),
+    };
+    let relation = item
+        .relation
+        .map_or_else(|| quote!(None), |path| quote!(Some(#path.to_string())));
+    let is_system = field.is_system;
+    let validators = validators(item.validators);
+    let sanitizers = sanitizers(item.sanitizers);
+
+    quote! {
+        ::mimic::obs::schema::FieldSchema {
+            ident: #ident.to_string(),
+            cardinality: #cardinality,
+            primitive: #primitive,
+            type_path: #type_path,
+            relation: #relation,
+            is_system: #is_system,
+            validators: #validators,
+            sanitizers: #sanitizers,
+        }
+    }
+}
+
+// index_schema
+fn index_schema(index: &Index) -> TokenStream {
+    let store = index.store;
+    let fields = index.fields;
+    let unique = index.unique;
+
+    quote! {
+        ::mimic::obs::schema::IndexSchema {
+            store: #store.to_string(),
+            fields: vec![#(#fields.to_string()),*],
+            unique: #unique,
+        }
+    }
+}
+
+// validators
+fn validators(nodes: &[TypeValidator]) -> TokenStream {
+    let rules = nodes.iter().map(|node| rule(node.path, &node.args));
+
+    quote!(vec![#(#rules),*])
+}
+
+// sanitizers
+fn sanitizers(nodes: &[TypeSanitizer]) -> TokenStream {
+    let rules = nodes.iter().map(|node| rule(node.path, &node.args));
+
+    quote!(vec![#(#rules),*])
+}
+
+// rule
+fn rule(path: &str, args: &[Arg]) -> TokenStream {
+    let args = args.iter().map(ToString::to_string);
+
+    quote! {
+        ::mimic::obs::schema::RuleSchema {
+            path: #path.to_string(),
+            args: vec![#(#args.to_string()),*],
+        }
+    }
+}
@@ -0,0 +1,761 @@
+//! TypeScript types for every entity and type node in the schema, plus a thin
+//! typed client for the canister's generated query endpoints.
+//!
+//! Rows and views travel as CBOR inside the candid calls, so the types follow
+//! their serde form (options are `T | null`, unit enum variants are strings,
+//! tuples are arrays) rather than the candid JS mapping.
+
+use crate::ActorBuilder;
+use mimic_schema::{
+    node::{
+        Def, Entity, Enum, Field, Item, ItemTarget, List, Map, Newtype, Record, Schema, Set, Tuple,
+        Value,
+    },
+    types::{Cardinality, Primitive},
+};
+use std::{collections::BTreeMap, fmt::Write};
+
+// generate
+#[must_use]
+pub fn generate(builder: &ActorBuilder) -> String {
+    let ts = Ts::new(&builder.schema);
+    let mut out = String::from(HEADER);
+
+    ts.types(&mut out);
+    ts.client(builder, &mut out);
+
+    out
+}
+
+///
+/// Ts
+/// the TypeScript name of every type node, plus the schema to look them up in
+///
+
+struct Ts<'a> {
+    schema: &'a Schema,
+    names: BTreeMap<String, String>,
+}
+
+impl<'a> Ts<'a> {
+    fn new(schema: &'a Schema) -> Self {
+        let defs = type_defs(schema);
+
+        // idents shared by several nodes, or whose Filter would shadow a
+        // prelude filter, fall back to the full path
+        let mut counts = BTreeMap::<&str, usize>::new();
+        for def in defs.values() {
+            *counts.entry(def.ident).or_default() += 1;
+        }
+        let names = defs
+            .iter()
+            .map(|(path, def)| {
+                let name = if counts[def.ident] > 1 || PRELUDE_FILTERS.contains(&def.ident) {
+                    path.replace("::", "_")
+                } else {
+                    def.ident.to_string()
+                };
+
+                (path.clone(), name)
+            })
+            .collect();
+
+        Self { schema, names }
+    }
+
+    fn name(&self, path: &str, suffix: &str) -> String {
+        let name = self
+            .names
+            .get(path)
+            .unwrap_or_else(|| panic!("no type node at '{path}'"));
+
+        format!("{name}{suffix}")
+    }
+
+    // types
+    // every type node, in path order
+    fn types(&self, out: &mut String) {
+        out.push_str("\n//\n// TYPES\n//\n");
+
+        self.struct_types(out);
+        self.alias_types(out);
+    }
+
+    // struct_types
+    // entities and records become interfaces
+    fn struct_types(&self, out: &mut String) {
+        for (path, node) in self.schema.get_nodes::<Entity>() {
+            let fields = node.fields.fields;
+            let editable: Vec<Field> = fields
+                .iter()
+                .filter(|f| f.ident != node.primary_key && !f.is_system)
+                .cloned()
+                .collect();
+
+            doc(out, &node.def);
+            self.interface(out, &self.name(path, "View"), fields, Self::field_view);
+            self.interface(out, &self.name(path, "Create"), &editable, Self::field_view);
+            self.interface(
+                out,
+                &self.name(path, "Update"),
+                &editable,
+                Self::field_update,
+            );
+            self.interface(out, &self.name(path, "Filter"), fields, Self::field_filter);
+        }
+
+        for (path, node) in self.schema.get_nodes::<Record>() {
+            let fields = node.fields.fields;
+
+            doc(out, &node.def);
+            self.interface(out, &self.name(path, "View"), fields, Self::field_view);
+            self.interface(out, &self.name(path, "Update"), fields, Self::field_update);
+            self.interface(out, &self.name(path, "Filter"), fields, Self::field_filter);
+        }
+    }
+
+    // alias_types
+    // every other type node becomes a type alias
+    fn alias_types(&self, out: &mut String) {
+        for (path, node) in self.schema.get_nodes::<Enum>() {
+            let variants: Vec<String> = node
+                .variants
+                .iter()
+                .map(|v| {
+                    let ident = if v.unspecified {
+                        "Unspecified"
+                    } else {
+                        v.ident
+                    };
+                    match &v.value {
+                        Some(value) => format!("{{ {ident}: {} }}", self.value_view(value)),
+                        None => format!("\"{ident}\""),
+                    }
+                })
+                .collect();
+
+            doc(out, &node.def);
+            alias(out, &self.name(path, "View"), &variants.join(" | "));
+            alias(out, &self.name(path, "Update"), &self.name(path, "View"));
+        }
+
+        for (path, node) in self.schema.get_nodes::<Newtype>() {
+            doc(out, &node.def);
+            alias(out, &self.name(path, "View"), &self.item_view(&node.item));
+            alias(
+                out,
+                &self.name(path, "Update"),
+                &self.item_update(&node.item),
+            );
+        }
+
+        for (path, node) in self.schema.get_nodes::<List>() {
+            let update = format!("ListPatch<{}>", self.item_update(&node.item));
+
+            doc(out, &node.def);
+            alias(
+                out,
+                &self.name(path, "View"),
+                &array(&self.item_view(&node.item)),
+            );
+            alias(out, &self.name(path, "Update"), &array(&update));
+        }
+
+        for (path, node) in self.schema.get_nodes::<Set>() {
+            let update = format!("SetPatch<{}>", self.item_update(&node.item));
+
+            doc(out, &node.def);
+            alias(
+                out,
+                &self.name(path, "View"),
+                &array(&self.item_view(&node.item)),
+            );
+            alias(out, &self.name(path, "Update"), &array(&update));
+        }
+
+        for (path, node) in self.schema.get_nodes::<Map>() {
+            let view = format!(
+                "[{}, {}]",
+                self.item_view(&node.key),
+                self.value_view(&node.value)
+            );
+            let update = format!(
+                "MapPatch<{}, {}>",
+                self.item_update(&node.key),
+                self.value_update(&node.value)
+            );
+
+            doc(out, &node.def);
+            alias(out, &self.name(path, "View"), &array(&view));
+            alias(out, &self.name(path, "Update"), &array(&update));
+        }
+
+        for (path, node) in self.schema.get_nodes::<Tuple>() {
+            let view: Vec<_> = node.values.iter().map(|v| self.value_view(v)).collect();
+            let update: Vec<_> = node
+                .values
+                .iter()
+                .map(|v| format!("{} | null", self.value_update(v)))
+                .collect();
+
+            doc(out, &node.def);
+            alias(
+                out,
+                &self.name(path, "View"),
+                &format!("[{}]", view.join(", ")),
+            );
+            alias(
+                out,
+                &self.name(path, "Update"),
+                &format!("[{}]", update.join(", ")),
+            );
+        }
+    }
+
+    fn interface(
+        &self,
+        out: &mut String,
+        name: &str,
+        fields: &[Field],
+        field: fn(&Self, &Field) -> String,
+    ) {
+        let _ = writeln!(out, "export interface {name} {{");
+        for f in fields {
+            let _ = writeln!(out, "  {};", field(self, f));
+        }
+        out.push_str("}\n\n");
+    }
+
+    fn field_view(&self, f: &Field) -> String {
+        format!("{}: {}", f.ident, self.value_view(&f.value))
+    }
+
+    fn field_update(&self, f: &Field) -> String {
+        format!("{}?: {} | null", f.ident, self.value_update(&f.value))
+    }
+
+    fn field_filter(&self, f: &Field) -> String {
+        let filter = match f.value.cardinality {
+            Cardinality::One | Cardinality::Opt => self.item_filter(&f.value.item).0,
+            Cardinality::Many => self.item_filter(&f.value.item).1,
+        };
+
+        format!("{}?: {filter} | null", f.ident)
+    }
+
+    fn value_view(&self, value: &Value) -> String {
+        let item = self.item_view(&value.item);
+
+        match value.cardinality {
+            Cardinality::One => item,
+            Cardinality::Opt => format!("{item} | null"),
+            Cardinality::Many => array(&item),
+        }
+    }
+
+    fn value_update(&self, value: &Value) -> String {
+        let item = self.item_update(&value.item);
+
+        match value.cardinality {
+            Cardinality::One => item,
+            Cardinality::Opt => format!("{item} | null"),
+            Cardinality::Many => array(&format!("ListPatch<{item}>")),
+        }
+    }
+
+    fn item_view(&self, item: &Item) -> String {
+        match item.target {
+            ItemTarget::Primitive(prim) => primitive(prim).to_string(),
+            ItemTarget::Is(path) => self.name(path, "View"),
+        }
+    }
+
+    fn item_update(&self, item: &Item) -> String {
+        match item.target {
+            ItemTarget::Primitive(prim) => primitive(prim).to_string(),
+            ItemTarget::Is(path) => self.name(path, "Update"),
+        }
+    }
+
+    // item_filter
+    // the (scalar, list) filter payloads, newtypes filter like their item
+    // and every other type node can't be filtered on
+    fn item_filter(&self, item: &Item) -> (&'static str, &'static str) {
+        match item.target {
+            ItemTarget::Primitive(prim) => primitive_filter(prim),
+            ItemTarget::Is(path) => match self.schema.cast_node::<Newtype>(path) {
+                Ok(node) => self.item_filter(&node.item),
+                Err(_) => ("NoFilter", "NoFilter"),
+            },
+        }
+    }
+
+    // client
+    // the entities stored in this canister and a client for its query endpoints
+    fn client(&self, builder: &ActorBuilder, out: &mut String) {
+        out.push_str("//\n// CLIENT\n//\n\n");
+        out.push_str("/** The types each entity in this canister is read and written as. */\n");
+        out.push_str("export interface Entities {\n");
+        for (path, _) in builder.get_entities() {
+            let _ = writeln!(
+                out,
+                "  \"{path}\": {{ view: {}; create: {}; update: {}; filter: {} }};",
+                self.name(&path, "View"),
+                self.name(&path, "Create"),
+                self.name(&path, "Update"),
+                self.name(&path, "Filter"),
+            );
+        }
+        out.push_str("}\n\n");
+        out.push_str(CLIENT);
+
+        for (name, method) in CLIENT_METHODS {
+            if !builder.skips(name) {
+                out.push_str(method);
+            }
+        }
+        out.push_str("}\n");
+    }
+}
+
+// type_defs
+// the def of every node that becomes a TypeScript type, by path
+fn type_defs(schema: &Schema) -> BTreeMap<String, Def> {
+    let mut defs = BTreeMap::new();
+    let mut add = |path: &str, def: &Def| {
+        defs.insert(path.to_string(), def.clone());
+    };
+
+    schema
+        .get_nodes::<Entity>()
+        .for_each(|(p, n)| add(p, &n.def));
+    schema.get_nodes::<Enum>().for_each(|(p, n)| add(p, &n.def));
+    schema.get_nodes::<List>().for_each(|(p, n)| add(p, &n.def));
+    schema.get_nodes::<Map>().for_each(|(p, n)| add(p, &n.def));
+    schema
+        .get_nodes::<Newtype>()
+        .for_each(|(p, n)| add(p, &n.def));
+    schema
+        .get_nodes::<Record>()
+        .for_each(|(p, n)| add(p, &n.def));
+    schema.get_nodes::<Set>().for_each(|(p, n)| add(p, &n.def));
+    schema
+        .get_nodes::<Tuple>()
+        .for_each(|(p, n)| add(p, &n.def));
+
+    defs
+}
+
+fn doc(out: &mut String, def: &Def) {
+    let _ = writeln!(out, "// {}::{}", def.module_path, def.ident);
+    if let Some(comments) = def.comments {
+        for line in comments.lines() {
+            out.push_str(format!("// {}", line.trim()).trim_end());
+            out.push('\n');
+        }
+    }
+}
+
+fn alias(out: &mut String, name: &str, ty: &str) {
+    let _ = write!(out, "export type {name} = {ty};\n\n");
+}
+
+fn array(item: &str) -> String {
+    format!("Array<{item}>")
+}
+
+// primitive
+// the serde form of each primitive's View, which is also its Update
+const fn primitive(prim: Primitive) -> &'static str {
+    match prim {
+        Primitive::Account => "Account",
+        Primitive::Blob | Primitive::Int128 | Primitive::Nat128 | Primitive::Principal => {
+            "Uint8Array"
+        }
+        Primitive::Bool => "boolean",
+        Primitive::Date | Primitive::Decimal | Primitive::Text | Primitive::Ulid => "string",
+        Primitive::Duration | Primitive::E8s | Primitive::Nat64 | Primitive::Timestamp => "U64",
+        Primitive::E18s => "U128",
+        Primitive::Float32
+        | Primitive::Float64
+        | Primitive::Int8
+        | Primitive::Int16
+        | Primitive::Int32
+        | Primitive::Nat8
+        | Primitive::Nat16
+        | Primitive::Nat32 => "number",
+        Primitive::Int => "Int",
+        Primitive::Int64 => "I64",
+        Primitive::Nat => "Nat",
+        Primitive::Subaccount => "Subaccount",
+        Primitive::Unit => "null",
+    }
+}
+
+// primitive_filter
+// the (scalar, list) filter payloads of each primitive, see impl Filterable
+const fn primitive_filter(prim: Primitive) -> (&'static str, &'static str) {
+    match prim {
+        Primitive::Account | Primitive::Text | Primitive::Ulid => ("TextFilter", "TextListFilter"),
+        Primitive::Blob | Primitive::Subaccount | Primitive::Unit => ("NoFilter", "NoFilter"),
+        Primitive::Bool => ("BoolEqualityFilter", "BoolListFilter"),
+        Primitive::Date => ("Int64RangeFilter", "Int64ListFilter"),
+        Primitive::Decimal | Primitive::Float32 | Primitive::Float64 => {
+            ("DecimalRangeFilter", "DecimalListFilter")
+        }
+        Primitive::Duration | Primitive::E8s | Primitive::Timestamp => {
+            ("Nat64RangeFilter", "Nat64ListFilter")
+        }
+        Primitive::E18s | Primitive::Nat | Primitive::Nat128 => ("NatRangeFilter", "NatListFilter"),
+        Primitive::Int | Primitive::Int128 => ("IntRangeFilter", "IntListFilter"),
+        Primitive::Int8 | Primitive::Int16 | Primitive::Int32 | Primitive::Int64 => {
+            ("Int64RangeFilter", "IntListFilter")
+        }
+        Primitive::Nat8 | Primitive::Nat16 | Primitive::Nat32 | Primitive::Nat64 => {
+            ("Nat64RangeFilter", "NatListFilter")
+        }
+        Primitive::Principal => ("TextEqualityFilter", "TextListFilter"),
+    }
+}
+
+// idents whose `{ident}Filter` is already declared in HEADER
+const PRELUDE_FILTERS: &[&str] = &[
+    "BoolEquality",
+    "BoolList",
+    "DecimalList",
+    "DecimalRange",
+    "Equality",
+    "Int64List",
+    "Int64Range",
+    "IntList",
+    "IntRange",
+    "ListValue",
+    "Nat64List",
+    "Nat64Range",
+    "NatList",
+    "NatRange",
+    "No",
+    "Range",
+    "Text",
+    "TextEquality",
+    "TextList",
+];
+
+const HEADER: &str = r#"// Generated by mimic_build from the schema, do not edit.
+
+import type { _SERVICE } from "./actor.did";
+
+//
+// PRIMITIVES
+// CBOR decoders give back a number or a bigint depending on the size
+//
+
+export type U64 = number | bigint;
+export type U128 = number | bigint;
+export type I64 = number | bigint;
+
+/** num-bigint's serde form: base 2^32 digits, least significant first. */
+export type Nat = Array<number>;
+export type Int = [-1 | 0 | 1, Nat];
+
+export type Subaccount = Array<number>;
+export interface Account {
+  owner: Uint8Array;
+  subaccount: Subaccount | null;
+}
+
+//
+// PATCHES
+//
+
+export type ListPatch<U> =
+  | { Update: { index: number; patch: U } }
+  | { Insert: { index: number; value: U } }
+  | { Push: { value: U } }
+  | { Overwrite: { values: Array<U> } }
+  | { Remove: { index: number } }
+  | "Clear";
+
+export type SetPatch<U> =
+  | { Insert: U }
+  | { Remove: U }
+  | { Overwrite: { values: Array<U> } }
+  | "Clear";
+
+export type MapPatch<K, V> =
+  | { Upsert: { key: K; value: V } }
+  | { Remove: { key: K } }
+  | { Overwrite: { entries: Array<[K, V]> } }
+  | "Clear";
+
+//
+// FILTERS
+//
+
+export type NoFilter = null;
+
+export interface EqualityFilter<T> {
+  eq?: T | null;
+  ne?: T | null;
+  in_?: Array<T> | null;
+  not_in?: Array<T> | null;
+  is_none?: boolean | null;
+}
+
+export interface RangeFilter<V> {
+  gt?: V | null;
+  gte?: V | null;
+  lt?: V | null;
+  lte?: V | null;
+  between?: [V, V] | null;
+}
+
+export interface ListValueFilter<T> {
+  contains?: T | null;
+  has_any?: Array<T> | null;
+  has_all?: Array<T> | null;
+  not_contains?: T | null;
+  has_none?: Array<T> | null;
+  lacks_all?: Array<T> | null;
+  len?: RangeFilter<I64> | null;
+}
+
+export interface TextClause {
+  values: Array<string>;
+}
+
+export interface TextFilter {
+  equal_cs?: TextClause | null;
+  equal_ci?: TextClause | null;
+  not_equal_cs?: TextClause | null;
+  not_equal_ci?: TextClause | null;
+  contains_cs?: TextClause | null;
+  contains_ci?: TextClause | null;
+  starts_with_cs?: TextClause | null;
+  starts_with_ci?: TextClause | null;
+  ends_with_cs?: TextClause | null;
+  ends_with_ci?: TextClause | null;
+  is_empty?: boolean | null;
+}
+
+export type TextEqualityFilter = EqualityFilter<string>;
+export type BoolEqualityFilter = EqualityFilter<boolean>;
+export type DecimalRangeFilter = RangeFilter<string>;
+export type Int64RangeFilter = RangeFilter<I64>;
+export type IntRangeFilter = RangeFilter<Int>;
+export type Nat64RangeFilter = RangeFilter<U64>;
+export type NatRangeFilter = RangeFilter<Nat>;
+export type TextListFilter = ListValueFilter<string>;
+export type BoolListFilter = ListValueFilter<boolean>;
+export type DecimalListFilter = ListValueFilter<string>;
+export type Int64ListFilter = ListValueFilter<I64>;
+export type IntListFilter = ListValueFilter<Int>;
+export type Nat64ListFilter = ListValueFilter<U64>;
+export type NatListFilter = ListValueFilter<Nat>;
+"#;
+
+const CLIENT: &str = r#"export type EntityPath = keyof Entities;
+
+type Method = keyof _SERVICE;
+type Arg<M extends Method, N extends number> = Parameters<_SERVICE[M]>[N];
+type Ok<M extends Method> = Extract<Awaited<ReturnType<_SERVICE[M]>>, { Ok: unknown }>["Ok"];
+
+/** Turns rows into bytes and back the way the canister does, with CBOR. */
+export interface Codec {
+  encode(value: unknown): Uint8Array;
+  decode(bytes: Uint8Array | Array<number>): unknown;
+}
+
+/** A `mimic::Error` returned by the canister. */
+export class MimicError extends Error {
+  constructor(readonly error: { [kind: string]: string }) {
+    super(Object.entries(error).map(([kind, msg]) => `${kind}: ${msg}`).join(", "));
+  }
+}
+
+function unwrap<T>(result: { Ok: T } | { Err: { [kind: string]: string } }): T {
+  if ("Err" in result) {
+    throw new MimicError(result.Err);
+  }
+
+  return result.Ok;
+}
+
+/** Calls the generated query endpoints through an agent actor for the canister. */
+export class MimicClient {
+  constructor(
+    private readonly actor: _SERVICE,
+    private readonly codec: Codec,
+  ) {}
+"#;
+
+const CLIENT_METHODS: &[(&str, &str)] = &[
+    (
+        "mimic_query_load",
+        r#"
+  /** Keys of the rows matching `query`. */
+  async load(path: EntityPath, query: Arg<"mimic_query_load", 1>) {
+    return unwrap(await this.actor.mimic_query_load(path, query));
+  }
+"#,
+    ),
+    (
+        "mimic_query_load_views",
+        r#"
+  /** A page of views, with the total match count and where the next page starts. */
+  async loadViews<P extends EntityPath>(path: P, query: Arg<"mimic_query_load_views", 1>) {
+    const page: Ok<"mimic_query_load_views"> = unwrap(
+      await this.actor.mimic_query_load_views(path, query),
+    );
+
+    return {
+      rows: page.rows.map(({ key, view }) => [key, this.codec.decode(view) as Entities[P]["view"]] as const),
+      total: page.total[0],
+      next: page.next[0],
+    };
+  }
+"#,
+    ),
+    (
+        "mimic_query_load_rows",
+        r#"
+  /** The rows matching `query`. */
+  async loadRows<P extends EntityPath>(path: P, query: Arg<"mimic_query_load_rows", 1>) {
+    const rows = unwrap(await this.actor.mimic_query_load_rows(path, query));
+
+    return rows.map((row) => this.codec.decode(row) as Entities[P]["view"]);
+  }
+"#,
+    ),
+    (
+        "mimic_query_save",
+        r#"
+  /** Save a row, returning its key. */
+  async save<P extends EntityPath>(
+    path: P,
+    mode: Arg<"mimic_query_save", 1>["mode"],
+    row: Entities[P]["view"],
+  ) {
+    return unwrap(await this.actor.mimic_query_save(path, { mode, bytes: this.codec.encode(row) }));
+  }
+"#,
+    ),
+    (
+        "mimic_query_save_row",
+        r#"
+  /** Save a row, returning it as stored. */
+  async saveRow<P extends EntityPath>(
+    path: P,
+    mode: Arg<"mimic_query_save_row", 1>["mode"],
+    row: Entities[P]["view"],
+  ) {
+    const bytes = unwrap(
+      await this.actor.mimic_query_save_row(path, { mode, bytes: this.codec.encode(row) }),
+    );
+
+    return this.codec.decode(bytes) as Entities[P]["view"];
+  }
+"#,
+    ),
+    (
+        "mimic_query_patch",
+        r#"
+  /** Merge `update` into the stored row at `key`, returning the result. */
+  async patch<P extends EntityPath>(
+    path: P,
+    key: Arg<"mimic_query_patch", 1>,
+    update: Entities[P]["update"],
+  ) {
+    const bytes = unwrap(await this.actor.mimic_query_patch(path, key, this.codec.encode(update)));
+
+    return this.codec.decode(bytes) as Entities[P]["view"];
+  }
+"#,
+    ),
+    (
+        "mimic_query_delete",
+        r#"
+  /** Delete the rows matching `query`, returning their keys. */
+  async delete(path: EntityPath, query: Arg<"mimic_query_delete", 1>) {
+    return unwrap(await this.actor.mimic_query_delete(path, query));
+  }
+"#,
+    ),
+];
+
+///
+/// TESTS
+///
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mimic_schema::node::{SchemaNode, Type};
+
+    fn newtype(module_path: &'static str, ident: &'static str, prim: Primitive) -> SchemaNode {
+        SchemaNode::Newtype(Newtype {
+            def: Def {
+                module_path,
+                ident,
+                comments: None,
+            },
+            item: Item {
+                target: ItemTarget::Primitive(prim),
+                relation: None,
+                relation_index: None,
+                validators: &[],
+                sanitizers: &[],
+                indirect: false,
+            },
+            default: None,
+            ty: Type {
+                sanitizers: &[],
+                validators: &[],
+            },
+        })
+    }
+
+    fn schema() -> Schema {
+        let mut schema = Schema::new();
+        schema.insert_node(newtype("a", "Code", Primitive::Text));
+        schema.insert_node(newtype("b", "Code", Primitive::Nat8));
+        schema.insert_node(newtype("a", "Text", Primitive::Text));
+        schema.insert_node(newtype("a", "Email", Primitive::Text));
+
+        schema
+    }
+
+    #[test]
+    fn clashing_names_use_the_path() {
+        let schema = schema();
+        let ts = Ts::new(&schema);
+
+        assert_eq!(ts.name("a::Code", "View"), "a_CodeView");
+        assert_eq!(ts.name("b::Code", "View"), "b_CodeView");
+        assert_eq!(ts.name("a::Text", "Filter"), "a_TextFilter");
+        assert_eq!(ts.name("a::Email", "View"), "EmailView");
+    }
+
+    #[test]
+    fn newtypes_filter_like_their_item() {
+        let schema = schema();
+        let ts = Ts::new(&schema);
+        let item = |path| Item {
+            target: ItemTarget::Is(path),
+            relation: None,
+            relation_index: None,
+            validators: &[],
+            sanitizers: &[],
+            indirect: false,
+        };
+
+        assert_eq!(
+            ts.item_filter(&item("a::Email")),
+            ("TextFilter", "TextListFilter")
+        );
+        assert_eq!(
+            ts.item_filter(&item("b::Code")),
+            ("Nat64RangeFilter", "NatListFilter")
+        );
+    }
+}
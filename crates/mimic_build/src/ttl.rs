@@ -0,0 +1,73 @@
+use crate::ActorBuilder;
+use proc_macro2::TokenStream;
+use quote::quote;
+
+// has_ttl
+// whether any entity in the canister expires rows
+#[must_use]
+pub fn has_ttl(builder: &ActorBuilder) -> bool {
+    builder
+        .get_entities()
+        .iter()
+        .any(|(_, entity)| entity.ttl.is_some())
+}
+
+// generate
+// only canisters with at least one ttl entity get the sweeper
+#[must_use]
+pub fn generate(builder: &ActorBuilder) -> TokenStream {
+    let mut sweeps = quote!();
+    for (entity_path, entity) in builder.get_entities() {
+        if entity.ttl.is_none() {
+            continue;
+        }
+
+        let entity_ident: syn::Path = syn::parse_str(&entity_path)
+            .unwrap_or_else(|_| panic!("invalid entity path: {entity_path}"));
+        sweeps.extend(quote! {
+            expired += u64::from(db().delete::<#entity_ident>().sweep_expired(batch)?.count());
+        });
+    }
+
+    if sweeps.is_empty() {
+        return quote!();
+    }
+
+    quote! {
+        /// How often the sweeper started by `mimic_init` and `mimic_post_upgrade` runs.
+        pub const MIMIC_TTL_INTERVAL: ::std::time::Duration = ::std::time::Duration::from_mins(1);
+
+        /// Rows each ttl entity purges per sweep.
+        pub const MIMIC_TTL_BATCH: u32 = 500;
+
+        /// Purge up to `batch` expired rows from each ttl entity, returning the number removed.
+        pub fn mimic_ttl_sweep(batch: u32) -> Result<u64, ::mimic::Error> {
+            let mut expired = 0u64;
+            #sweeps
+
+            Ok(expired)
+        }
+
+        thread_local! {
+            // set once this instance has started the sweeper, see `db()`
+            static MIMIC_TTL_STARTED: ::std::cell::Cell<bool> = const { ::std::cell::Cell::new(false) };
+        }
+
+        /// Run `mimic_ttl_sweep` every `interval`, logging failed sweeps.
+        /// Timers do not survive upgrades; `mimic_init` and `mimic_post_upgrade` start it,
+        /// and `db()` traps until it has been started.
+        #[must_use]
+        pub fn mimic_ttl_start(
+            interval: ::std::time::Duration,
+            batch: u32,
+        ) -> ::mimic::export::canic::cdk::timers::TimerId {
+            MIMIC_TTL_STARTED.set(true);
+
+            ::mimic::export::canic::cdk::timers::set_timer_interval(interval, move || async move {
+                if let Err(e) = mimic_ttl_sweep(batch) {
+                    ::mimic::export::canic::log!(Error, "ttl sweep failed: {e}");
+                }
+            })
+        }
+    }
+}
@@ -12,7 +12,7 @@ pub struct ErrorTree {
     pub messages: Vec<String>,
 
     /// child errors indexed by field/key
-    pub children: HashMap<String, ErrorTree>,
+    pub children: HashMap<String, Self>,
 }
 
 impl ErrorTree {
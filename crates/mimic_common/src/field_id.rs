@@ -0,0 +1,30 @@
+/// field_id
+/// A field's key in `Compact` rows: its name's FNV-1a hash folded to 16 bits,
+/// so it stays the same when fields are added, removed or reordered.
+#[must_use]
+pub fn field_id(name: &str) -> u16 {
+    let hash = name.bytes().fold(0x811c_9dc5_u32, |hash, b| {
+        (hash ^ u32::from(b)).wrapping_mul(0x0100_0193)
+    });
+
+    #[allow(clippy::cast_possible_truncation)]
+    let id = ((hash >> 16) ^ hash) as u16;
+
+    id
+}
+
+//
+// TESTS
+//
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ids_depend_only_on_the_name() {
+        assert_eq!(field_id("name"), field_id("name"));
+        assert_ne!(field_id("name"), field_id("score"));
+        assert_eq!(field_id(""), 0x9dc5 ^ 0x811c);
+    }
+}
@@ -1,2 +1,3 @@
 pub mod case;
 pub mod error;
+pub mod field_id;
@@ -1,6 +1,5 @@
 use proc_macro2::TokenStream;
-use quote::{ToTokens, format_ident, quote};
-use syn::Ident;
+use quote::{ToTokens, quote};
 
 ///
 /// QUOTING
@@ -72,13 +71,13 @@ pub fn to_path<T: ToTokens>(t: &T) -> TokenStream {
 /// DARLING HELPERS
 ///
 
-// split_idents
+// split_list
 #[allow(clippy::needless_pass_by_value)]
 #[must_use]
-pub fn split_idents(s: String) -> Vec<Ident> {
+pub fn split_list(s: String) -> Vec<String> {
     s.split(',')
         .map(str::trim)
         .filter(|item| !item.is_empty())
-        .map(|item| format_ident!("{item}"))
+        .map(ToString::to_string)
         .collect()
 }
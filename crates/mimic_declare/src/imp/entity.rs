@@ -23,11 +23,46 @@ impl Imp<Entity> for EntityKindTrait {
 
         // indexes
         let indexes = &node
-            .indexes
+            .all_indexes()
             .iter()
             .map(Index::schema_part)
             .collect::<Vec<_>>();
 
+        // relations
+        let relations = &node
+            .fields
+            .iter()
+            .filter(|f| f.value.item.is_relation())
+            .map(Field::schema_part)
+            .collect::<Vec<_>>();
+
+        let soft_delete = node.soft_delete;
+        let ttl_field = quote_option(node.ttl.as_ref(), |ttl| to_str_lit(&ttl.field));
+        let history_limit = quote_option(node.history.as_ref(), |history| {
+            let limit = history.limit;
+            quote!(#limit)
+        });
+
+        let version = node.version;
+        let codec = node.codec.as_ref().map_or_else(
+            || quote!(::mimic::db::store::RowCodec::DEFAULT),
+            |codec| {
+                let format = &codec.format;
+                let compress = codec.compress;
+                quote!(::mimic::db::store::RowCodec::new(#format, #compress))
+            },
+        );
+        let retired = node
+            .codec
+            .as_ref()
+            .map(|codec| codec.retired.as_slice())
+            .unwrap_or_default();
+        let migrations = node
+            .sorted_migrations()
+            .into_iter()
+            .map(|m| m.path)
+            .collect::<Vec<_>>();
+
         // static definitions
         let mut q = quote! {
             type PrimaryKey = #pk_type;
@@ -38,10 +73,24 @@ impl Imp<Entity> for EntityKindTrait {
             const PRIMARY_KEY: &'static str = #pk_field;
             const FIELDS: &'static [&'static str]  = &[ #( Self::#field_refs ),* ];
             const INDEXES: &'static [&'static ::mimic::schema::node::Index]  = &[#(&#indexes),*];
+            const RELATIONS: &'static [&'static ::mimic::schema::node::Field]  = &[#(&#relations),*];
+            const SOFT_DELETE: bool = #soft_delete;
+            const TTL_FIELD: Option<&'static str> = #ttl_field;
+            const HISTORY_LIMIT: Option<u32> = #history_limit;
+            const SCHEMA_VERSION: u32 = #version;
+            const MIGRATIONS: &'static [::mimic::db::store::RowMigration] = &[#(#migrations),*];
+            const CODEC: ::mimic::db::store::RowCodec = #codec;
+            const RETIRED_FIELDS: &'static [&'static str] = &[#(#retired),*];
         };
 
         // impls
         q.extend(key(node));
+        if node.soft_delete {
+            q.extend(deleted_at());
+        }
+        if let Some(ttl) = &node.ttl {
+            q.extend(expires_at(node, ttl));
+        }
 
         let tokens = Implementor::new(&node.def, TraitKind::EntityKind)
             .set_tokens(q)
@@ -65,3 +114,37 @@ fn key(node: &Entity) -> TokenStream {
         }
     }
 }
+
+// deleted_at
+// soft delete entities expose the system field so executors can stamp it
+fn deleted_at() -> TokenStream {
+    quote! {
+        fn deleted_at(&self) -> Option<::mimic::types::Timestamp> {
+            self.deleted_at
+        }
+
+        fn set_deleted_at(&mut self, deleted_at: Option<::mimic::types::Timestamp>) {
+            self.deleted_at = deleted_at;
+        }
+    }
+}
+
+// expires_at
+fn expires_at(node: &Entity, ttl: &Ttl) -> TokenStream {
+    let field = &ttl.field;
+    let is_opt = node
+        .fields
+        .get(field)
+        .is_some_and(|f| f.value.cardinality() == Cardinality::Opt);
+    let value = if is_opt {
+        quote!(self.#field)
+    } else {
+        quote!(Some(self.#field))
+    };
+
+    quote! {
+        fn expires_at(&self) -> Option<::mimic::types::Timestamp> {
+            #value
+        }
+    }
+}
@@ -45,6 +45,10 @@ impl Imp<Enum> for FieldValueTrait {
 
                 Value::Enum(ev)
             }
+
+            fn family() -> ::mimic::core::value::ValueFamily {
+                ::mimic::core::value::ValueFamily::Enum
+            }
         };
 
         let tokens = Implementor::new(node.def(), TraitKind::FieldValue)
@@ -61,6 +65,7 @@ impl Imp<Enum> for FieldValueTrait {
 
 impl Imp<List> for FieldValueTrait {
     fn strategy(node: &List) -> Option<TraitStrategy> {
+        let item = node.item.type_expr();
         let q = quote! {
             fn to_value(&self) -> ::mimic::core::value::Value {
                 ::mimic::core::value::Value::List(
@@ -69,6 +74,44 @@ impl Imp<List> for FieldValueTrait {
                         .collect()
                 )
             }
+
+            fn get_path(&self, path: &[&str]) -> Option<::mimic::core::value::Value> {
+                ::mimic::core::value::get_list_path(self.iter(), path)
+            }
+
+            fn path_family(path: &[&str]) -> Option<::mimic::core::value::ValueFamily> {
+                ::mimic::core::value::list_path_family::<#item>(path)
+            }
+        };
+
+        let tokens = Implementor::new(node.def(), TraitKind::FieldValue)
+            .set_tokens(q)
+            .to_token_stream();
+
+        Some(TraitStrategy::from_impl(tokens))
+    }
+}
+
+///
+/// Map
+/// only maps of single values can be followed into
+///
+
+impl Imp<Map> for FieldValueTrait {
+    fn strategy(node: &Map) -> Option<TraitStrategy> {
+        if node.value.cardinality() != Cardinality::One {
+            return None;
+        }
+
+        let value = node.value.item.type_expr();
+        let q = quote! {
+            fn get_path(&self, path: &[&str]) -> Option<::mimic::core::value::Value> {
+                ::mimic::core::value::get_map_path(self.iter(), path)
+            }
+
+            fn path_family(path: &[&str]) -> Option<::mimic::core::value::ValueFamily> {
+                ::mimic::core::value::map_path_family::<#value>(path)
+            }
         };
 
         let tokens = Implementor::new(node.def(), TraitKind::FieldValue)
@@ -85,10 +128,19 @@ impl Imp<List> for FieldValueTrait {
 
 impl Imp<Newtype> for FieldValueTrait {
     fn strategy(node: &Newtype) -> Option<TraitStrategy> {
+        let item = node.item.type_expr();
         let q = quote! {
             fn to_value(&self) -> ::mimic::core::value::Value {
                 self.0.to_value()
             }
+
+            fn get_path(&self, path: &[&str]) -> Option<::mimic::core::value::Value> {
+                ::mimic::core::traits::FieldValue::get_path(&self.0, path)
+            }
+
+            fn path_family(path: &[&str]) -> Option<::mimic::core::value::ValueFamily> {
+                <#item as ::mimic::core::traits::FieldValue>::path_family(path)
+            }
         };
 
         let tokens = Implementor::new(node.def(), TraitKind::FieldValue)
@@ -99,8 +151,39 @@ impl Imp<Newtype> for FieldValueTrait {
     }
 }
 
+///
+/// Record
+/// follows paths into its fields, the record itself has no `Value`
+///
+
+impl Imp<Record> for FieldValueTrait {
+    fn strategy(node: &Record) -> Option<TraitStrategy> {
+        let get_arms = node.fields.iter().map(|field| {
+            let name = field.ident.to_string();
+            let ident = &field.ident;
+            let get = get_path_expr(&field.value, &quote!(self.#ident));
+
+            quote!(#name => #get,)
+        });
+        let family_arms = node.fields.iter().map(|field| {
+            let name = field.ident.to_string();
+            let family = path_family_expr(&field.value);
+
+            quote!(#name => #family,)
+        });
+
+        let q = paths_tokens(get_arms, family_arms);
+        let tokens = Implementor::new(node.def(), TraitKind::FieldValue)
+            .set_tokens(q)
+            .to_token_stream();
+
+        Some(TraitStrategy::from_impl(tokens))
+    }
+}
+
 ///
 /// Set
+/// no paths, a set has no stable order to index into
 ///
 
 impl Imp<Set> for FieldValueTrait {
@@ -113,6 +196,10 @@ impl Imp<Set> for FieldValueTrait {
                         .collect()
                 )
             }
+
+            fn family() -> ::mimic::core::value::ValueFamily {
+                ::mimic::core::value::ValueFamily::Collection
+            }
         };
 
         let tokens = Implementor::new(node.def(), TraitKind::FieldValue)
@@ -122,3 +209,95 @@ impl Imp<Set> for FieldValueTrait {
         Some(TraitStrategy::from_impl(tokens))
     }
 }
+
+///
+/// Tuple
+/// follows paths into its values by position
+///
+
+impl Imp<Tuple> for FieldValueTrait {
+    fn strategy(node: &Tuple) -> Option<TraitStrategy> {
+        let get_arms = node.values.iter().enumerate().map(|(i, value)| {
+            let name = i.to_string();
+            let index = syn::Index::from(i);
+            let get = get_path_expr(value, &quote!(self.#index));
+
+            quote!(#name => #get,)
+        });
+        let family_arms = node.values.iter().enumerate().map(|(i, value)| {
+            let name = i.to_string();
+            let family = path_family_expr(value);
+
+            quote!(#name => #family,)
+        });
+
+        let q = paths_tokens(get_arms, family_arms);
+        let tokens = Implementor::new(node.def(), TraitKind::FieldValue)
+            .set_tokens(q)
+            .to_token_stream();
+
+        Some(TraitStrategy::from_impl(tokens))
+    }
+}
+
+// paths_tokens
+// get_path and path_family matching the first segment against named parts
+fn paths_tokens(
+    get_arms: impl Iterator<Item = TokenStream>,
+    family_arms: impl Iterator<Item = TokenStream>,
+) -> TokenStream {
+    quote! {
+        fn get_path(&self, path: &[&str]) -> Option<::mimic::core::value::Value> {
+            let Some((part, rest)) = path.split_first() else {
+                return Some(::mimic::core::traits::FieldValue::to_value(self));
+            };
+
+            match *part {
+                #(#get_arms)*
+                _ => None,
+            }
+        }
+
+        fn path_family(path: &[&str]) -> Option<::mimic::core::value::ValueFamily> {
+            let Some((part, rest)) = path.split_first() else {
+                return Some(::mimic::core::value::ValueFamily::Unsupported);
+            };
+
+            match *part {
+                #(#family_arms)*
+                _ => None,
+            }
+        }
+    }
+}
+
+// get_path_expr
+// follows `rest` into the value at `access`
+pub fn get_path_expr(value: &Value, access: &TokenStream) -> TokenStream {
+    match value.cardinality() {
+        Cardinality::One => quote! {
+            ::mimic::core::traits::FieldValue::get_path(&#access, rest)
+        },
+        Cardinality::Opt => quote! {
+            ::mimic::core::value::get_opt_path(#access.as_ref(), rest)
+        },
+        Cardinality::Many => quote! {
+            ::mimic::core::value::get_list_path(&#access, rest)
+        },
+    }
+}
+
+// path_family_expr
+// the family `rest` resolves to, an optional value has its item's family
+pub fn path_family_expr(value: &Value) -> TokenStream {
+    let item = value.item.type_expr();
+
+    match value.cardinality() {
+        Cardinality::One | Cardinality::Opt => quote! {
+            <#item as ::mimic::core::traits::FieldValue>::path_family(rest)
+        },
+        Cardinality::Many => quote! {
+            ::mimic::core::value::list_path_family::<#item>(rest)
+        },
+    }
+}
@@ -1,7 +1,11 @@
-use crate::prelude::*;
+use crate::{
+    imp::field_value::{get_path_expr, path_family_expr},
+    prelude::*,
+};
 
 ///
 /// FieldValuesTrait
+/// a field name may continue as a dotted path into the field's value
 ///
 
 pub struct FieldValuesTrait {}
@@ -12,47 +16,37 @@ pub struct FieldValuesTrait {}
 
 impl Imp<Entity> for FieldValuesTrait {
     fn strategy(node: &Entity) -> Option<TraitStrategy> {
-        let match_arms = node
-            .fields
-            .iter()
-            .map(|field| {
-                let field_ident = &field.ident;
-                let field_const = &field.const_ident();
+        let get_arms = node.fields.iter().map(|field| {
+            let field_ident = &field.ident;
+            let field_const = &field.const_ident();
+            let get = get_path_expr(&field.value, &quote!(self.#field_ident));
 
-                match field.value.cardinality() {
-                    Cardinality::One => Some(quote! {
-                        Self::#field_const => Some(self.#field_ident.to_value()),
-                    }),
+            quote!(Self::#field_const => #get,)
+        });
+        let family_arms = node.fields.iter().map(|field| {
+            let field_const = &field.const_ident();
+            let family = path_family_expr(&field.value);
 
-                    Cardinality::Opt => Some(quote! {
-                        Self::#field_const => {
-                            match self.#field_ident.as_ref() {
-                                Some(inner) => Some(FieldValue::to_value(inner)),
-                                None => Some(Value::None),
-                            }
-                        }
-                    }),
+            quote!(Self::#field_const => #family,)
+        });
 
-                    Cardinality::Many => Some(quote! {
-                        Self::#field_const => {
-                            let list = self.#field_ident
-                                .iter()
-                                .map(FieldValue::to_value)
-                                .collect::<Vec<_>>();
+        let q = quote! {
+            fn get_value(&self, field: &str) -> Option<::mimic::core::Value> {
+                let path = ::mimic::core::value::split_path(field);
+                let (field, rest) = path.split_first()?;
 
-                            Some(Value::List(list))
-                        }
-                    }),
+                match *field {
+                    #(#get_arms)*
+                    _ => None,
                 }
-            })
-            .collect::<Vec<_>>();
+            }
 
-        let q = quote! {
-            fn get_value(&self, field: &str) -> Option<::mimic::core::Value> {
-                use ::mimic::core::{traits::FieldValue, Value};
+            fn path_family(field: &str) -> Option<::mimic::core::value::ValueFamily> {
+                let path = ::mimic::core::value::split_path(field);
+                let (field, rest) = path.split_first()?;
 
-                match field {
-                    #(#match_arms)*
+                match *field {
+                    #(#family_arms)*
                     _ => None,
                 }
             }
@@ -26,13 +26,13 @@ mod prelude {
     pub use crate::{
         r#gen::{Imp, Implementor},
         helper::{
-            as_tokens, quote_one, quote_option, quote_slice, split_idents, to_path, to_str_lit,
+            as_tokens, quote_one, quote_option, quote_slice, split_list, to_path, to_str_lit,
         },
         node::*,
         trait_kind::{TraitBuilder, TraitKind, TraitSet},
         types::TraitStrategy,
     };
-    pub use mimic_schema::types::{Cardinality, Primitive, StoreType};
+    pub use mimic_schema::types::{Cardinality, Primitive, RowFormat, StoreType};
 
     // proc-macro essentials
     pub use darling::FromMeta;
@@ -0,0 +1,81 @@
+use crate::prelude::*;
+use darling::{Error as DarlingError, ast::NestedMeta};
+
+///
+/// Access
+///
+
+#[derive(Clone, Debug, Default, FromMeta)]
+pub struct Access {
+    #[darling(default)]
+    pub read: Option<AccessRule>,
+
+    #[darling(default)]
+    pub write: Option<AccessRule>,
+
+    #[darling(default)]
+    pub admin: Option<AccessRule>,
+}
+
+impl HasSchemaPart for Access {
+    fn schema_part(&self) -> TokenStream {
+        let read = quote_option(self.read.as_ref(), AccessRule::schema_part);
+        let write = quote_option(self.write.as_ref(), AccessRule::schema_part);
+        let admin = quote_option(self.admin.as_ref(), AccessRule::schema_part);
+
+        quote! {
+            ::mimic::schema::node::Access {
+                read: #read,
+                write: #write,
+                admin: #admin,
+            }
+        }
+    }
+}
+
+///
+/// AccessRule
+/// `read = "public"`, `read = "controllers"` or `read(guard = "path::to::fn")`
+///
+
+#[derive(Clone, Debug)]
+pub enum AccessRule {
+    Public,
+    Controllers,
+    Guard(Path),
+}
+
+#[derive(FromMeta)]
+struct GuardRule {
+    guard: Path,
+}
+
+impl FromMeta for AccessRule {
+    fn from_string(s: &str) -> Result<Self, DarlingError> {
+        match s {
+            "public" => Ok(Self::Public),
+            "controllers" => Ok(Self::Controllers),
+            _ => Err(DarlingError::unknown_value(s)),
+        }
+    }
+
+    fn from_list(items: &[NestedMeta]) -> Result<Self, DarlingError> {
+        let rule = GuardRule::from_list(items)?;
+
+        Ok(Self::Guard(rule.guard))
+    }
+}
+
+impl HasSchemaPart for AccessRule {
+    fn schema_part(&self) -> TokenStream {
+        match self {
+            Self::Public => quote!(::mimic::schema::node::AccessRule::Public),
+            Self::Controllers => quote!(::mimic::schema::node::AccessRule::Controllers),
+            Self::Guard(path) => {
+                let path = path.to_token_stream().to_string().replace(' ', "");
+
+                quote!(::mimic::schema::node::AccessRule::Guard(#path))
+            }
+        }
+    }
+}
@@ -13,6 +13,12 @@ pub struct Canister {
     // inclusive range of ic memories
     pub memory_min: u8,
     pub memory_max: u8,
+
+    #[darling(default)]
+    pub access: Access,
+
+    #[darling(multiple, rename = "skip_endpoint")]
+    pub skip_endpoints: Vec<LitStr>,
 }
 
 impl HasDef for Canister {
@@ -32,12 +38,16 @@ impl HasSchemaPart for Canister {
         let def = self.def.schema_part();
         let memory_min = self.memory_min;
         let memory_max = self.memory_max;
+        let access = self.access.schema_part();
+        let skip_endpoints = &self.skip_endpoints;
 
         quote! {
             ::mimic::schema::node::Canister{
                 def: #def,
                 memory_min: #memory_min,
                 memory_max: #memory_max,
+                access: #access,
+                skip_endpoints: &[#(#skip_endpoints),*],
             }
         }
     }
@@ -0,0 +1,34 @@
+use crate::prelude::*;
+
+///
+/// Codec
+///
+
+#[derive(Clone, Debug, FromMeta)]
+pub struct Codec {
+    #[darling(default)]
+    pub format: RowFormat,
+
+    #[darling(default)]
+    pub compress: bool,
+
+    /// Fields removed or renamed since compact rows were first written
+    #[darling(default, map = "split_list")]
+    pub retired: Vec<String>,
+}
+
+impl HasSchemaPart for Codec {
+    fn schema_part(&self) -> TokenStream {
+        let format = &self.format;
+        let compress = self.compress;
+        let retired = quote_slice(&self.retired, |field| quote!(#field));
+
+        quote! {
+            ::mimic::schema::node::Codec {
+                format: #format,
+                compress: #compress,
+                retired: #retired,
+            }
+        }
+    }
+}
@@ -5,6 +5,7 @@ use crate::prelude::*;
 ///
 
 #[derive(Debug, FromMeta)]
+#[darling(and_then = "Entity::add_soft_delete")]
 pub struct Entity {
     #[darling(default, skip)]
     pub def: Def,
@@ -20,6 +21,27 @@ pub struct Entity {
     #[darling(default, map = "Entity::add_metadata")]
     pub fields: FieldList,
 
+    #[darling(default)]
+    pub soft_delete: bool,
+
+    #[darling(default)]
+    pub ttl: Option<Ttl>,
+
+    #[darling(default)]
+    pub history: Option<History>,
+
+    #[darling(default = "Entity::default_version")]
+    pub version: u32,
+
+    #[darling(multiple, rename = "migration")]
+    pub migrations: Vec<Migration>,
+
+    #[darling(default)]
+    pub codec: Option<Codec>,
+
+    #[darling(default)]
+    pub access: Option<Access>,
+
     #[darling(default)]
     pub ty: Type,
 
@@ -35,12 +57,50 @@ impl Entity {
             .filter(|f| f.ident != self.primary_key && !f.is_system)
     }
 
+    /// Declared indexes plus one generated index per relation field that sets `rel_index`.
+    pub fn all_indexes(&self) -> Vec<Index> {
+        let mut indexes = self.indexes.clone();
+
+        for field in &self.fields {
+            if let Some(store) = &field.value.item.relation_index {
+                indexes.push(Index {
+                    store: store.clone(),
+                    fields: vec![field.ident.to_string()],
+                    unique: false,
+                });
+            }
+        }
+
+        indexes
+    }
+
+    const fn default_version() -> u32 {
+        1
+    }
+
+    /// Migrations ordered by the version they upgrade from.
+    pub fn sorted_migrations(&self) -> Vec<Migration> {
+        let mut migrations = self.migrations.clone();
+        migrations.sort_by_key(|m| m.from);
+
+        migrations
+    }
+
     fn add_metadata(mut fields: FieldList) -> FieldList {
         fields.push(Field::created_at());
         fields.push(Field::updated_at());
 
         fields
     }
+
+    #[allow(clippy::unnecessary_wraps)]
+    fn add_soft_delete(mut self) -> darling::Result<Self> {
+        if self.soft_delete {
+            self.fields.push(Field::deleted_at());
+        }
+
+        Ok(self)
+    }
 }
 
 //
@@ -66,8 +126,15 @@ impl HasSchemaPart for Entity {
         let def = &self.def.schema_part();
         let store = quote_one(&self.store, to_path);
         let primary_key = quote_one(&self.primary_key, to_str_lit);
-        let indexes = quote_slice(&self.indexes, Index::schema_part);
+        let indexes = quote_slice(&self.all_indexes(), Index::schema_part);
         let fields = &self.fields.schema_part();
+        let soft_delete = self.soft_delete;
+        let ttl = quote_option(self.ttl.as_ref(), Ttl::schema_part);
+        let history = quote_option(self.history.as_ref(), History::schema_part);
+        let version = self.version;
+        let migrations = quote_slice(&self.sorted_migrations(), Migration::schema_part);
+        let codec = quote_option(self.codec.as_ref(), Codec::schema_part);
+        let access = quote_option(self.access.as_ref(), Access::schema_part);
         let ty = &self.ty.schema_part();
 
         quote! {
@@ -77,6 +144,13 @@ impl HasSchemaPart for Entity {
                 primary_key: #primary_key,
                 indexes: #indexes,
                 fields: #fields,
+                soft_delete: #soft_delete,
+                ttl: #ttl,
+                history: #history,
+                version: #version,
+                migrations: #migrations,
+                codec: #codec,
+                access: #access,
                 ty: #ty,
             }
         }
@@ -85,7 +159,10 @@ impl HasSchemaPart for Entity {
 
 impl HasTraits for Entity {
     fn traits(&self) -> Vec<TraitKind> {
-        let mut traits = self.traits.with_type_traits().build();
+        // hooks go through the builder so entities can remove them and write their own
+        let mut builder = self.traits.with_type_traits();
+        builder.add.push(TraitKind::EntityHooks);
+        let mut traits = builder.build();
 
         traits.extend(vec![
             TraitKind::Inherent,
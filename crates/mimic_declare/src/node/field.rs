@@ -134,6 +134,19 @@ impl Field {
             is_system: true,
         }
     }
+
+    pub fn deleted_at() -> Self {
+        Self {
+            ident: format_ident!("deleted_at"),
+            value: Value {
+                opt: true,
+                item: Item::deleted_at(),
+                ..Default::default()
+            },
+            default: None,
+            is_system: true,
+        }
+    }
 }
 
 impl HasSchemaPart for Field {
@@ -141,12 +154,14 @@ impl HasSchemaPart for Field {
         let ident = quote_one(&self.ident, to_str_lit);
         let value = self.value.schema_part();
         let default = quote_option(self.default.as_ref(), Arg::schema_part);
+        let is_system = self.is_system;
 
         quote! {
             ::mimic::schema::node::Field {
                 ident: #ident,
                 value: #value,
                 default: #default,
+                is_system: #is_system,
             }
         }
     }
@@ -0,0 +1,22 @@
+use crate::prelude::*;
+
+///
+/// History
+///
+
+#[derive(Clone, Debug, FromMeta)]
+pub struct History {
+    pub limit: u32,
+}
+
+impl HasSchemaPart for History {
+    fn schema_part(&self) -> TokenStream {
+        let limit = self.limit;
+
+        quote! {
+            ::mimic::schema::node::History {
+                limit: #limit,
+            }
+        }
+    }
+}
@@ -4,12 +4,13 @@ use crate::prelude::*;
 /// Index
 ///
 
-#[derive(Debug, FromMeta)]
+#[derive(Clone, Debug, FromMeta)]
 pub struct Index {
     pub store: Path,
 
-    #[darling(default, map = "split_idents")]
-    pub fields: Vec<Ident>,
+    /// Field names, or dotted paths into fields
+    #[darling(default, map = "split_list")]
+    pub fields: Vec<String>,
 
     #[darling(default)]
     pub unique: bool,
@@ -18,7 +19,7 @@ pub struct Index {
 impl HasSchemaPart for Index {
     fn schema_part(&self) -> TokenStream {
         let store = quote_one(&self.store, to_path);
-        let fields = quote_slice(&self.fields, to_str_lit);
+        let fields = quote_slice(&self.fields, |field| quote!(#field));
         let unique = &self.unique;
 
         quote! {
@@ -15,6 +15,9 @@ pub struct Item {
     #[darling(default, rename = "rel")]
     pub relation: Option<Path>,
 
+    #[darling(default, rename = "rel_index")]
+    pub relation_index: Option<Path>,
+
     #[darling(multiple, rename = "sanitizer")]
     pub sanitizers: Vec<TypeSanitizer>,
 
@@ -59,6 +62,13 @@ impl Item {
         }
     }
 
+    pub fn deleted_at() -> Self {
+        Self {
+            primitive: Some(Primitive::Timestamp),
+            ..Default::default()
+        }
+    }
+
     pub const fn is_relation(&self) -> bool {
         self.relation.is_some()
     }
@@ -72,6 +82,7 @@ impl HasSchemaPart for Item {
     fn schema_part(&self) -> TokenStream {
         let target = self.target().schema_part();
         let relation = quote_option(self.relation.as_ref(), to_path);
+        let relation_index = quote_option(self.relation_index.as_ref(), to_path);
         let validators = quote_slice(&self.validators, TypeValidator::schema_part);
         let sanitizers = quote_slice(&self.sanitizers, TypeSanitizer::schema_part);
         let indirect = self.indirect;
@@ -80,6 +91,7 @@ impl HasSchemaPart for Item {
             ::mimic::schema::node::Item{
                 target: #target,
                 relation: #relation,
+                relation_index: #relation_index,
                 validators: #validators,
                 sanitizers: #sanitizers,
                 indirect: #indirect,
@@ -0,0 +1,25 @@
+use crate::prelude::*;
+
+///
+/// Migration
+///
+
+#[derive(Clone, Debug, FromMeta)]
+pub struct Migration {
+    pub from: u32,
+    pub path: Path,
+}
+
+impl HasSchemaPart for Migration {
+    fn schema_part(&self) -> TokenStream {
+        let from = self.from;
+        let path = self.path.to_token_stream().to_string().replace(' ', "");
+
+        quote! {
+            ::mimic::schema::node::Migration {
+                from: #from,
+                path: #path,
+            }
+        }
+    }
+}
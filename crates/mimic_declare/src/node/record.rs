@@ -59,6 +59,7 @@ impl HasTraits for Record {
 
         match t {
             TraitKind::Default => DefaultTrait::strategy(self),
+            TraitKind::FieldValue => FieldValueTrait::strategy(self),
             TraitKind::Filterable => FilterableTrait::strategy(self),
             TraitKind::SanitizeAuto => SanitizeAutoTrait::strategy(self),
             TraitKind::UpdateView => UpdateViewTrait::strategy(self),
@@ -187,6 +187,7 @@ pub trait HasTraits: HasType {
             // Marker traits — empty impls
             // ─────────────────────────────
             TraitKind::CanisterKind
+            | TraitKind::EntityHooks
             | TraitKind::FieldValue
             | TraitKind::SanitizeAuto
             | TraitKind::SanitizeCustom
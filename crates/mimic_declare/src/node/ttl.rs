@@ -0,0 +1,22 @@
+use crate::prelude::*;
+
+///
+/// Ttl
+///
+
+#[derive(Clone, Debug, FromMeta)]
+pub struct Ttl {
+    pub field: Ident,
+}
+
+impl HasSchemaPart for Ttl {
+    fn schema_part(&self) -> TokenStream {
+        let field = quote_one(&self.field, to_str_lit);
+
+        quote! {
+            ::mimic::schema::node::Ttl {
+                field: #field,
+            }
+        }
+    }
+}
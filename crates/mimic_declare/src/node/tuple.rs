@@ -58,6 +58,7 @@ impl HasTraits for Tuple {
         use crate::imp::*;
 
         match t {
+            TraitKind::FieldValue => FieldValueTrait::strategy(self),
             TraitKind::Filterable => FilterableTrait::strategy(self),
             TraitKind::UpdateView => UpdateViewTrait::strategy(self),
             TraitKind::View => ViewTrait::strategy(self),
@@ -55,6 +55,7 @@ pub enum TraitKind {
     // kind
     // traits for the implementation of specific Schema Nodes
     CanisterKind,
+    EntityHooks,
     EntityKind,
     IndexKind,
     PrimitiveKind,
@@ -270,7 +271,7 @@ impl TraitBuilder {
 
         // self.remove
         for tr in self.remove.iter() {
-            assert!(set.remove(tr), "cannot remove trait {tr} from {set:?}",);
+            assert!(set.remove(tr), "cannot remove trait {tr} from {set:?}");
         }
 
         set
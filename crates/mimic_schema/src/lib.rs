@@ -6,6 +6,14 @@ pub mod visit;
 use crate::{build::BuildError, node::NodeError};
 use thiserror::Error as ThisError;
 
+///
+/// CONSTANTS
+///
+
+/// Memory id of the schema record, reserved for mimic itself so it sits
+/// outside every canister's store range (255 is not a valid id).
+pub const SCHEMA_MEMORY_ID: u8 = 254;
+
 ///
 /// Prelude
 ///
@@ -14,7 +22,7 @@ pub mod prelude {
     pub(crate) use crate::build::{schema_read, validate::validate_ident};
     pub use crate::{
         node::*,
-        types::{Cardinality, Primitive, StoreType},
+        types::{Cardinality, Primitive, RowFormat, StoreType},
         visit::Visitor,
     };
     pub use candid::CandidType;
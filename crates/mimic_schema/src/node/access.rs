@@ -0,0 +1,40 @@
+use crate::prelude::*;
+
+///
+/// Access
+///
+/// Who may call a canister's generated endpoints. `read` covers loads,
+/// `write` covers saves, patches and deletes, and `admin` covers the
+/// snapshot, metrics, job and change log endpoints.
+///
+/// An entity's rules replace the canister's for that entity, and an unset
+/// rule falls back to the canister's, then to `Public` for read and write
+/// and `Controllers` for admin.
+///
+
+#[derive(CandidType, Clone, Debug, Default, Serialize)]
+pub struct Access {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub read: Option<AccessRule>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub write: Option<AccessRule>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub admin: Option<AccessRule>,
+}
+
+///
+/// AccessRule
+///
+/// Public      : anyone
+/// Controllers : only controllers of the canister
+/// Guard       : a `fn() -> Result<(), String>` that rejects the caller with an error
+///
+
+#[derive(CandidType, Clone, Copy, Debug, Serialize)]
+pub enum AccessRule {
+    Public,
+    Controllers,
+    Guard(&'static str),
+}
@@ -1,14 +1,20 @@
-use crate::prelude::*;
+use crate::{SCHEMA_MEMORY_ID, prelude::*};
 
 ///
 /// Canister
 ///
+/// Stores use memory ids from `memory_min` to `memory_max`, which must stay
+/// below the id mimic reserves for its schema record.
+/// `skip_endpoints` names generated endpoints to leave out.
+///
 
 #[derive(CandidType, Clone, Debug, Serialize)]
 pub struct Canister {
     pub def: Def,
     pub memory_min: u8,
     pub memory_max: u8,
+    pub access: Access,
+    pub skip_endpoints: &'static [&'static str],
 }
 
 impl MacroNode for Canister {
@@ -25,7 +31,17 @@ impl ValidateNode for Canister {
         // Check for duplicate memory IDs among stores for this canister
         let canister_path = self.def.path();
         let mut seen_ids = std::collections::HashSet::new();
+        let mut change_logs = 0;
+        let mut histories = 0;
+        let mut jobs = 0;
         for (_, store) in schema.filter_nodes::<Store>(|node| node.canister == canister_path) {
+            match store.ty {
+                StoreType::ChangeLog => change_logs += 1,
+                StoreType::History => histories += 1,
+                StoreType::Job => jobs += 1,
+                _ => {}
+            }
+
             let memory_id = store.memory_id;
             if !seen_ids.insert(memory_id) {
                 err!(
@@ -37,10 +53,46 @@ impl ValidateNode for Canister {
             }
         }
 
+        // a canister has a single change log
+        if change_logs > 1 {
+            err!(
+                errs,
+                "canister `{}` has {} change log stores, expected at most one",
+                canister_path,
+                change_logs
+            );
+        }
+
+        // and a single history store
+        if histories > 1 {
+            err!(
+                errs,
+                "canister `{}` has {} history stores, expected at most one",
+                canister_path,
+                histories
+            );
+        }
+
+        // and a single job store
+        if jobs > 1 {
+            err!(
+                errs,
+                "canister `{}` has {} job stores, expected at most one",
+                canister_path,
+                jobs
+            );
+        }
+
         // store
         if self.memory_min > self.memory_max {
             err!(errs, "memory_min must be equal to or less than memory_max");
         }
+        if self.memory_max >= SCHEMA_MEMORY_ID {
+            err!(
+                errs,
+                "memory_max must be below {SCHEMA_MEMORY_ID}, which is reserved for the schema record"
+            );
+        }
 
         errs.result()
     }
@@ -0,0 +1,54 @@
+use crate::prelude::*;
+use mimic_common::field_id::field_id;
+use std::collections::HashMap;
+
+///
+/// Codec
+///
+/// How an entity's rows are encoded: the serialization `format`, and whether
+/// rows are compressed when that makes them smaller. `retired` names fields
+/// that were removed or renamed, so older compact rows still decode.
+///
+
+#[derive(Clone, Debug, Serialize)]
+pub struct Codec {
+    pub format: RowFormat,
+    pub compress: bool,
+
+    #[serde(default, skip_serializing_if = "<[_]>::is_empty")]
+    pub retired: &'static [&'static str],
+}
+
+impl Codec {
+    /// Compact rows key fields by a hash of their name, which must not collide,
+    /// nor match the id a retired field left in older rows.
+    pub(crate) fn validate_for(&self, entity: &Entity, errs: &mut ErrorTree) {
+        if self.format != RowFormat::Compact && self.retired.is_empty() {
+            return;
+        }
+
+        let mut ids = HashMap::new();
+        for field in entity.fields.fields {
+            if let Some(other) = ids.insert(field_id(field.ident), field.ident) {
+                err!(
+                    errs,
+                    "fields '{other}' and '{}' have the same compact id, rename one",
+                    field.ident
+                );
+            }
+        }
+
+        for retired in self.retired {
+            if entity.fields.get(retired).is_some() {
+                err!(errs, "retired field '{retired}' is still a field");
+                continue;
+            }
+            if let Some(other) = ids.insert(field_id(retired), retired) {
+                err!(
+                    errs,
+                    "field '{other}' has the same compact id as retired field '{retired}', rename it"
+                );
+            }
+        }
+    }
+}
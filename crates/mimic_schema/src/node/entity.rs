@@ -1,5 +1,5 @@
 use crate::prelude::*;
-use std::{any::Any, collections::HashSet};
+use std::{any::Any, collections::HashSet, ops::Not};
 
 ///
 /// Entity
@@ -17,6 +17,27 @@ pub struct Entity {
     pub indexes: &'static [Index],
 
     pub fields: FieldList,
+
+    #[serde(default, skip_serializing_if = "Not::not")]
+    pub soft_delete: bool,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ttl: Option<Ttl>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub history: Option<History>,
+
+    pub version: u32,
+
+    #[serde(default, skip_serializing_if = "<[_]>::is_empty")]
+    pub migrations: &'static [Migration],
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub codec: Option<Codec>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub access: Option<Access>,
+
     pub ty: Type,
 }
 
@@ -67,10 +88,32 @@ impl ValidateNode for Entity {
             Ok(store) if !matches!(store.ty, StoreType::Data) => {
                 err!(errs, "store is not type Data");
             }
-            Ok(_) => {}
+            Ok(store) => {
+                if let Some(history) = &self.history {
+                    history.validate_for(&schema, store, &mut errs);
+                }
+            }
             Err(e) => errs.add(e),
         }
 
+        // ttl
+        if let Some(ttl) = &self.ttl {
+            ttl.validate_for(self, &mut errs);
+        }
+
+        // admin endpoints aren't per entity
+        if self.access.as_ref().is_some_and(|a| a.admin.is_some()) {
+            err!(errs, "entity access can only set read and write");
+        }
+
+        // migrations
+        Migration::validate_all(self.migrations, self.version, &mut errs);
+
+        // codec
+        if let Some(codec) = &self.codec {
+            codec.validate_for(self, &mut errs);
+        }
+
         // Load and validate index references
         let mut resolved_indexes = Vec::new();
 
@@ -105,12 +148,12 @@ impl ValidateNode for Entity {
                 if !seen.insert(*field) {
                     err!(errs, "index contains duplicate field '{field}'");
                 }
-                if let Some(field) = self.fields.get(field) {
-                    if field.value.cardinality == Cardinality::Many {
+                match self.fields.resolve_path(&schema, field) {
+                    Ok(Cardinality::Many) => {
                         err!(errs, "cannot add an index field with many cardinality");
                     }
-                } else {
-                    err!(errs, "index field '{field}' not found");
+                    Ok(_) => {}
+                    Err(e) => err!(errs, "index field '{field}': {e}"),
                 }
             }
             resolved_indexes.push(index);
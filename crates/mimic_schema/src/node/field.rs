@@ -1,4 +1,5 @@
 use crate::prelude::*;
+use std::ops::Not;
 
 ///
 /// FieldList
@@ -17,6 +18,90 @@ impl FieldList {
     }
 }
 
+impl FieldList {
+    /// Follows a dotted path (`stats.level`, `tags.0`) from one of these fields
+    /// through records, tuples, newtypes, lists and maps, returning the
+    /// cardinality of what it ends on.
+    pub fn resolve_path(&self, schema: &Schema, path: &str) -> Result<Cardinality, String> {
+        let segments = path.split('.').collect::<Vec<_>>();
+        let field = self
+            .get(segments[0])
+            .ok_or_else(|| format!("field '{}' not found", segments[0]))?;
+
+        resolve_value(schema, &field.value, &segments[1..])
+    }
+}
+
+// resolve_value
+fn resolve_value(schema: &Schema, value: &Value, path: &[&str]) -> Result<Cardinality, String> {
+    if value.cardinality != Cardinality::Many {
+        let cardinality = resolve_item(schema, &value.item, path)?;
+
+        return Ok(match cardinality {
+            Cardinality::One => value.cardinality,
+            other => other,
+        });
+    }
+
+    match path.split_first() {
+        None => Ok(Cardinality::Many),
+        Some((index, rest)) if is_index(index) => resolve_item(schema, &value.item, rest),
+        Some((segment, _)) => Err(format!("expected a list index, found '{segment}'")),
+    }
+}
+
+// resolve_item
+fn resolve_item(schema: &Schema, item: &Item, path: &[&str]) -> Result<Cardinality, String> {
+    let Some((segment, rest)) = path.split_first() else {
+        return Ok(match item.target {
+            ItemTarget::Is(target) => match schema.get_node(target) {
+                Some(SchemaNode::List(_) | SchemaNode::Set(_) | SchemaNode::Map(_)) => {
+                    Cardinality::Many
+                }
+                Some(SchemaNode::Newtype(node)) => resolve_item(schema, &node.item, &[])?,
+                _ => Cardinality::One,
+            },
+            ItemTarget::Primitive(_) => Cardinality::One,
+        });
+    };
+
+    let ItemTarget::Is(target) = item.target else {
+        return Err(format!("'{segment}' follows a primitive"));
+    };
+
+    match schema.get_node(target) {
+        Some(SchemaNode::Record(node)) => {
+            let field = node
+                .fields
+                .get(segment)
+                .ok_or_else(|| format!("field '{segment}' not found in {target}"))?;
+
+            resolve_value(schema, &field.value, rest)
+        }
+        Some(SchemaNode::Tuple(node)) => {
+            let value = segment
+                .parse::<usize>()
+                .ok()
+                .filter(|_| is_index(segment))
+                .and_then(|i| node.values.get(i))
+                .ok_or_else(|| format!("{target} has no value '{segment}'"))?;
+
+            resolve_value(schema, value, rest)
+        }
+        Some(SchemaNode::Newtype(node)) => resolve_item(schema, &node.item, path),
+        Some(SchemaNode::List(node)) if is_index(segment) => resolve_item(schema, &node.item, rest),
+        Some(SchemaNode::Map(node)) if node.value.cardinality == Cardinality::One => {
+            resolve_value(schema, &node.value, rest)
+        }
+        _ => Err(format!("cannot follow '{segment}' into {target}")),
+    }
+}
+
+// is_index
+fn is_index(segment: &str) -> bool {
+    !segment.is_empty() && segment.bytes().all(|b| b.is_ascii_digit())
+}
+
 impl ValidateNode for FieldList {}
 
 impl VisitableNode for FieldList {
@@ -38,6 +123,10 @@ pub struct Field {
 
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub default: Option<Arg>,
+
+    // created_at, updated_at and deleted_at, which Create and Update views leave out
+    #[serde(default, skip_serializing_if = "Not::not")]
+    pub is_system: bool,
 }
 
 impl ValidateNode for Field {
@@ -0,0 +1,36 @@
+use crate::prelude::*;
+
+///
+/// History
+///
+/// Keeps up to `limit` prior versions of each row in the canister's history store.
+///
+
+#[derive(Clone, Debug, Serialize)]
+pub struct History {
+    pub limit: u32,
+}
+
+impl History {
+    /// Old versions need a history store on the same canister as the entity's `store`.
+    pub(crate) fn validate_for(&self, schema: &Schema, store: &Store, errs: &mut ErrorTree) {
+        if self.limit == 0 {
+            err!(errs, "history limit must be greater than 0");
+        }
+
+        let has_history_store = schema
+            .filter_nodes::<Store>(|node| {
+                node.canister == store.canister && matches!(node.ty, StoreType::History)
+            })
+            .next()
+            .is_some();
+
+        if !has_history_store {
+            err!(
+                errs,
+                "history is enabled but canister `{}` has no history store",
+                store.canister
+            );
+        }
+    }
+}
@@ -12,6 +12,9 @@ pub struct Item {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub relation: Option<&'static str>,
 
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub relation_index: Option<&'static str>,
+
     #[serde(default, skip_serializing_if = "<[_]>::is_empty")]
     pub validators: &'static [TypeValidator],
 
@@ -74,6 +77,21 @@ impl ValidateNode for Item {
             }
         }
 
+        // relation index
+        if let Some(store) = &self.relation_index {
+            if self.relation.is_none() {
+                err!(errs, "relation index requires a relation");
+            }
+
+            match schema.cast_node::<Store>(store) {
+                Ok(store) if !matches!(store.ty, StoreType::Index) => {
+                    err!(errs, "relation index store is not type Index");
+                }
+                Ok(_) => {}
+                Err(e) => errs.add(e),
+            }
+        }
+
         errs.result()
     }
 }
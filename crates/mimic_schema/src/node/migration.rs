@@ -0,0 +1,41 @@
+use crate::prelude::*;
+
+///
+/// Migration
+///
+/// Upgrades a stored row of an entity from schema version `from` to `from + 1`.
+/// `path` is the migration function, `fn(&[u8]) -> Result<Vec<u8>, Error>`.
+///
+
+#[derive(Clone, Debug, Serialize)]
+pub struct Migration {
+    pub from: u32,
+    pub path: &'static str,
+}
+
+impl Migration {
+    /// An entity at `version` needs exactly one migration from each earlier version.
+    pub(crate) fn validate_all(migrations: &[Self], version: u32, errs: &mut ErrorTree) {
+        if version == 0 {
+            err!(errs, "entity version must be at least 1");
+        }
+
+        for from in 1..version {
+            match migrations.iter().filter(|m| m.from == from).count() {
+                0 => err!(errs, "missing migration from version {from}"),
+                1 => {}
+                n => err!(errs, "{n} migrations from version {from}, expected one"),
+            }
+        }
+
+        for migration in migrations {
+            if migration.from == 0 || migration.from >= version {
+                err!(
+                    errs,
+                    "migration from version {} is outside 1..{version}",
+                    migration.from
+                );
+            }
+        }
+    }
+}
@@ -1,40 +1,50 @@
+mod access;
 mod arg;
 mod canister;
+mod codec;
 mod def;
 mod entity;
 mod r#enum;
 mod field;
+mod history;
 mod index;
 mod item;
 mod list;
 mod map;
+mod migration;
 mod newtype;
 mod record;
 mod sanitizer;
 mod schema;
 mod set;
 mod store;
+mod ttl;
 mod tuple;
 mod r#type;
 mod validator;
 mod value;
 
+pub use self::access::*;
 pub use self::arg::*;
 pub use self::canister::*;
+pub use self::codec::*;
 pub use self::def::*;
 pub use self::entity::*;
 pub use self::r#enum::*;
 pub use self::field::*;
+pub use self::history::*;
 pub use self::index::*;
 pub use self::item::*;
 pub use self::list::*;
 pub use self::map::*;
+pub use self::migration::*;
 pub use self::newtype::*;
 pub use self::record::*;
 pub use self::sanitizer::*;
 pub use self::schema::*;
 pub use self::set::*;
 pub use self::store::*;
+pub use self::ttl::*;
 pub use self::tuple::*;
 pub use self::r#type::*;
 pub use self::validator::*;
@@ -0,0 +1,36 @@
+use crate::prelude::*;
+
+///
+/// Ttl
+///
+/// Row expiry for an entity, driven by a timestamp field.
+///
+
+#[derive(Clone, Debug, Serialize)]
+pub struct Ttl {
+    pub field: &'static str,
+}
+
+impl Ttl {
+    /// The ttl field must be a single timestamp on `entity`.
+    pub(crate) fn validate_for(&self, entity: &Entity, errs: &mut ErrorTree) {
+        let Some(field) = entity.fields.get(self.field) else {
+            err!(errs, "ttl field '{}' not found", self.field);
+            return;
+        };
+
+        if field.value.cardinality == Cardinality::Many {
+            err!(
+                errs,
+                "ttl field '{}' cannot have many cardinality",
+                self.field
+            );
+        }
+        if !matches!(
+            field.value.item.target,
+            ItemTarget::Primitive(Primitive::Timestamp)
+        ) {
+            err!(errs, "ttl field '{}' must be a Timestamp", self.field);
+        }
+    }
+}
@@ -10,7 +10,18 @@ pub struct Value {
     pub item: Item,
 }
 
-impl ValidateNode for Value {}
+impl ValidateNode for Value {
+    fn validate(&self) -> Result<(), ErrorTree> {
+        let mut errs = ErrorTree::new();
+
+        // reverse lookups always scan many relations, so an index would only cost writes
+        if self.item.relation_index.is_some() && self.cardinality == Cardinality::Many {
+            err!(errs, "relation index is not supported on a many relation");
+        }
+
+        errs.result()
+    }
+}
 
 impl VisitableNode for Value {
     fn drive<V: Visitor>(&self, v: &mut V) {
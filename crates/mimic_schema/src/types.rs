@@ -213,14 +213,48 @@ impl ToTokens for Primitive {
     }
 }
 
+///
+/// RowFormat
+///
+/// How an entity's rows are serialized before any compression.
+/// `Compact` replaces top-level field names with a 16-bit hash of the name.
+///
+
+#[derive(
+    CandidType, Clone, Copy, Debug, Default, Deserialize, Display, Eq, FromStr, PartialEq, Serialize,
+)]
+pub enum RowFormat {
+    #[default]
+    Cbor,
+    Compact,
+}
+
+impl FromMeta for RowFormat {
+    fn from_string(s: &str) -> Result<Self, darling::Error> {
+        s.parse::<Self>()
+            .map_err(|_| darling::Error::unknown_value(s))
+    }
+}
+
+impl ToTokens for RowFormat {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let ident = format_ident!("{self}");
+
+        tokens.extend(quote!(::mimic::schema::types::RowFormat::#ident));
+    }
+}
+
 ///
 /// StoreType
 ///
 
 #[derive(CandidType, Clone, Copy, Debug, Deserialize, Display, FromStr, Serialize)]
 pub enum StoreType {
+    ChangeLog,
     Data,
+    History,
     Index,
+    Job,
 }
 
 impl FromMeta for StoreType {
@@ -1,7 +1,7 @@
 use test_design as _;
 
 fn main() -> std::io::Result<()> {
-    mimic::mimic_build!("test_design::schema::Canister");
+    mimic::mimic_build!("test_design::schema::Canister", snapshot = "schema.json");
 
     Ok(())
 }
@@ -0,0 +1,55 @@
+use mimic::{
+    core::traits::Path,
+    db::query::{LoadQuery, SaveMode, SaveQuery},
+    prelude::*,
+};
+use test_design::test::access::GuardedEntity;
+
+///
+/// AccessSuite
+///
+
+pub struct AccessSuite {}
+
+impl AccessSuite {
+    pub fn test() {
+        let tests: Vec<(&str, fn())> = vec![
+            ("guard_rejects_write", Self::guard_rejects_write),
+            ("public_read_allowed", Self::public_read_allowed),
+        ];
+
+        for (name, test_fn) in tests {
+            crate::clear_test_data_store();
+
+            println!("Running test: {name}");
+            test_fn();
+        }
+    }
+
+    fn guard_rejects_write() {
+        let query = SaveQuery::new(SaveMode::Insert)
+            .from_entity(GuardedEntity {
+                name: "nope".to_string(),
+                ..Default::default()
+            })
+            .unwrap();
+        let err = crate::mimic_query_save(GuardedEntity::PATH.to_string(), query).unwrap_err();
+
+        assert!(err.to_string().contains("writes are closed"));
+        assert_eq!(db!().load::<GuardedEntity>().count_all().unwrap(), 0);
+    }
+
+    fn public_read_allowed() {
+        let e = db!()
+            .insert(GuardedEntity {
+                name: "ok".to_string(),
+                ..Default::default()
+            })
+            .unwrap();
+
+        let keys =
+            crate::mimic_query_load(GuardedEntity::PATH.to_string(), LoadQuery::all()).unwrap();
+
+        assert_eq!(keys, vec![e.key()]);
+    }
+}
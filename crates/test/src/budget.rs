@@ -0,0 +1,127 @@
+use mimic::{core::traits::Path, prelude::*};
+use test_design::{e2e::db::SimpleEntity, schema::TestDataStore};
+
+///
+/// BudgetSuite
+///
+
+pub struct BudgetSuite {}
+
+impl BudgetSuite {
+    pub fn test() {
+        let tests: Vec<(&str, fn())> = vec![
+            ("load_resumes_in_key_order", Self::load_resumes_in_key_order),
+            ("load_limit_resumes", Self::load_limit_resumes),
+            ("load_instruction_budget", Self::load_instruction_budget),
+            ("delete_resumes", Self::delete_resumes),
+            ("budget_rejects_sort", Self::budget_rejects_sort),
+        ];
+
+        for (name, test_fn) in tests {
+            crate::DATA_REGISTRY
+                .with(|reg| reg.with_store_mut(TestDataStore::PATH, |store| store.clear()))
+                .unwrap();
+
+            println!("Running test: {name}");
+            test_fn();
+        }
+    }
+
+    fn insert(n: usize) -> Vec<Key> {
+        let mut keys: Vec<Key> = (0..n)
+            .map(|_| db!().insert(SimpleEntity::default()).unwrap().key())
+            .collect();
+        keys.sort();
+
+        keys
+    }
+
+    fn load_resumes_in_key_order() {
+        let keys = Self::insert(10);
+
+        let mut loaded = Vec::new();
+        let mut resume = None;
+        let mut calls = 0;
+        loop {
+            let res = db!()
+                .load::<SimpleEntity>()
+                .execute(db::query::load().max_rows(3).resume_opt(resume))
+                .unwrap();
+            calls += 1;
+            loaded.extend(res.keys());
+
+            resume = res.resume();
+            if resume.is_none() {
+                break;
+            }
+        }
+
+        assert_eq!(calls, 4);
+        assert_eq!(loaded, keys);
+    }
+
+    fn load_limit_resumes() {
+        let keys = Self::insert(5);
+
+        let res = db!()
+            .load::<SimpleEntity>()
+            .execute(db::query::load().limit(2).max_rows(100))
+            .unwrap();
+        assert_eq!(res.keys(), keys[..2]);
+        assert_eq!(res.resume(), Some(ResumeToken(keys[1])));
+
+        let res = db!()
+            .load::<SimpleEntity>()
+            .execute(
+                db::query::load()
+                    .limit(10)
+                    .max_rows(100)
+                    .resume(ResumeToken(keys[1])),
+            )
+            .unwrap();
+        assert_eq!(res.keys(), keys[2..]);
+        assert!(res.is_complete());
+    }
+
+    // every call makes progress even if the budget is already spent
+    fn load_instruction_budget() {
+        let keys = Self::insert(5);
+
+        let res = db!()
+            .load::<SimpleEntity>()
+            .execute(db::query::load().max_instructions(1))
+            .unwrap();
+        assert_eq!(res.keys(), keys[..1]);
+        assert!(!res.is_complete());
+    }
+
+    fn delete_resumes() {
+        Self::insert(10);
+
+        let mut deleted = 0;
+        let mut resume = None;
+        loop {
+            let res = db!()
+                .delete::<SimpleEntity>()
+                .execute(db::query::delete().max_rows(4).resume_opt(resume))
+                .unwrap();
+            deleted += res.count();
+
+            resume = res.resume();
+            if resume.is_none() {
+                break;
+            }
+        }
+
+        assert_eq!(deleted, 10);
+        assert_eq!(db!().load::<SimpleEntity>().count_all().unwrap(), 0);
+    }
+
+    fn budget_rejects_sort() {
+        let res = db!()
+            .load::<SimpleEntity>()
+            .execute(db::query::load().sort(|s| s.asc("id")).max_rows(10));
+
+        assert!(res.is_err(), "budgeted loads are always in key order");
+    }
+}
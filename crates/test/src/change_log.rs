@@ -0,0 +1,88 @@
+use mimic::{
+    core::traits::EntityKind,
+    db::store::{ChangeOp, decode_row},
+    prelude::*,
+};
+use test_design::test::relation::EntityA;
+
+///
+/// ChangeLogSuite
+///
+
+pub struct ChangeLogSuite {}
+
+impl ChangeLogSuite {
+    pub fn test() {
+        let tests: Vec<(&str, fn())> = vec![
+            ("records_save_and_delete", Self::records_save_and_delete),
+            ("pages_from_sequence", Self::pages_from_sequence),
+        ];
+
+        for (name, test_fn) in tests {
+            println!("Running test: {name}");
+            test_fn();
+        }
+    }
+
+    // the change log isn't cleared between suites, so always read from the
+    // current end of the log
+    fn last_seq() -> u64 {
+        let mut since = 0;
+        loop {
+            let page = crate::DB.changes(since, usize::MAX).unwrap();
+            since = page.last_seq;
+            if !page.has_more {
+                return since;
+            }
+        }
+    }
+
+    fn records_save_and_delete() {
+        let since = Self::last_seq();
+
+        let a = db!().insert(EntityA::default()).unwrap();
+        db!().replace(a.clone()).unwrap();
+        db!().delete::<EntityA>().one(a.id).unwrap();
+
+        let page = crate::DB.changes(since, 10).unwrap();
+        let ops: Vec<_> = page.changes.iter().map(|c| c.op).collect();
+        assert_eq!(
+            ops,
+            vec![ChangeOp::Insert, ChangeOp::Replace, ChangeOp::Delete]
+        );
+
+        for change in &page.changes {
+            assert_eq!(change.path, EntityA::PATH);
+            assert_eq!(change.key, a.key());
+        }
+
+        let insert = &page.changes[0];
+        assert!(insert.old.is_none());
+        let new: EntityA = decode_row(insert.new.as_ref().unwrap()).unwrap();
+        assert_eq!(new.id, a.id);
+
+        let replace = &page.changes[1];
+        assert_eq!(replace.old, replace.new);
+
+        let delete = &page.changes[2];
+        assert_eq!(delete.old, replace.new);
+        assert!(delete.new.is_none());
+    }
+
+    fn pages_from_sequence() {
+        let since = Self::last_seq();
+
+        for _ in 0..5 {
+            db!().insert(EntityA::default()).unwrap();
+        }
+
+        let first = crate::DB.changes(since, 3).unwrap();
+        assert_eq!(first.changes.len(), 3);
+        assert!(first.has_more);
+
+        let second = crate::DB.changes(first.last_seq, 3).unwrap();
+        assert_eq!(second.changes.len(), 2);
+        assert!(!second.has_more);
+        assert_eq!(second.last_seq, since + 5);
+    }
+}
@@ -0,0 +1,145 @@
+use mimic::{
+    core::{serialize, traits::Path},
+    db::{
+        jobs::{self, JobKind},
+        primitives::BudgetExpr,
+        store::{DataKey, RowCodec},
+    },
+    obs::storage_report,
+    prelude::*,
+    schema::types::RowFormat,
+};
+use test_design::{schema::TestDataStore, test::codec::CompressedEntity};
+
+// header tag of a compact, compressed row
+const COMPRESSED_TAG: u8 = RowCodec::new(RowFormat::Compact, true).tag();
+
+///
+/// CodecSuite
+///
+
+pub struct CodecSuite {}
+
+impl CodecSuite {
+    pub fn test() {
+        let tests: Vec<(&str, fn())> = vec![
+            ("compressed_round_trip", Self::compressed_round_trip),
+            ("reads_default_codec_rows", Self::reads_default_codec_rows),
+            ("migrate_job_reencodes", Self::migrate_job_reencodes),
+            ("report_counts_savings", Self::report_counts_savings),
+        ];
+
+        for (name, test_fn) in tests {
+            crate::clear_test_data_store();
+
+            println!("Running test: {name}");
+            test_fn();
+        }
+    }
+
+    fn stored(id: Ulid) -> Vec<u8> {
+        crate::DATA_REGISTRY
+            .with(|reg| {
+                reg.with_store(TestDataStore::PATH, |store| {
+                    store.get(&DataKey::new::<CompressedEntity>(id))
+                })
+            })
+            .unwrap()
+            .unwrap()
+    }
+
+    // put_default
+    // a row as written before the entity had a codec
+    fn put_default(body: &str) -> Ulid {
+        let entity = CompressedEntity {
+            body: body.to_string(),
+            ..Default::default()
+        };
+        let mut bytes = vec![0xFF, 0, 0, 0, 1];
+        bytes.extend(serialize(&entity).unwrap());
+
+        crate::DATA_REGISTRY
+            .with(|reg| {
+                reg.with_store_mut(TestDataStore::PATH, |store| {
+                    store.insert(DataKey::new::<CompressedEntity>(entity.id), bytes)
+                })
+            })
+            .unwrap();
+
+        entity.id
+    }
+
+    fn compressed_round_trip() {
+        let body = "all work and no play ".repeat(50);
+        let e = db!()
+            .insert(CompressedEntity {
+                body: body.clone(),
+                ..Default::default()
+            })
+            .unwrap();
+
+        let bytes = Self::stored(e.id);
+        assert_eq!(bytes[..2], [0xFE, COMPRESSED_TAG]);
+        assert!(bytes.len() < body.len() / 4);
+
+        let loaded = db!()
+            .load::<CompressedEntity>()
+            .one(e.id)
+            .unwrap()
+            .entity()
+            .unwrap();
+        assert_eq!(loaded.body, body);
+    }
+
+    fn reads_default_codec_rows() {
+        let id = Self::put_default("plain");
+
+        let e = db!()
+            .load::<CompressedEntity>()
+            .one(id)
+            .unwrap()
+            .entity()
+            .unwrap();
+        assert_eq!(e.body, "plain");
+
+        // reads don't rewrite the row
+        assert_eq!(Self::stored(id)[0], 0xFF);
+    }
+
+    fn migrate_job_reencodes() {
+        let id = Self::put_default(&"abcd".repeat(100));
+
+        jobs::start(
+            crate::DB,
+            crate::MIMIC_JOB_RUNNERS,
+            JobKind::Migrate {
+                entity: CompressedEntity::PATH.to_string(),
+            },
+        )
+        .unwrap();
+        while jobs::step(crate::DB, crate::MIMIC_JOB_RUNNERS, &BudgetExpr::default()).unwrap() {}
+
+        assert_eq!(Self::stored(id)[..2], [0xFE, COMPRESSED_TAG]);
+    }
+
+    fn report_counts_savings() {
+        db!()
+            .insert(CompressedEntity {
+                body: "z".repeat(2000),
+                ..Default::default()
+            })
+            .unwrap();
+
+        let report = storage_report(
+            &crate::DB,
+            &[(CompressedEntity::ENTITY_ID, CompressedEntity::PATH)],
+        );
+        let entity = report
+            .entity_storage
+            .iter()
+            .find(|e| e.path == CompressedEntity::PATH)
+            .unwrap();
+
+        assert!(entity.saved_bytes > 1500);
+    }
+}
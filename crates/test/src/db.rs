@@ -21,6 +21,7 @@ impl DbSuite {
             ("index_create_and_delete", Self::index_create_and_delete),
             ("index_option", Self::index_option),
             ("limit_query", Self::limit_query),
+            ("load_pages", Self::load_pages),
             ("load_one", Self::load_one),
             ("load_many", Self::load_many),
             ("unit_primary_key", Self::unit_primary_key),
@@ -353,6 +354,43 @@ impl DbSuite {
         }
     }
 
+    fn load_pages() {
+        use mimic::db::response::NextPage;
+        use test_design::e2e::db::Limit;
+
+        for value in 1..=25 {
+            db!()
+                .replace(Limit {
+                    value,
+                    ..Default::default()
+                })
+                .unwrap();
+        }
+
+        // walk every page, following `next`
+        let mut query = db::query::load().filter(|f| f.gt("value", 5)).limit(8);
+        let mut values = Vec::new();
+        loop {
+            let page = db!().load::<Limit>().page(query.clone()).unwrap();
+            assert_eq!(page.total, Some(20));
+            values.extend(page.rows.iter().map(|(_, e)| e.value));
+
+            match page.next {
+                Some(next) => query = query.page(next),
+                None => break,
+            }
+        }
+        assert_eq!(values, (6..=25).collect::<Vec<_>>());
+
+        // budgeted loads page by resume token
+        let page = db!()
+            .load::<Limit>()
+            .page(db::query::load().max_rows(10))
+            .unwrap();
+        assert_eq!(page.total, None);
+        assert!(matches!(page.next, Some(NextPage::Resume(_))));
+    }
+
     fn load_one() {
         use test_design::e2e::db::SimpleEntity;
 
@@ -0,0 +1,139 @@
+use mimic::{prelude::*, types::Timestamp};
+use test_design::test::history::HistoryEntity;
+
+///
+/// HistorySuite
+///
+
+pub struct HistorySuite {}
+
+impl HistorySuite {
+    pub fn test() {
+        let tests: Vec<(&str, fn())> = vec![
+            ("records_versions", Self::records_versions),
+            ("retention_limit", Self::retention_limit),
+            ("delete_keeps_version", Self::delete_keeps_version),
+            ("rollback", Self::rollback),
+            ("as_of", Self::as_of),
+        ];
+
+        for (name, test_fn) in tests {
+            crate::clear_test_data_store();
+            crate::TEST_HISTORY_STORE.with_borrow_mut(|history| history.clear_new());
+
+            println!("Running test: {name}");
+            test_fn();
+        }
+    }
+
+    fn insert(balance: u64) -> HistoryEntity {
+        db!()
+            .insert(HistoryEntity {
+                balance,
+                ..Default::default()
+            })
+            .unwrap()
+    }
+
+    fn set_balance(mut e: HistoryEntity, balance: u64) -> HistoryEntity {
+        e.balance = balance;
+
+        db!().update(e).unwrap()
+    }
+
+    fn balances(e: &HistoryEntity) -> Vec<Option<u64>> {
+        db!()
+            .load::<HistoryEntity>()
+            .history(e.id)
+            .unwrap()
+            .into_iter()
+            .map(|v| v.entity.map(|e| e.balance))
+            .collect()
+    }
+
+    fn records_versions() {
+        let e = Self::insert(10);
+        let e = Self::set_balance(e, 20);
+
+        // creation is recorded as an empty version
+        assert_eq!(Self::balances(&e), vec![None, Some(10)]);
+
+        let versions = db!().load::<HistoryEntity>().history(e.id).unwrap();
+        let numbers: Vec<_> = versions.iter().map(|v| v.version).collect();
+        assert_eq!(numbers, vec![1, 2]);
+    }
+
+    fn retention_limit() {
+        let mut e = Self::insert(0);
+        for balance in 1..=5 {
+            e = Self::set_balance(e, balance);
+        }
+
+        // limit = 3, the oldest versions are dropped
+        assert_eq!(Self::balances(&e), vec![Some(2), Some(3), Some(4)]);
+    }
+
+    fn delete_keeps_version() {
+        let e = Self::insert(10);
+        db!().delete::<HistoryEntity>().one(e.id).unwrap();
+
+        assert_eq!(Self::balances(&e), vec![None, Some(10)]);
+    }
+
+    fn rollback() {
+        let e = Self::insert(10);
+        let e = Self::set_balance(e, 999);
+
+        let old = db!()
+            .load::<HistoryEntity>()
+            .history(e.id)
+            .unwrap()
+            .into_iter()
+            .rev()
+            .find_map(|v| v.entity)
+            .unwrap();
+        db!().replace(old).unwrap();
+
+        let current = db!().load::<HistoryEntity>().one_entity(e.id).unwrap();
+        assert_eq!(current.balance, 10);
+    }
+
+    // a canister call sees a single time, so everything written in this
+    // suite happened at `now`
+    fn as_of() {
+        let e = Self::insert(10);
+        let e = Self::set_balance(e, 20);
+        let now = Timestamp::now();
+
+        let res = db!()
+            .load::<HistoryEntity>()
+            .execute(db::query::load().one::<HistoryEntity>(e.id).as_of(now))
+            .unwrap()
+            .try_entity()
+            .unwrap();
+        assert_eq!(res.balance, 20);
+
+        // before the row was created
+        let before = Timestamp::from(now.get() - 1);
+        let res = db!()
+            .load::<HistoryEntity>()
+            .execute(db::query::load().as_of(before))
+            .unwrap();
+        assert!(res.is_empty(), "no rows existed before this test ran");
+
+        // once retention drops the version live at `before`, reading it fails
+        (1..=3).fold(Self::insert(0), Self::set_balance);
+        let res = db!()
+            .load::<HistoryEntity>()
+            .execute(db::query::load().as_of(before));
+        assert!(res.is_err(), "history before {before} was pruned");
+
+        // deleted rows are still visible at a point before the delete
+        db!().delete::<HistoryEntity>().one(e.id).unwrap();
+        let res = db!()
+            .load::<HistoryEntity>()
+            .execute(db::query::load().as_of(now))
+            .unwrap();
+        assert!(!res.keys().contains(&e.key()));
+    }
+}
@@ -0,0 +1,156 @@
+use mimic::prelude::*;
+use test_design::test::hooks::{HookCounter, HookedEntity};
+
+///
+/// HooksSuite
+///
+
+pub struct HooksSuite {}
+
+impl HooksSuite {
+    pub fn test() {
+        let tests: Vec<(&str, fn())> = vec![
+            ("before_save_modifies", Self::before_save_modifies),
+            ("before_save_veto", Self::before_save_veto),
+            ("after_save_counts", Self::after_save_counts),
+            ("before_delete_veto", Self::before_delete_veto),
+            ("after_delete_counts", Self::after_delete_counts),
+        ];
+
+        for (name, test_fn) in tests {
+            crate::clear_test_data_store();
+
+            println!("Running test: {name}");
+            test_fn();
+        }
+    }
+
+    fn counter() -> HookCounter {
+        db!().insert(HookCounter::default()).unwrap()
+    }
+
+    fn reload_counter(counter: &HookCounter) -> HookCounter {
+        db!()
+            .load::<HookCounter>()
+            .one(counter.id)
+            .unwrap()
+            .try_entity()
+            .unwrap()
+    }
+
+    fn before_save_modifies() {
+        let counter = Self::counter();
+
+        let saved = db!()
+            .insert(HookedEntity {
+                counter_id: counter.id,
+                name: "hello".to_string(),
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(saved.name_len, 5);
+
+        // the stored row has the hook's changes too
+        let loaded = db!()
+            .load::<HookedEntity>()
+            .one(saved.id)
+            .unwrap()
+            .try_entity()
+            .unwrap();
+        assert_eq!(loaded.name_len, 5);
+    }
+
+    fn before_save_veto() {
+        let counter = Self::counter();
+
+        let res = db!().insert(HookedEntity {
+            counter_id: counter.id,
+            name: "veto".to_string(),
+            ..Default::default()
+        });
+        assert!(res.is_err(), "before_save should veto the insert");
+
+        // nothing was written, and after_save never ran
+        let count = db!().load::<HookedEntity>().all().unwrap().count();
+        assert_eq!(count, 0);
+        assert_eq!(Self::reload_counter(&counter).inserts, 0);
+
+        // old value is passed on update
+        let mut locked = db!()
+            .insert(HookedEntity {
+                counter_id: counter.id,
+                locked: true,
+                ..Default::default()
+            })
+            .unwrap();
+        locked.name = "changed".to_string();
+        assert!(
+            db!().update(locked).is_err(),
+            "locked rows can't be updated"
+        );
+    }
+
+    fn after_save_counts() {
+        let counter = Self::counter();
+
+        let mut row = db!()
+            .insert(HookedEntity {
+                counter_id: counter.id,
+                ..Default::default()
+            })
+            .unwrap();
+        row.name = "renamed".to_string();
+        db!().update(row.clone()).unwrap();
+        db!().replace(row).unwrap();
+
+        let counter = Self::reload_counter(&counter);
+        assert_eq!(counter.inserts, 1);
+        assert_eq!(counter.updates, 2);
+    }
+
+    fn before_delete_veto() {
+        let counter = Self::counter();
+
+        let open = db!()
+            .insert(HookedEntity {
+                counter_id: counter.id,
+                ..Default::default()
+            })
+            .unwrap();
+        db!()
+            .insert(HookedEntity {
+                counter_id: counter.id,
+                locked: true,
+                ..Default::default()
+            })
+            .unwrap();
+
+        // one locked row vetoes the whole delete
+        let res = db!().delete::<HookedEntity>().all();
+        assert!(res.is_err(), "before_delete should veto the delete");
+
+        let count = db!().load::<HookedEntity>().all().unwrap().count();
+        assert_eq!(count, 2);
+        assert_eq!(Self::reload_counter(&counter).deletes, 0);
+
+        db!().delete::<HookedEntity>().one(open.id).unwrap();
+        assert_eq!(Self::reload_counter(&counter).deletes, 1);
+    }
+
+    fn after_delete_counts() {
+        let counter = Self::counter();
+
+        for _ in 0..3 {
+            db!()
+                .insert(HookedEntity {
+                    counter_id: counter.id,
+                    ..Default::default()
+                })
+                .unwrap();
+        }
+
+        let deleted = db!().delete::<HookedEntity>().all().unwrap();
+        assert_eq!(deleted.count(), 3);
+        assert_eq!(Self::reload_counter(&counter).deletes, 3);
+    }
+}
@@ -100,7 +100,7 @@ impl IndexSuite {
             QueryPlan::Index(index_plan) => {
                 let len = index_plan.values.len();
 
-                assert_eq!(len, 1, "Expected one index field to be matched, got {len}",);
+                assert_eq!(len, 1, "Expected one index field to be matched, got {len}");
                 println!("✅ Index plan uses {len} fields");
             }
             _ => panic!("❌ Expected index plan, got: {plan:?}"),
@@ -0,0 +1,190 @@
+use mimic::{
+    core::traits::Path,
+    db::{
+        jobs::{self, Job, JobKind, JobStatus},
+        primitives::BudgetExpr,
+    },
+    prelude::*,
+    types::Timestamp,
+};
+use test_design::{
+    e2e::db::SimpleEntity,
+    schema::TestIndexStore,
+    test::{soft_delete::SoftDeleteEntity, ttl::TtlEntity},
+};
+
+///
+/// JobsSuite
+///
+
+pub struct JobsSuite {}
+
+impl JobsSuite {
+    pub fn test() {
+        let tests: Vec<(&str, fn())> = vec![
+            ("delete_in_chunks", Self::delete_in_chunks),
+            ("pause_resume_cancel", Self::pause_resume_cancel),
+            ("reindex", Self::reindex),
+            ("ttl_sweep", Self::ttl_sweep),
+            ("unknown_entity", Self::unknown_entity),
+        ];
+
+        for (name, test_fn) in tests {
+            crate::clear_test_data_store();
+            crate::DB.with_job_store(|store| store.clear_new()).unwrap();
+
+            println!("Running test: {name}");
+            test_fn();
+        }
+    }
+
+    fn start(kind: JobKind) -> Job {
+        jobs::start(crate::DB, crate::MIMIC_JOB_RUNNERS, kind).unwrap()
+    }
+
+    // runs chunks of two rows until nothing is running, returning the chunk count
+    fn run() -> usize {
+        let budget = BudgetExpr::default().max_rows(2);
+
+        let mut chunks = 0;
+        loop {
+            chunks += 1;
+            if !jobs::step(crate::DB, crate::MIMIC_JOB_RUNNERS, &budget).unwrap() {
+                return chunks;
+            }
+        }
+    }
+
+    fn get(id: u64) -> Job {
+        jobs::list(crate::DB)
+            .unwrap()
+            .into_iter()
+            .find(|job| job.id == id)
+            .unwrap()
+    }
+
+    fn delete_in_chunks() {
+        for _ in 0..5 {
+            db!().insert(SimpleEntity::default()).unwrap();
+        }
+
+        let job = Self::start(JobKind::Delete {
+            entity: SimpleEntity::PATH.to_string(),
+            query: Box::new(db::query::delete().limit(1)),
+        });
+        assert_eq!(job.status, JobStatus::Running);
+
+        assert_eq!(Self::run(), 3);
+
+        let job = Self::get(job.id);
+        assert_eq!(job.status, JobStatus::Completed);
+        assert_eq!(job.rows, 5, "limit is ignored by delete jobs");
+        assert_eq!(db!().load::<SimpleEntity>().count_all().unwrap(), 0);
+    }
+
+    fn pause_resume_cancel() {
+        for _ in 0..5 {
+            db!().insert(SimpleEntity::default()).unwrap();
+        }
+
+        let job = Self::start(JobKind::Delete {
+            entity: SimpleEntity::PATH.to_string(),
+            query: Box::new(db::query::delete()),
+        });
+        let budget = BudgetExpr::default().max_rows(2);
+        jobs::step(crate::DB, crate::MIMIC_JOB_RUNNERS, &budget).unwrap();
+
+        // paused jobs are skipped
+        jobs::pause(crate::DB, job.id).unwrap();
+        assert!(!jobs::step(crate::DB, crate::MIMIC_JOB_RUNNERS, &budget).unwrap());
+        assert_eq!(db!().load::<SimpleEntity>().count_all().unwrap(), 3);
+        assert!(jobs::pause(crate::DB, job.id).is_err());
+
+        // and pick up from their cursor
+        jobs::resume(crate::DB, job.id).unwrap();
+        jobs::step(crate::DB, crate::MIMIC_JOB_RUNNERS, &budget).unwrap();
+        assert_eq!(Self::get(job.id).rows, 4);
+
+        jobs::cancel(crate::DB, job.id).unwrap();
+        assert!(!jobs::step(crate::DB, crate::MIMIC_JOB_RUNNERS, &budget).unwrap());
+        assert_eq!(db!().load::<SimpleEntity>().count_all().unwrap(), 1);
+        assert!(jobs::resume(crate::DB, job.id).is_err());
+        assert!(jobs::cancel(crate::DB, job.id).is_err());
+    }
+
+    fn reindex() {
+        for name in ["a", "b", "c"] {
+            db!()
+                .insert(SoftDeleteEntity {
+                    name: name.to_string(),
+                    ..Default::default()
+                })
+                .unwrap();
+        }
+
+        // lose the index, so the unique check no longer sees the rows
+        crate::INDEX_REGISTRY
+            .with(|reg| reg.with_store_mut(TestIndexStore::PATH, |store| store.clear()))
+            .unwrap();
+
+        let job = Self::start(JobKind::Reindex {
+            entity: SoftDeleteEntity::PATH.to_string(),
+        });
+        Self::run();
+
+        let job = Self::get(job.id);
+        assert_eq!(job.status, JobStatus::Completed);
+        assert_eq!(job.rows, 3);
+
+        let res = db!().insert(SoftDeleteEntity {
+            name: "b".to_string(),
+            ..Default::default()
+        });
+        assert!(
+            res.is_err(),
+            "rebuilt unique index should reject duplicates"
+        );
+    }
+
+    fn ttl_sweep() {
+        let past = Timestamp::from(Timestamp::now().get() - 60);
+        for i in 0..3 {
+            db!()
+                .insert(TtlEntity {
+                    name: format!("expired{i}"),
+                    expires_at: past,
+                    ..Default::default()
+                })
+                .unwrap();
+        }
+
+        let job = Self::start(JobKind::TtlSweep {
+            entity: TtlEntity::PATH.to_string(),
+        });
+        Self::run();
+
+        let job = Self::get(job.id);
+        assert_eq!(job.status, JobStatus::Completed);
+        assert_eq!(job.rows, 3);
+        assert_eq!(
+            db!()
+                .delete::<TtlEntity>()
+                .sweep_expired(10)
+                .unwrap()
+                .count(),
+            0
+        );
+    }
+
+    fn unknown_entity() {
+        let res = jobs::start(
+            crate::DB,
+            crate::MIMIC_JOB_RUNNERS,
+            JobKind::Reindex {
+                entity: "not::an::Entity".to_string(),
+            },
+        );
+        assert!(res.is_err());
+        assert!(jobs::list(crate::DB).unwrap().is_empty());
+    }
+}
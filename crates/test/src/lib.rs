@@ -1,9 +1,23 @@
+mod access;
+mod budget;
+mod change_log;
+mod codec;
 mod db;
 mod filter;
+mod history;
+mod hooks;
 mod index;
+mod jobs;
 mod merge;
 mod metrics;
+mod migration;
 mod ops;
+mod path;
+mod relation;
+mod schema;
+mod soft_delete;
+mod ttl;
+mod values;
 mod view_into;
 
 use canic::{cdk::export_candid, prelude::*};
@@ -17,7 +31,7 @@ use test_design::{
 // INIT
 //
 
-mimic_start!();
+mimic_start!(hooks = true);
 
 pub static WASMS: &[(CanisterType, &[u8])] = &[];
 
@@ -39,11 +53,25 @@ pub fn clear_test_data_store() {
 #[update]
 pub fn test() {
     let tests: Vec<(&str, fn())> = vec![
+        ("access", access::AccessSuite::test),
+        ("budget", budget::BudgetSuite::test),
+        ("change_log", change_log::ChangeLogSuite::test),
+        ("codec", codec::CodecSuite::test),
         ("db", db::DbSuite::test),
+        ("history", history::HistorySuite::test),
+        ("hooks", hooks::HooksSuite::test),
         ("index", index::IndexSuite::test),
+        ("jobs", jobs::JobsSuite::test),
         ("ops", ops::OpsSuite::test),
+        ("path", path::PathSuite::test),
         ("metrics", metrics::MetricsSuite::test),
         ("merge", merge::MergeSuite::test),
+        ("migration", migration::MigrationSuite::test),
+        ("relation", relation::RelationSuite::test),
+        ("schema", schema::SchemaSuite::test),
+        ("soft_delete", soft_delete::SoftDeleteSuite::test),
+        ("ttl", ttl::TtlSuite::test),
+        ("values", values::ValuesSuite::test),
         ("view_into", view_into::ViewIntoSuite::test),
         // filter
         ("delete_filter", filter::delete::DeleteFilterSuite::test),
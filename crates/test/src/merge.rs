@@ -19,8 +19,10 @@ pub struct MergeSuite;
 
 impl MergeSuite {
     pub fn test() {
-        let tests: Vec<(&str, fn())> =
-            vec![("entity_merge_round_trip", Self::entity_merge_round_trip)];
+        let tests: Vec<(&str, fn())> = vec![
+            ("entity_merge_round_trip", Self::entity_merge_round_trip),
+            ("patch_stored_row", Self::patch_stored_row),
+        ];
 
         for (name, test_fn) in tests {
             println!("Running test: {name}");
@@ -123,4 +125,38 @@ impl MergeSuite {
         assert_eq!(opt_profile.visits, 4);
         assert!(opt_profile.favorite_numbers.is_empty());
     }
+
+    fn patch_stored_row() {
+        let entity = db!()
+            .insert(MergeEntity {
+                name: "seed".into(),
+                scores: vec![1, 2],
+                ..Default::default()
+            })
+            .unwrap();
+
+        let update = Update::<MergeEntity> {
+            name: Some("patched".into()),
+            scores: Some(vec![ListPatch::Push { value: 3 }]),
+            ..Default::default()
+        };
+        let patched = db!().patch::<MergeEntity>(entity.key(), update).unwrap();
+        assert_eq!(patched.name, "patched");
+
+        let loaded = db!()
+            .load::<MergeEntity>()
+            .one(entity.key())
+            .unwrap()
+            .try_entity()
+            .unwrap();
+        assert_eq!(loaded.name, "patched");
+        assert_eq!(loaded.scores, vec![1, 2, 3]);
+
+        // there's nothing to patch at an unknown key
+        assert!(
+            db!()
+                .patch::<MergeEntity>(Ulid::generate(), Update::<MergeEntity>::default())
+                .is_err()
+        );
+    }
 }
@@ -0,0 +1,206 @@
+use mimic::{
+    core::{serialize, traits::Path},
+    db::{
+        jobs::{self, JobKind, JobStatus},
+        primitives::BudgetExpr,
+        store::{DataKey, row_version},
+    },
+    prelude::*,
+};
+use test_design::{
+    schema::{TestDataStore, TestIndexStore},
+    test::migration::{MigratedEntity, MigratedEntityV1},
+};
+
+///
+/// MigrationSuite
+///
+
+pub struct MigrationSuite {}
+
+impl MigrationSuite {
+    pub fn test() {
+        let tests: Vec<(&str, fn())> = vec![
+            ("lazy_on_read", Self::lazy_on_read),
+            ("save_writes_current", Self::save_writes_current),
+            ("migrate_job", Self::migrate_job),
+            ("stale_index_entries", Self::stale_index_entries),
+            ("newer_row_fails", Self::newer_row_fails),
+        ];
+
+        for (name, test_fn) in tests {
+            crate::clear_test_data_store();
+
+            println!("Running test: {name}");
+            test_fn();
+        }
+    }
+
+    // put_raw
+    // writes row bytes directly, as an older build of the canister would have
+    fn put_raw(id: Ulid, bytes: Vec<u8>) {
+        crate::DATA_REGISTRY
+            .with(|reg| {
+                reg.with_store_mut(TestDataStore::PATH, |store| {
+                    store.insert(DataKey::new::<MigratedEntity>(id), bytes)
+                })
+            })
+            .unwrap();
+    }
+
+    // put_v1
+    // rows from before versioning have no header and count as version 1
+    fn put_v1(name: &str) -> Ulid {
+        let id = Ulid::generate();
+        let bytes = serialize(&MigratedEntityV1 {
+            id,
+            name: name.to_string(),
+        })
+        .unwrap();
+        Self::put_raw(id, bytes);
+
+        id
+    }
+
+    fn stored_version(id: Ulid) -> u32 {
+        let bytes = crate::DATA_REGISTRY
+            .with(|reg| {
+                reg.with_store(TestDataStore::PATH, |store| {
+                    store.get(&DataKey::new::<MigratedEntity>(id))
+                })
+            })
+            .unwrap()
+            .unwrap();
+
+        row_version(&bytes).0
+    }
+
+    fn lazy_on_read() {
+        let id = Self::put_v1("alice");
+
+        let e = db!()
+            .load::<MigratedEntity>()
+            .one(id)
+            .unwrap()
+            .entity()
+            .unwrap();
+        assert_eq!(e.full_name, "alice");
+
+        // reads don't write the row back
+        assert_eq!(Self::stored_version(id), 1);
+    }
+
+    fn save_writes_current() {
+        let id = Self::put_v1("bob");
+
+        let e = db!()
+            .load::<MigratedEntity>()
+            .one(id)
+            .unwrap()
+            .entity()
+            .unwrap();
+        db!().update(e).unwrap();
+
+        assert_eq!(Self::stored_version(id), 2);
+    }
+
+    fn migrate_job() {
+        let ids: Vec<Ulid> = ["a", "b", "c"].into_iter().map(Self::put_v1).collect();
+
+        let job = jobs::start(
+            crate::DB,
+            crate::MIMIC_JOB_RUNNERS,
+            JobKind::Migrate {
+                entity: MigratedEntity::PATH.to_string(),
+            },
+        )
+        .unwrap();
+
+        let budget = BudgetExpr::default().max_rows(2);
+        while jobs::step(crate::DB, crate::MIMIC_JOB_RUNNERS, &budget).unwrap() {}
+
+        let job = jobs::list(crate::DB)
+            .unwrap()
+            .into_iter()
+            .find(|j| j.id == job.id)
+            .unwrap();
+        assert_eq!(job.status, JobStatus::Completed);
+        assert_eq!(job.rows, 3);
+
+        for id in ids {
+            assert_eq!(Self::stored_version(id), 2);
+        }
+        let mut names: Vec<String> = db!()
+            .load::<MigratedEntity>()
+            .all()
+            .unwrap()
+            .entities()
+            .into_iter()
+            .map(|e| e.full_name)
+            .collect();
+        names.sort();
+        assert_eq!(names, vec!["a", "b", "c"]);
+    }
+
+    // put_old_index
+    // an index entry for a value the row held before its migration
+    fn put_old_index(id: Ulid, full_name: &str) {
+        let old = MigratedEntity {
+            id,
+            full_name: full_name.to_string(),
+            ..Default::default()
+        };
+        crate::INDEX_REGISTRY
+            .with(|reg| {
+                reg.with_store_mut(TestIndexStore::PATH, |store| {
+                    store.insert_index_entry(&old, MigratedEntity::INDEXES[0])
+                })
+            })
+            .unwrap()
+            .unwrap();
+    }
+
+    fn stale_index_entries() {
+        let saved = Self::put_v1("a");
+        Self::put_old_index(saved, "old_a");
+        let migrated = Self::put_v1("b");
+        Self::put_old_index(migrated, "old_b");
+
+        let e = db!()
+            .load::<MigratedEntity>()
+            .one(saved)
+            .unwrap()
+            .entity()
+            .unwrap();
+        db!().update(e).unwrap();
+
+        jobs::start(
+            crate::DB,
+            crate::MIMIC_JOB_RUNNERS,
+            JobKind::Migrate {
+                entity: MigratedEntity::PATH.to_string(),
+            },
+        )
+        .unwrap();
+        while jobs::step(crate::DB, crate::MIMIC_JOB_RUNNERS, &BudgetExpr::default()).unwrap() {}
+
+        // the old values no longer hold the unique index
+        for name in ["old_a", "old_b"] {
+            db!()
+                .insert(MigratedEntity {
+                    full_name: name.to_string(),
+                    ..Default::default()
+                })
+                .unwrap();
+        }
+    }
+
+    fn newer_row_fails() {
+        let mut bytes = vec![0xFF, 0, 0, 0, 3];
+        bytes.extend(serialize(&MigratedEntity::default()).unwrap());
+        let id = Ulid::generate();
+        Self::put_raw(id, bytes);
+
+        assert!(db!().load::<MigratedEntity>().one(id).is_err());
+    }
+}
@@ -0,0 +1,173 @@
+use mimic::{
+    core::{Value, traits::FieldValues},
+    db::{
+        primitives::FilterExpr,
+        query::{self, QueryPlan, QueryPlanner},
+    },
+    prelude::*,
+};
+use std::collections::HashMap;
+use test_design::test::path::{PathAddress, PathEntity, PathLabels, PathPoint, PathStats};
+
+///
+/// PathSuite
+///
+
+pub struct PathSuite {}
+
+impl PathSuite {
+    pub fn test() {
+        let tests: Vec<(&str, fn())> = vec![
+            ("get_nested_values", Self::get_nested_values),
+            ("filter_nested", Self::filter_nested),
+            ("sort_nested", Self::sort_nested),
+            ("index_nested", Self::index_nested),
+            ("invalid_paths_fail", Self::invalid_paths_fail),
+            ("filter_field_cmp", Self::filter_field_cmp),
+            ("invalid_field_cmp_fails", Self::invalid_field_cmp_fails),
+        ];
+
+        for (name, test_fn) in tests {
+            crate::clear_test_data_store();
+
+            println!("Running test: {name}");
+            test_fn();
+        }
+    }
+
+    fn entity(level: u32, city: Option<&str>) -> PathEntity {
+        PathEntity {
+            stats: PathStats {
+                level,
+                tags: vec!["a".to_string(), "b".to_string()],
+            },
+            address: city.map(|city| PathAddress {
+                city: city.to_string(),
+            }),
+            labels: PathLabels(HashMap::from([("en".to_string(), level)])),
+            point: PathPoint(-1, 2),
+            ..Default::default()
+        }
+    }
+
+    fn filter(filter: &str) -> Vec<PathEntity> {
+        let expr: FilterExpr = filter.parse().unwrap();
+
+        db!()
+            .load::<PathEntity>()
+            .execute(query::load().filter(|_| expr))
+            .unwrap()
+            .entities()
+    }
+
+    fn get_nested_values() {
+        let e = Self::entity(3, Some("Paris"));
+
+        assert_eq!(e.get_value("stats.level"), Some(Value::Uint(3)));
+        assert_eq!(e.get_value("stats.tags.1"), Some(Value::Text("b".into())));
+        assert_eq!(e.get_value("stats.tags.7"), Some(Value::None));
+        assert_eq!(
+            e.get_value("address.city"),
+            Some(Value::Text("Paris".into()))
+        );
+        assert_eq!(e.get_value("labels.en"), Some(Value::Uint(3)));
+        assert_eq!(e.get_value("point.0"), Some(Value::Int(-1)));
+        assert_eq!(e.get_value("stats.nope"), None);
+
+        let e = Self::entity(3, None);
+        assert_eq!(e.get_value("address.city"), Some(Value::None));
+    }
+
+    fn filter_nested() {
+        db!().insert(Self::entity(1, Some("Paris"))).unwrap();
+        db!().insert(Self::entity(2, None)).unwrap();
+        db!().insert(Self::entity(3, Some("Rome"))).unwrap();
+
+        assert_eq!(Self::filter("stats.level >= 2").len(), 2);
+        assert_eq!(Self::filter(r#"address.city = "Rome""#).len(), 1);
+        assert_eq!(Self::filter("address.city is_none").len(), 1);
+        assert_eq!(
+            Self::filter(r#"stats.tags.0 = "a" and labels.en < 3"#).len(),
+            2
+        );
+        assert_eq!(Self::filter("point.1 = 2").len(), 3);
+    }
+
+    fn sort_nested() {
+        for level in [2, 3, 1] {
+            db!().insert(Self::entity(level, None)).unwrap();
+        }
+
+        let levels = db!()
+            .load::<PathEntity>()
+            .execute(query::load().sort(|s| s.desc("stats.level")))
+            .unwrap()
+            .entities()
+            .into_iter()
+            .map(|e| e.stats.level)
+            .collect::<Vec<_>>();
+
+        assert_eq!(levels, [3, 2, 1]);
+    }
+
+    fn index_nested() {
+        db!().insert(Self::entity(5, None)).unwrap();
+
+        let query = query::load().filter(|f| f.eq("stats.level", 5u32));
+        let plan = QueryPlanner::new(query.filter.as_ref()).plan::<PathEntity>();
+        assert!(matches!(plan, QueryPlan::Index(_)), "got {plan:?}");
+
+        let found = db!().load::<PathEntity>().execute(query).unwrap();
+        assert_eq!(found.count(), 1);
+    }
+
+    fn filter_field_cmp() {
+        let mut e = Self::entity(1, None);
+        e.labels.0.insert("en".to_string(), 4);
+        db!().insert(e).unwrap();
+        db!().insert(Self::entity(2, None)).unwrap();
+        db!().insert(Self::entity(3, Some("3"))).unwrap();
+
+        assert_eq!(Self::filter("stats.level < labels.en").len(), 1);
+        assert_eq!(Self::filter("stats.level = labels.en").len(), 2);
+        assert_eq!(Self::filter("point.0 < point.1").len(), 3);
+        assert_eq!(Self::filter("labels.de = labels.fr").len(), 3);
+        assert_eq!(Self::filter("labels.de != stats.level").len(), 3);
+        assert_eq!(Self::filter("address.city = stats.tags.0").len(), 0);
+
+        let found = db!()
+            .load::<PathEntity>()
+            .execute(query::load().filter(|f| f.gte_field("stats.level", "labels.en")))
+            .unwrap();
+        assert_eq!(found.count(), 2);
+    }
+
+    fn invalid_field_cmp_fails() {
+        for filter in [
+            "stats.level < stats.nope",
+            "stats.level = stats.tags",
+            "stats.tags < stats.tags",
+            "stats.level any_in labels.en",
+        ] {
+            let expr: FilterExpr = filter.parse().unwrap();
+            let res = db!()
+                .load::<PathEntity>()
+                .execute(query::load().filter(|_| expr));
+            assert!(res.is_err(), "filter {filter} should fail");
+        }
+    }
+
+    fn invalid_paths_fail() {
+        for field in ["stats.nope", "stats.level.x", "stats.tags.first", "point.2"] {
+            let res = db!()
+                .load::<PathEntity>()
+                .execute(query::load().filter(|f| f.eq(field, 1u32)));
+            assert!(res.is_err(), "filter on {field} should fail");
+
+            let res = db!()
+                .load::<PathEntity>()
+                .execute(query::load().sort(|s| s.asc(field)));
+            assert!(res.is_err(), "sort on {field} should fail");
+        }
+    }
+}
@@ -0,0 +1,224 @@
+use mimic::{
+    core::traits::EntityKind,
+    db::query::{QueryPlan, QueryPlanner},
+    prelude::*,
+};
+use test_design::test::relation::{EntityA, HasIndexedRelation, HasManyRelation, HasRelation};
+
+///
+/// RelationSuite
+///
+
+pub struct RelationSuite {}
+
+impl RelationSuite {
+    pub fn test() {
+        let tests: Vec<(&str, fn())> = vec![
+            ("include_one", Self::include_one),
+            ("include_many", Self::include_many),
+            ("include_missing_target", Self::include_missing_target),
+            ("include_invalid_field", Self::include_invalid_field),
+            ("include_wrong_target", Self::include_wrong_target),
+            ("relation_index_generated", Self::relation_index_generated),
+            ("referencing_indexed", Self::referencing_indexed),
+            ("referencing_many", Self::referencing_many),
+            ("referencing_after_update", Self::referencing_after_update),
+            ("referencing_no_relation", Self::referencing_no_relation),
+        ];
+
+        for (name, test_fn) in tests {
+            crate::clear_test_data_store();
+
+            println!("Running test: {name}");
+            test_fn();
+        }
+    }
+
+    fn include_one() {
+        let a = db!().insert(EntityA::default()).unwrap();
+        for _ in 0..3 {
+            db!()
+                .insert(HasRelation {
+                    a_id: a.id,
+                    ..Default::default()
+                })
+                .unwrap();
+        }
+
+        let res = db!()
+            .load::<HasRelation>()
+            .execute(db::query::load().include("a_id"))
+            .unwrap();
+
+        assert_eq!(res.count(), 3);
+
+        // three rows point at the same target, it should only be loaded once
+        let related = res.related_rows::<EntityA>("a_id").unwrap();
+        assert_eq!(related.len(), 1);
+        assert_eq!(related[0].1.id, a.id);
+    }
+
+    fn include_many() {
+        let a1 = db!().insert(EntityA::default()).unwrap();
+        let a2 = db!().insert(EntityA::default()).unwrap();
+        let a3 = db!().insert(EntityA::default()).unwrap();
+
+        db!()
+            .insert(HasManyRelation {
+                a_ids: vec![a1.id, a2.id],
+                ..Default::default()
+            })
+            .unwrap();
+        db!()
+            .insert(HasManyRelation {
+                a_ids: vec![a2.id, a3.id],
+                ..Default::default()
+            })
+            .unwrap();
+
+        let res = db!()
+            .load::<HasManyRelation>()
+            .execute(db::query::load().include("a_ids"))
+            .unwrap();
+
+        let related = res.related_map::<EntityA>("a_ids").unwrap();
+        assert_eq!(related.len(), 3);
+
+        for (_, row) in res {
+            for id in row.a_ids {
+                assert!(related.contains_key(&id.into()), "missing related row {id}");
+            }
+        }
+    }
+
+    fn include_missing_target() {
+        db!()
+            .insert(HasRelation {
+                a_id: Ulid::generate(),
+                ..Default::default()
+            })
+            .unwrap();
+
+        let res = db!()
+            .load::<HasRelation>()
+            .execute(db::query::load().include("a_id"))
+            .unwrap();
+
+        assert_eq!(res.count(), 1);
+        assert!(res.related_rows::<EntityA>("a_id").unwrap().is_empty());
+    }
+
+    fn include_invalid_field() {
+        let res = db!()
+            .load::<HasRelation>()
+            .execute(db::query::load().include("id"));
+
+        assert!(res.is_err(), "include on a non-relation field should fail");
+    }
+
+    fn include_wrong_target() {
+        let res = db!()
+            .load::<HasRelation>()
+            .execute(db::query::load().include("a_id"))
+            .unwrap();
+
+        assert!(res.related_rows::<HasRelation>("a_id").is_err());
+        assert!(res.related_rows::<EntityA>("b_id").is_err());
+    }
+
+    fn relation_index_generated() {
+        assert_eq!(HasIndexedRelation::INDEXES.len(), 2);
+
+        let query = db::query::load().filter(|f| f.eq("a_id", Ulid::generate()));
+        let plan = QueryPlanner::new(query.filter.as_ref()).plan::<HasIndexedRelation>();
+
+        assert!(
+            matches!(plan, QueryPlan::Index(_)),
+            "expected relation index plan, got {plan}"
+        );
+    }
+
+    fn referencing_indexed() {
+        let a = db!().insert(EntityA::default()).unwrap();
+        let other = db!().insert(EntityA::default()).unwrap();
+
+        let primary = db!()
+            .insert(HasIndexedRelation {
+                a_id: a.id,
+                ..Default::default()
+            })
+            .unwrap();
+        let backup = db!()
+            .insert(HasIndexedRelation {
+                a_id: other.id,
+                backup_a_id: Some(a.id),
+                ..Default::default()
+            })
+            .unwrap();
+        db!()
+            .insert(HasIndexedRelation {
+                a_id: other.id,
+                ..Default::default()
+            })
+            .unwrap();
+
+        let res = db!()
+            .referencing::<HasIndexedRelation, EntityA>(a.id)
+            .unwrap();
+        let mut expected = vec![Key::from(primary.id), Key::from(backup.id)];
+        expected.sort();
+
+        assert_eq!(res.keys(), expected);
+    }
+
+    fn referencing_many() {
+        let a = db!().insert(EntityA::default()).unwrap();
+
+        let hit = db!()
+            .insert(HasManyRelation {
+                a_ids: vec![Ulid::generate(), a.id],
+                ..Default::default()
+            })
+            .unwrap();
+        db!()
+            .insert(HasManyRelation {
+                a_ids: vec![Ulid::generate()],
+                ..Default::default()
+            })
+            .unwrap();
+
+        let res = db!().referencing::<HasManyRelation, EntityA>(a.id).unwrap();
+
+        assert_eq!(res.keys(), vec![Key::from(hit.id)]);
+    }
+
+    fn referencing_after_update() {
+        let a = db!().insert(EntityA::default()).unwrap();
+        let b = db!().insert(EntityA::default()).unwrap();
+
+        let mut row = db!()
+            .insert(HasIndexedRelation {
+                a_id: a.id,
+                ..Default::default()
+            })
+            .unwrap();
+        row.a_id = b.id;
+        db!().update(row).unwrap();
+
+        let from_a = db!()
+            .referencing::<HasIndexedRelation, EntityA>(a.id)
+            .unwrap();
+        let from_b = db!()
+            .referencing::<HasIndexedRelation, EntityA>(b.id)
+            .unwrap();
+
+        assert!(from_a.is_empty(), "stale relation index entry");
+        assert_eq!(from_b.count(), 1);
+    }
+
+    fn referencing_no_relation() {
+        let res = db!().referencing::<EntityA, HasRelation>(Ulid::generate());
+
+        assert!(res.is_err(), "EntityA has no relation to HasRelation");
+    }
+}
@@ -0,0 +1,116 @@
+use mimic::{
+    core::traits::{EntityKind, Path},
+    db::{
+        jobs::{self, JobKind, JobStatus},
+        primitives::BudgetExpr,
+    },
+    schema::types::{Cardinality, Primitive, StoreType},
+};
+use test_design::{
+    schema::TestIndexStore,
+    test::{migration::MigratedEntity, soft_delete::SoftDeleteEntity},
+};
+
+///
+/// SchemaSuite
+///
+
+pub struct SchemaSuite {}
+
+impl SchemaSuite {
+    pub fn test() {
+        let tests: Vec<(&str, fn())> = vec![
+            ("records_schema", Self::records_schema),
+            (
+                "version_bump_starts_migration",
+                Self::version_bump_starts_migration,
+            ),
+            ("incompatible_record_fails", Self::incompatible_record_fails),
+            ("describes_entities", Self::describes_entities),
+        ];
+
+        for (name, test_fn) in tests {
+            crate::DB.with_job_store(|store| store.clear_new()).unwrap();
+
+            println!("Running test: {name}");
+            test_fn();
+        }
+    }
+
+    fn records_schema() {
+        crate::mimic_schema_upgrade();
+
+        let stored = crate::MIMIC_SCHEMA_STORE.with_borrow(|store| store.get().clone());
+        assert_eq!(stored, crate::mimic_schema_record());
+        assert!(!stored.hash.is_empty());
+
+        // nothing changed since
+        assert!(crate::mimic_schema_upgrade().is_empty());
+    }
+
+    // pretend the stored rows were written before MigratedEntity's last version bump
+    fn version_bump_starts_migration() {
+        let mut old = crate::mimic_schema_record();
+        old.hash = "older".to_string();
+        let entity = old.entities.get_mut(MigratedEntity::PATH).unwrap();
+        entity.version -= 1;
+        let store = entity.store.clone();
+        old.stores.get_mut(&store).unwrap().fingerprint += 1;
+        crate::MIMIC_SCHEMA_STORE.with_borrow_mut(|s| s.set(old));
+
+        let upgrade = crate::mimic_schema_upgrade();
+        assert_eq!(upgrade.migrate, vec![MigratedEntity::PATH.to_string()]);
+
+        let jobs = jobs::list(crate::DB).unwrap();
+        assert_eq!(jobs.len(), 1);
+        assert!(
+            matches!(&jobs[0].kind, JobKind::Migrate { entity } if entity == MigratedEntity::PATH)
+        );
+
+        let budget = BudgetExpr::default().max_rows(100);
+        while jobs::step(crate::DB, crate::MIMIC_JOB_RUNNERS, &budget).unwrap() {}
+        assert_eq!(
+            jobs::list(crate::DB).unwrap()[0].status,
+            JobStatus::Completed
+        );
+    }
+
+    fn incompatible_record_fails() {
+        let current = crate::mimic_schema_record();
+        let mut old = current.clone();
+        old.hash = "older".to_string();
+        for store in old.stores.values_mut() {
+            store.memory_id += 1;
+        }
+
+        assert!(old.upgrade_to(&current).is_err());
+    }
+
+    fn describes_entities() {
+        let report = crate::mimic_schema().unwrap();
+        assert_eq!(report.hash, crate::mimic_schema_record().hash);
+
+        let index_store = report
+            .stores
+            .iter()
+            .find(|s| s.path == TestIndexStore::PATH)
+            .unwrap();
+        assert!(matches!(index_store.ty, StoreType::Index));
+
+        let entity = report.entity(SoftDeleteEntity::PATH).unwrap();
+        assert_eq!(entity.entity_id, SoftDeleteEntity::ENTITY_ID);
+        assert_eq!(entity.primary_key, "id");
+        assert!(entity.soft_delete);
+
+        let name = entity.field("name").unwrap();
+        assert_eq!(name.cardinality, Cardinality::One);
+        assert_eq!(name.primitive, Some(Primitive::Text));
+        assert!(!name.is_system);
+        assert!(entity.fields.iter().any(|f| f.is_system));
+
+        assert_eq!(entity.indexes.len(), 1);
+        assert_eq!(entity.indexes[0].store, TestIndexStore::PATH);
+        assert_eq!(entity.indexes[0].fields, vec!["name".to_string()]);
+        assert!(entity.indexes[0].unique);
+    }
+}
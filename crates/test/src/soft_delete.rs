@@ -0,0 +1,178 @@
+use mimic::prelude::*;
+use test_design::test::soft_delete::{HasSoftDeleteRelation, SoftDeleteEntity};
+
+///
+/// SoftDeleteSuite
+///
+
+pub struct SoftDeleteSuite {}
+
+impl SoftDeleteSuite {
+    pub fn test() {
+        let tests: Vec<(&str, fn())> = vec![
+            ("delete_hides_row", Self::delete_hides_row),
+            ("with_deleted", Self::with_deleted),
+            ("only_deleted", Self::only_deleted),
+            ("index_lookup_hides_row", Self::index_lookup_hides_row),
+            ("unique_includes_deleted", Self::unique_includes_deleted),
+            ("restore", Self::restore),
+            ("restore_live_row_fails", Self::restore_live_row_fails),
+            ("purge", Self::purge),
+            ("include_hides_deleted", Self::include_hides_deleted),
+        ];
+
+        for (name, test_fn) in tests {
+            crate::clear_test_data_store();
+
+            println!("Running test: {name}");
+            test_fn();
+        }
+    }
+
+    fn insert(name: &str) -> SoftDeleteEntity {
+        db!()
+            .insert(SoftDeleteEntity {
+                name: name.to_string(),
+                ..Default::default()
+            })
+            .unwrap()
+    }
+
+    fn delete_hides_row() {
+        let e = Self::insert("a");
+
+        let deleted = db!().delete::<SoftDeleteEntity>().one_entity(e.id).unwrap();
+        assert!(deleted.deleted_at.is_some());
+
+        let res = db!().load::<SoftDeleteEntity>().one(e.id).unwrap();
+        assert!(res.is_empty(), "soft deleted row should be hidden");
+
+        // a second delete doesn't touch the tombstone
+        let res = db!().delete::<SoftDeleteEntity>().one(e.id).unwrap();
+        assert!(res.is_empty());
+    }
+
+    fn with_deleted() {
+        let e = Self::insert("a");
+        Self::insert("b");
+        db!().delete::<SoftDeleteEntity>().one(e.id).unwrap();
+
+        let res = db!()
+            .load::<SoftDeleteEntity>()
+            .execute(db::query::load().with_deleted())
+            .unwrap();
+        assert_eq!(res.count(), 2);
+
+        let res = db!().load::<SoftDeleteEntity>().all().unwrap();
+        assert_eq!(res.count(), 1);
+    }
+
+    fn only_deleted() {
+        let e = Self::insert("a");
+        Self::insert("b");
+        db!().delete::<SoftDeleteEntity>().one(e.id).unwrap();
+
+        let res = db!()
+            .load::<SoftDeleteEntity>()
+            .execute(db::query::load().only_deleted())
+            .unwrap();
+        assert_eq!(res.keys(), vec![Key::from(e.id)]);
+
+        // pagination applies to live rows only
+        let res = db!()
+            .load::<SoftDeleteEntity>()
+            .execute(db::query::load().limit(1))
+            .unwrap();
+        assert_eq!(res.count(), 1);
+        assert_eq!(res.try_entity().unwrap().name, "b");
+    }
+
+    fn index_lookup_hides_row() {
+        let e = Self::insert("indexed");
+        db!().delete::<SoftDeleteEntity>().one(e.id).unwrap();
+
+        let res = db!()
+            .load::<SoftDeleteEntity>()
+            .filter(|f| f.eq("name", "indexed"))
+            .unwrap();
+        assert!(res.is_empty());
+    }
+
+    fn unique_includes_deleted() {
+        let e = Self::insert("taken");
+        db!().delete::<SoftDeleteEntity>().one(e.id).unwrap();
+
+        let res = db!().insert(SoftDeleteEntity {
+            name: "taken".to_string(),
+            ..Default::default()
+        });
+        assert!(
+            res.is_err(),
+            "soft deleted row still holds its unique value"
+        );
+    }
+
+    fn restore() {
+        let e = Self::insert("a");
+        db!().delete::<SoftDeleteEntity>().one(e.id).unwrap();
+
+        let restored = db!().restore::<SoftDeleteEntity>(e.id).unwrap();
+        assert!(restored.deleted_at.is_none());
+
+        let loaded = db!().load::<SoftDeleteEntity>().one_entity(e.id).unwrap();
+        assert_eq!(loaded.name, "a");
+    }
+
+    fn restore_live_row_fails() {
+        let e = Self::insert("a");
+
+        assert!(db!().restore::<SoftDeleteEntity>(e.id).is_err());
+        assert!(db!().restore::<SoftDeleteEntity>(Ulid::generate()).is_err());
+    }
+
+    fn purge() {
+        let live = Self::insert("live");
+        let trashed = Self::insert("trashed");
+        db!().delete::<SoftDeleteEntity>().one(trashed.id).unwrap();
+
+        let res = db!().delete::<SoftDeleteEntity>().purge().all().unwrap();
+        assert_eq!(res.count(), 2);
+
+        let res = db!()
+            .load::<SoftDeleteEntity>()
+            .execute(db::query::load().with_deleted())
+            .unwrap();
+        assert!(res.is_empty());
+
+        // index entries are gone, so the names can be reused
+        Self::insert("live");
+        Self::insert("trashed");
+        assert_ne!(live.id, trashed.id);
+    }
+
+    fn include_hides_deleted() {
+        let live = Self::insert("live");
+        let trashed = Self::insert("trashed");
+        db!().delete::<SoftDeleteEntity>().one(trashed.id).unwrap();
+
+        for target in [&live, &trashed] {
+            db!()
+                .insert(HasSoftDeleteRelation {
+                    target_id: target.id,
+                    ..Default::default()
+                })
+                .unwrap();
+        }
+
+        let res = db!()
+            .load::<HasSoftDeleteRelation>()
+            .execute(db::query::load().include("target_id"))
+            .unwrap();
+        assert_eq!(res.count(), 2);
+
+        // a load of the target would hide the deleted row, so the include does too
+        let related = res.related_rows::<SoftDeleteEntity>("target_id").unwrap();
+        assert_eq!(related.len(), 1);
+        assert_eq!(related[0].1.id, live.id);
+    }
+}
@@ -0,0 +1,126 @@
+use mimic::{prelude::*, types::Timestamp};
+use test_design::test::ttl::{OptTtlEntity, TtlEntity};
+
+///
+/// TtlSuite
+///
+
+pub struct TtlSuite {}
+
+impl TtlSuite {
+    pub fn test() {
+        let tests: Vec<(&str, fn())> = vec![
+            ("expired_rows_hidden", Self::expired_rows_hidden),
+            ("opt_expiry", Self::opt_expiry),
+            ("sweep_respects_batch", Self::sweep_respects_batch),
+            ("sweep_all_entities", Self::sweep_all_entities),
+            ("sweep_metrics", Self::sweep_metrics),
+        ];
+
+        for (name, test_fn) in tests {
+            crate::clear_test_data_store();
+
+            println!("Running test: {name}");
+            test_fn();
+        }
+    }
+
+    fn insert(name: &str, expires_at: Timestamp) -> TtlEntity {
+        db!()
+            .insert(TtlEntity {
+                name: name.to_string(),
+                expires_at,
+                ..Default::default()
+            })
+            .unwrap()
+    }
+
+    fn past() -> Timestamp {
+        Timestamp::from(Timestamp::now().get() - 60)
+    }
+
+    fn future() -> Timestamp {
+        Timestamp::from(Timestamp::now().get() + 3_600)
+    }
+
+    fn expired_rows_hidden() {
+        let live = Self::insert("live", Self::future());
+        let expired = Self::insert("expired", Self::past());
+
+        let res = db!().load::<TtlEntity>().all().unwrap();
+        assert_eq!(res.keys(), vec![live.key()]);
+
+        let res = db!().load::<TtlEntity>().one(expired.id).unwrap();
+        assert!(res.is_empty(), "expired row should be hidden");
+
+        db!().delete::<TtlEntity>().sweep_expired(100).unwrap();
+    }
+
+    fn opt_expiry() {
+        let never = db!().insert(OptTtlEntity::default()).unwrap();
+        db!()
+            .insert(OptTtlEntity {
+                expires_at: Some(Self::past()),
+                ..Default::default()
+            })
+            .unwrap();
+
+        let res = db!().load::<OptTtlEntity>().all().unwrap();
+        assert_eq!(res.keys(), vec![never.key()]);
+
+        let swept = db!().delete::<OptTtlEntity>().sweep_expired(100).unwrap();
+        assert_eq!(swept.count(), 1);
+    }
+
+    fn sweep_respects_batch() {
+        for i in 0..5 {
+            Self::insert(&format!("expired{i}"), Self::past());
+        }
+        let live = Self::insert("live", Self::future());
+
+        let swept = db!().delete::<TtlEntity>().sweep_expired(2).unwrap();
+        assert_eq!(swept.count(), 2);
+
+        let swept = db!().delete::<TtlEntity>().sweep_expired(10).unwrap();
+        assert_eq!(swept.count(), 3);
+
+        let swept = db!().delete::<TtlEntity>().sweep_expired(10).unwrap();
+        assert!(swept.is_empty());
+
+        let res = db!().load::<TtlEntity>().all().unwrap();
+        assert_eq!(res.keys(), vec![live.key()]);
+    }
+
+    fn sweep_all_entities() {
+        Self::insert("expired", Self::past());
+        db!()
+            .insert(OptTtlEntity {
+                expires_at: Some(Self::past()),
+                ..Default::default()
+            })
+            .unwrap();
+
+        let swept = crate::mimic_ttl_sweep(100).unwrap();
+        assert_eq!(swept, 2);
+        assert_eq!(crate::mimic_ttl_sweep(100).unwrap(), 0);
+    }
+
+    fn sweep_metrics() {
+        crate::mimic_metrics_reset().unwrap();
+
+        Self::insert("a", Self::past());
+        Self::insert("b", Self::past());
+        db!().delete::<TtlEntity>().sweep_expired(10).unwrap();
+
+        let stats = crate::mimic_metrics().unwrap();
+        let m = stats.counters.as_ref().expect("metrics snapshot present");
+        assert_eq!(m.ops.ttl_sweeps, 1);
+        assert_eq!(m.ops.rows_expired, 2);
+
+        let e_ops = m
+            .entities
+            .get(TtlEntity::PATH)
+            .expect("per-entity counters present for TtlEntity");
+        assert_eq!(e_ops.rows_expired, 2);
+    }
+}
@@ -0,0 +1,125 @@
+use mimic::{
+    core::{Value, traits::Path},
+    db::query::LoadQuery,
+    prelude::*,
+};
+use std::collections::BTreeMap;
+use test_design::test::{
+    entity::Entity, sanitize::clamp::SanitizeTest, validate::decimal::ValidateTest,
+};
+
+///
+/// ValuesSuite
+///
+
+pub struct ValuesSuite {}
+
+impl ValuesSuite {
+    pub fn test() {
+        let tests: Vec<(&str, fn())> = vec![
+            ("load_values", Self::load_values),
+            ("insert_values_sanitizes", Self::insert_values_sanitizes),
+            ("insert_values_validates", Self::insert_values_validates),
+            ("update_values", Self::update_values),
+            ("uneditable_fields_fail", Self::uneditable_fields_fail),
+        ];
+
+        for (name, test_fn) in tests {
+            crate::clear_test_data_store();
+
+            println!("Running test: {name}");
+            test_fn();
+        }
+    }
+
+    fn values(pairs: &[(&str, Value)]) -> BTreeMap<String, Value> {
+        pairs
+            .iter()
+            .map(|(k, v)| ((*k).to_string(), v.clone()))
+            .collect()
+    }
+
+    fn load_values() {
+        let e = db!()
+            .insert(Entity {
+                a: 5,
+                ..Default::default()
+            })
+            .unwrap();
+
+        let rows =
+            crate::mimic_query_load_values(Entity::PATH.to_string(), LoadQuery::all()).unwrap();
+
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].key, e.key());
+        assert_eq!(rows[0].values["a"], Value::Int(5));
+        assert_eq!(rows[0].values["id"], Value::Ulid(e.id));
+    }
+
+    fn insert_values_sanitizes() {
+        let row = crate::mimic_query_insert_values(
+            SanitizeTest::PATH.to_string(),
+            Self::values(&[("cint32", Value::Int(50))]),
+        )
+        .unwrap();
+
+        assert_eq!(row.values["cint32"], Value::Int(20));
+        assert_eq!(row.values["cint32_opt"], Value::None);
+
+        let stored = db!().load::<SanitizeTest>().one(row.key).unwrap();
+        assert_eq!(stored.count(), 1);
+    }
+
+    fn insert_values_validates() {
+        let path = ValidateTest::PATH.to_string();
+
+        let err = crate::mimic_query_insert_values(
+            path.clone(),
+            Self::values(&[("lte_ten", Value::Uint(11)), ("gt_fifty", Value::Uint(51))]),
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("lte_ten"));
+        assert_eq!(db!().load::<ValidateTest>().count_all().unwrap(), 0);
+
+        crate::mimic_query_insert_values(
+            path,
+            Self::values(&[("lte_ten", Value::Uint(10)), ("gt_fifty", Value::Uint(51))]),
+        )
+        .unwrap();
+        assert_eq!(db!().load::<ValidateTest>().count_all().unwrap(), 1);
+    }
+
+    fn update_values() {
+        let e = db!().insert(Entity::default()).unwrap();
+
+        let row = crate::mimic_query_update_values(
+            Entity::PATH.to_string(),
+            e.key(),
+            Self::values(&[("a", Value::Int(9))]),
+        )
+        .unwrap();
+        assert_eq!(row.values["a"], Value::Int(9));
+
+        let stored = db!().load::<Entity>().one(e.id).unwrap().entity().unwrap();
+        assert_eq!(stored.a, 9);
+        assert_eq!(stored.created_at, e.created_at);
+    }
+
+    fn uneditable_fields_fail() {
+        let e = db!().insert(Entity::default()).unwrap();
+        let path = Entity::PATH.to_string();
+
+        for field in ["id", "created_at", "nope"] {
+            let values = Self::values(&[(field, Value::Int(1))]);
+
+            assert!(crate::mimic_query_update_values(path.clone(), e.key(), values).is_err());
+        }
+        assert!(
+            crate::mimic_query_insert_values(
+                path,
+                Self::values(&[("a", Value::Text("three".to_string()))])
+            )
+            .is_err()
+        );
+    }
+}
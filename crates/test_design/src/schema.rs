@@ -30,3 +30,39 @@ pub struct TestDataStore {}
     memory_id = 51
 )]
 pub struct TestIndexStore {}
+
+///
+/// TestChangeLogStore
+///
+
+#[store(
+    ident = "TEST_CHANGE_LOG_STORE",
+    ty = "ChangeLog",
+    canister = "Canister",
+    memory_id = 52
+)]
+pub struct TestChangeLogStore {}
+
+///
+/// TestHistoryStore
+///
+
+#[store(
+    ident = "TEST_HISTORY_STORE",
+    ty = "History",
+    canister = "Canister",
+    memory_id = 53
+)]
+pub struct TestHistoryStore {}
+
+///
+/// TestJobStore
+///
+
+#[store(
+    ident = "TEST_JOB_STORE",
+    ty = "Job",
+    canister = "Canister",
+    memory_id = 54
+)]
+pub struct TestJobStore {}
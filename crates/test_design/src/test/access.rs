@@ -0,0 +1,26 @@
+use crate::prelude::*;
+
+///
+/// GuardedEntity
+///
+/// Anyone can read, but the write guard turns every caller away.
+///
+
+#[entity(
+    store = "TestDataStore",
+    pk = "id",
+    access(
+        read = "public",
+        write(guard = "test_design::test::access::deny_writes")
+    ),
+    fields(
+        field(ident = "id", value(item(prim = "Ulid")), default = "Ulid::generate"),
+        field(ident = "name", value(item(prim = "Text"))),
+    )
+)]
+pub struct GuardedEntity {}
+
+/// Guard that rejects every caller.
+pub fn deny_writes() -> Result<(), String> {
+    Err("writes are closed".to_string())
+}
@@ -0,0 +1,18 @@
+use crate::prelude::*;
+
+///
+/// CompressedEntity
+///
+/// Rows use the compact format and are compressed when that makes them smaller.
+///
+
+#[entity(
+    store = "TestDataStore",
+    pk = "id",
+    codec(format = "Compact", compress),
+    fields(
+        field(ident = "id", value(item(prim = "Ulid")), default = "Ulid::generate"),
+        field(ident = "body", value(item(prim = "Text"))),
+    )
+)]
+pub struct CompressedEntity {}
@@ -0,0 +1,16 @@
+use crate::prelude::*;
+
+///
+/// HistoryEntity
+///
+
+#[entity(
+    store = "TestDataStore",
+    pk = "id",
+    history(limit = 3),
+    fields(
+        field(ident = "id", value(item(prim = "Ulid")), default = "Ulid::generate"),
+        field(ident = "balance", value(item(prim = "Nat64"))),
+    )
+)]
+pub struct HistoryEntity {}
@@ -0,0 +1,99 @@
+use crate::prelude::*;
+use mimic::{Error, db::DbSession, db::executor::ExecutorError};
+
+///
+/// HookCounter
+///
+/// Counters maintained by the `HookedEntity` lifecycle hooks.
+///
+
+#[entity(
+    store = "TestDataStore",
+    pk = "id",
+    fields(
+        field(ident = "id", value(item(prim = "Ulid")), default = "Ulid::generate"),
+        field(ident = "inserts", value(item(prim = "Nat32"))),
+        field(ident = "updates", value(item(prim = "Nat32"))),
+        field(ident = "deletes", value(item(prim = "Nat32"))),
+    )
+)]
+pub struct HookCounter;
+
+///
+/// HookedEntity
+///
+
+#[entity(
+    store = "TestDataStore",
+    pk = "id",
+    fields(
+        field(ident = "id", value(item(prim = "Ulid")), default = "Ulid::generate"),
+        field(ident = "counter_id", value(item(rel = "HookCounter"))),
+        field(ident = "name", value(item(prim = "Text"))),
+        field(ident = "name_len", value(item(prim = "Nat32"))),
+        field(ident = "locked", value(item(prim = "Bool"))),
+    ),
+    traits(remove(EntityHooks))
+)]
+pub struct HookedEntity;
+
+impl HookedEntity {
+    fn bump(
+        &self,
+        db: &DbSession<<Self as EntityKind>::Canister>,
+        f: impl FnOnce(&mut HookCounter),
+    ) -> Result<(), Error> {
+        let mut counter = db
+            .load::<HookCounter>()
+            .one(self.counter_id)?
+            .try_entity()?;
+        f(&mut counter);
+        db.update(counter)?;
+
+        Ok(())
+    }
+}
+
+impl EntityHooks for HookedEntity {
+    fn before_save(
+        &mut self,
+        _db: &DbSession<<Self as EntityKind>::Canister>,
+        old: Option<&Self>,
+    ) -> Result<(), Error> {
+        if old.is_some_and(|old| old.locked) {
+            return Err(ExecutorError::hook_rejected(Self::PATH, "entity is locked"))?;
+        }
+        if self.name == "veto" {
+            return Err(ExecutorError::hook_rejected(Self::PATH, "vetoed name"))?;
+        }
+
+        // denormalised field kept in sync on every save
+        self.name_len = u32::try_from(self.name.len()).unwrap_or(u32::MAX);
+
+        Ok(())
+    }
+
+    fn after_save(
+        &self,
+        db: &DbSession<<Self as EntityKind>::Canister>,
+        old: Option<&Self>,
+    ) -> Result<(), Error> {
+        if old.is_some() {
+            self.bump(db, |c| c.updates += 1)
+        } else {
+            self.bump(db, |c| c.inserts += 1)
+        }
+    }
+
+    fn before_delete(&self, _db: &DbSession<<Self as EntityKind>::Canister>) -> Result<(), Error> {
+        if self.locked {
+            return Err(ExecutorError::hook_rejected(Self::PATH, "entity is locked"))?;
+        }
+
+        Ok(())
+    }
+
+    fn after_delete(&self, db: &DbSession<<Self as EntityKind>::Canister>) -> Result<(), Error> {
+        self.bump(db, |c| c.deletes += 1)
+    }
+}
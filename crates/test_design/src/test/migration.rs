@@ -0,0 +1,43 @@
+use crate::prelude::*;
+use mimic::core::{deserialize, serialize};
+
+///
+/// MigratedEntity
+///
+/// Version 1 stored the name in `name`, version 2 renamed it to `full_name`.
+///
+
+#[entity(
+    store = "TestDataStore",
+    pk = "id",
+    version = 2,
+    migration(from = 1, path = "migrate_v1"),
+    index(store = "TestIndexStore", fields = "full_name", unique),
+    fields(
+        field(ident = "id", value(item(prim = "Ulid")), default = "Ulid::generate"),
+        field(ident = "full_name", value(item(prim = "Text"))),
+    )
+)]
+pub struct MigratedEntity {}
+
+///
+/// MigratedEntityV1
+///
+/// The version 1 row layout, only used to read old rows.
+///
+
+#[record(fields(
+    field(ident = "id", value(item(prim = "Ulid"))),
+    field(ident = "name", value(item(prim = "Text"))),
+))]
+pub struct MigratedEntityV1 {}
+
+pub fn migrate_v1(bytes: &[u8]) -> Result<Vec<u8>, mimic::Error> {
+    let old: MigratedEntityV1 = deserialize(bytes)?;
+
+    serialize(&MigratedEntity {
+        id: old.id,
+        full_name: old.name,
+        ..Default::default()
+    })
+}
@@ -1,9 +1,17 @@
+pub mod access;
+pub mod codec;
 pub mod collection;
 pub mod entity;
+pub mod history;
+pub mod hooks;
 pub mod merge;
+pub mod migration;
 pub mod newtype;
+pub mod path;
 pub mod relation;
 pub mod sanitize;
+pub mod soft_delete;
+pub mod ttl;
 pub mod validate;
 pub mod view_into;
 
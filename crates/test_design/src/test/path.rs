@@ -0,0 +1,50 @@
+use crate::prelude::*;
+
+///
+/// PathEntity
+///
+
+#[entity(
+    store = "TestDataStore",
+    pk = "id",
+    index(store = "TestIndexStore", fields = "stats.level"),
+    fields(
+        field(ident = "id", value(item(prim = "Ulid")), default = "Ulid::generate"),
+        field(ident = "stats", value(item(is = "PathStats"))),
+        field(ident = "address", value(opt, item(is = "PathAddress"))),
+        field(ident = "labels", value(item(is = "PathLabels"))),
+        field(ident = "point", value(item(is = "PathPoint"))),
+    )
+)]
+pub struct PathEntity {}
+
+///
+/// PathStats
+///
+
+#[record(fields(
+    field(ident = "level", value(item(prim = "Nat32"))),
+    field(ident = "tags", value(many, item(prim = "Text"))),
+))]
+pub struct PathStats {}
+
+///
+/// PathAddress
+///
+
+#[record(fields(field(ident = "city", value(item(prim = "Text")))))]
+pub struct PathAddress {}
+
+///
+/// PathLabels
+///
+
+#[map(key(prim = "Text"), value(item(prim = "Nat32")))]
+pub struct PathLabels {}
+
+///
+/// PathPoint
+///
+
+#[tuple(value(item(prim = "Int32")), value(item(prim = "Int32")))]
+pub struct PathPoint {}
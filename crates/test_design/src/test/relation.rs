@@ -30,6 +30,27 @@ pub struct HasRelation;
 )]
 pub struct HasManyRelation;
 
+///
+/// HasIndexedRelation
+///
+
+#[entity(
+    store = "TestDataStore",
+    pk = "id",
+    fields(
+        field(ident = "id", value(item(prim = "Ulid")), default = "Ulid::generate"),
+        field(
+            ident = "a_id",
+            value(item(rel = "EntityA", rel_index = "TestIndexStore"))
+        ),
+        field(
+            ident = "backup_a_id",
+            value(opt, item(rel = "EntityA", rel_index = "TestIndexStore"))
+        ),
+    )
+)]
+pub struct HasIndexedRelation;
+
 ///
 /// EntityA
 ///
@@ -0,0 +1,31 @@
+use crate::prelude::*;
+
+///
+/// SoftDeleteEntity
+///
+
+#[entity(
+    store = "TestDataStore",
+    pk = "id",
+    soft_delete,
+    index(store = "TestIndexStore", fields = "name", unique),
+    fields(
+        field(ident = "id", value(item(prim = "Ulid")), default = "Ulid::generate"),
+        field(ident = "name", value(item(prim = "Text"))),
+    )
+)]
+pub struct SoftDeleteEntity {}
+
+///
+/// HasSoftDeleteRelation
+///
+
+#[entity(
+    store = "TestDataStore",
+    pk = "id",
+    fields(
+        field(ident = "id", value(item(prim = "Ulid")), default = "Ulid::generate"),
+        field(ident = "target_id", value(item(rel = "SoftDeleteEntity"))),
+    )
+)]
+pub struct HasSoftDeleteRelation {}
@@ -0,0 +1,34 @@
+use crate::prelude::*;
+
+///
+/// TtlEntity
+///
+
+#[entity(
+    store = "TestDataStore",
+    pk = "id",
+    ttl(field = "expires_at"),
+    fields(
+        field(ident = "id", value(item(prim = "Ulid")), default = "Ulid::generate"),
+        field(ident = "name", value(item(prim = "Text"))),
+        field(ident = "expires_at", value(item(prim = "Timestamp"))),
+    )
+)]
+pub struct TtlEntity {}
+
+///
+/// OptTtlEntity
+///
+/// Rows without an expiry never expire.
+///
+
+#[entity(
+    store = "TestDataStore",
+    pk = "id",
+    ttl(field = "expires_at"),
+    fields(
+        field(ident = "id", value(item(prim = "Ulid")), default = "Ulid::generate"),
+        field(ident = "expires_at", value(opt, item(prim = "Timestamp"))),
+    )
+)]
+pub struct OptTtlEntity {}